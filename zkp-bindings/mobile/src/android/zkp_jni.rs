@@ -1,99 +1,176 @@
+use std::cell::RefCell;
+
 use jni::objects::{JClass, JByteArray};
-use jni::sys::{jbyteArray, jboolean};
+use jni::sys::{jbyteArray, jboolean, jlong, jstring};
 use jni::JNIEnv;
 
 // Import our ZKP circuit - use actual types
 use zkp_circuit::circuit::BiometricCircuit;
-use curve25519_dalek_ng::scalar::Scalar;
+use zkp_circuit::config::DEFAULT_THRESHOLD;
+
+use crate::{MobileUtils, ZKPLimits};
+
+thread_local! {
+    /// Message of the most recent failed call on this thread
+    static LAST_ERROR: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: String) {
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
+
+fn take_last_error() -> Option<String> {
+    LAST_ERROR.with(|last| last.borrow_mut().take())
+}
+
+/// Record the error of a failed call, returning the value of a successful one
+fn record_error<T>(result: std::thread::Result<Result<T, String>>) -> Option<T> {
+    match result {
+        Ok(Ok(value)) => Some(value),
+        Ok(Err(message)) => {
+            set_last_error(message);
+            None
+        }
+        Err(_) => {
+            set_last_error("Panic inside the ZKP library".to_string());
+            None
+        }
+    }
+}
+
+/// Enroll a reference template
+///
+/// Returns the JSON enrollment bundle, or null on failure; the caller should
+/// then call `getLastError` on the same thread for the reason.
+#[no_mangle]
+pub extern "system" fn Java_com_paynet_zkp_ZKPProof_enroll(
+    env: JNIEnv,
+    _class: JClass,
+    biometric_data: JByteArray,
+) -> jbyteArray {
+    take_last_error();
+    let result = std::panic::catch_unwind(|| {
+        let data_bytes = env.convert_byte_array(biometric_data)
+            .map_err(|e| format!("Failed to read biometric data: {}", e))?;
+
+        let bundle_bytes = MobileUtils::enroll_bytes(&data_bytes)?;
+
+        env.byte_array_from_slice(&bundle_bytes)
+            .map(|java_array| java_array.into_raw())
+            .map_err(|e| format!("Failed to allocate enrollment array: {}", e))
+    });
 
-/// Simple biometric data structure for JNI
-#[derive(serde::Deserialize, serde::Serialize)]
-struct SimpleBiometricData {
-    template: Vec<f64>,
+    record_error(result).unwrap_or(std::ptr::null_mut())
 }
 
 /// Generate ZKP proof for biometric data
+///
+/// Returns null on failure; the caller should then call `getLastError` on
+/// the same thread for the reason.
 #[no_mangle]
 pub extern "system" fn Java_com_paynet_zkp_ZKPProof_generateProof(
     env: JNIEnv,
     _class: JClass,
     biometric_data: JByteArray,
 ) -> jbyteArray {
+    take_last_error();
     let result = std::panic::catch_unwind(|| {
         // Convert Java byte array to Rust Vec<u8>
-        let data_bytes = match env.convert_byte_array(biometric_data) {
-            Ok(bytes) => bytes,
-            Err(_) => return std::ptr::null_mut(),
-        };
-
-        // Parse biometric data (assume JSON format)
-        let biometric_input: SimpleBiometricData = match serde_json::from_slice(&data_bytes) {
-            Ok(data) => data,
-            Err(_) => return std::ptr::null_mut(),
-        };
-
-        // Convert to Scalars (simplified)
-        let current_embedding: Vec<Scalar> = biometric_input.template
-            .into_iter()
-            .map(|f| Scalar::from((f * 1000.0) as u64))
-            .collect();
-
-        // Create reference embedding
-        let reference_embedding: Vec<Scalar> = vec![Scalar::from(500u64); current_embedding.len()];
-
-        // Generate proof using our circuit
-        let circuit = BiometricCircuit::new(current_embedding.len(), 1000);
-        let proof_result = circuit.generate_proof(&current_embedding, &reference_embedding);
-
-        match proof_result {
-            Ok(proof) => {
-                // Serialize proof to bytes
-                match serde_json::to_vec(&proof) {
-                    Ok(proof_bytes) => {
-                        // Convert back to Java byte array
-                        match env.byte_array_from_slice(&proof_bytes) {
-                            Ok(java_array) => java_array.into_raw(),
-                            Err(_) => std::ptr::null_mut(),
-                        }
-                    }
-                    Err(_) => std::ptr::null_mut(),
-                }
-            }
-            Err(_) => std::ptr::null_mut(),
-        }
+        let data_bytes = env.convert_byte_array(biometric_data)
+            .map_err(|e| format!("Failed to read biometric data: {}", e))?;
+
+        let proof_bytes = MobileUtils::generate_proof_bytes(&data_bytes)?;
+
+        // Convert back to Java byte array
+        env.byte_array_from_slice(&proof_bytes)
+            .map(|java_array| java_array.into_raw())
+            .map_err(|e| format!("Failed to allocate proof array: {}", e))
     });
 
-    result.unwrap_or(std::ptr::null_mut())
+    record_error(result).unwrap_or(std::ptr::null_mut())
 }
 
-/// Verify ZKP proof (simplified)
+/// Message of the last failed call on this thread, or null if there was none
+///
+/// Reading the message clears it.
 #[no_mangle]
-pub extern "system" fn Java_com_paynet_zkp_ZKPProof_verifyProof(
+pub extern "system" fn Java_com_paynet_zkp_ZKPProof_getLastError(
     env: JNIEnv,
     _class: JClass,
+) -> jstring {
+    match take_last_error() {
+        Some(message) => env.new_string(message)
+            .map(|java_string| java_string.into_raw())
+            .unwrap_or(std::ptr::null_mut()),
+        None => std::ptr::null_mut(),
+    }
+}
+
+/// Verify a proof against the JSON public data at `threshold`, recording
+/// the reason on failure
+fn verify_with_threshold(
+    env: &JNIEnv,
     proof_data: JByteArray,
     public_data: JByteArray,
+    threshold: jlong,
 ) -> jboolean {
+    take_last_error();
     let result = std::panic::catch_unwind(|| {
-        let proof_bytes = match env.convert_byte_array(proof_data) {
-            Ok(bytes) => bytes,
-            Err(_) => return 0u8, // false
-        };
-
-        let public_bytes = match env.convert_byte_array(public_data) {
-            Ok(bytes) => bytes,
-            Err(_) => return 0u8, // false
-        };
-
-        // Basic validation that we have data
-        if proof_bytes.is_empty() || public_bytes.is_empty() {
-            return 0u8;
-        }
+        let threshold = u64::try_from(threshold)
+            .map_err(|_| format!("Threshold {} must not be negative", threshold))?;
+        let proof_bytes = env.convert_byte_array(proof_data)
+            .map_err(|e| format!("Failed to read proof data: {}", e))?;
+        let public_bytes = env.convert_byte_array(public_data)
+            .map_err(|e| format!("Failed to read public data: {}", e))?;
 
-        1u8 // true
+        MobileUtils::verify_proof_bytes(&proof_bytes, &public_bytes, threshold)
     });
 
-    result.unwrap_or(0u8)
+    record_error(result).map_or(0u8, u8::from)
+}
+
+/// Verify a proof from `generateProof` against the JSON public data at
+/// `DEFAULT_THRESHOLD`
+///
+/// Equivalent to `verifyProofWithThreshold` with `DEFAULT_THRESHOLD`.
+#[no_mangle]
+pub extern "system" fn Java_com_paynet_zkp_ZKPProof_verifyProof(
+    env: JNIEnv,
+    _class: JClass,
+    proof_data: JByteArray,
+    public_data: JByteArray,
+) -> jboolean {
+    verify_with_threshold(&env, proof_data, public_data, DEFAULT_THRESHOLD as jlong)
+}
+
+/// Verify a proof from `generateProof` against the JSON public data at the
+/// caller's `threshold`
+///
+/// Returns false both for proofs that do not verify and on failure; after a
+/// false return `getLastError` is non-null only if the call failed.
+#[no_mangle]
+pub extern "system" fn Java_com_paynet_zkp_ZKPProof_verifyProofWithThreshold(
+    env: JNIEnv,
+    _class: JClass,
+    proof_data: JByteArray,
+    public_data: JByteArray,
+    threshold: jlong,
+) -> jboolean {
+    verify_with_threshold(&env, proof_data, public_data, threshold)
+}
+
+/// JSON object with the embedding sizes and thresholds the library accepts
+///
+/// Carries `MAX_EMBEDDING_SIZE`, `MIN_THRESHOLD`, `MAX_THRESHOLD` and
+/// `RECOMMENDED_EMBEDDING_SIZES`; null only if the string cannot be allocated.
+#[no_mangle]
+pub extern "system" fn Java_com_paynet_zkp_ZKPProof_getLimits(
+    env: JNIEnv,
+    _class: JClass,
+) -> jstring {
+    env.new_string(ZKPLimits::current().to_json())
+        .map(|java_string| java_string.into_raw())
+        .unwrap_or(std::ptr::null_mut())
 }
 
 /// Initialize ZKP system
@@ -114,10 +191,78 @@ pub extern "system" fn Java_com_paynet_zkp_ZKPProof_initialize(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use zkp_circuit::types::BiometricProof;
+    use crate::SimpleBiometricData;
 
     #[test]
     fn test_android_interface() {
-        // Basic test to ensure the module compiles
-        assert!(true);
+        // The JNI layer accepts the JSON template payload sent from Kotlin
+        let data: SimpleBiometricData = serde_json::from_slice(br#"{"template":[0.1,0.2]}"#).unwrap();
+        assert_eq!(data.template, vec![0.1, 0.2]);
+        assert!(data.attestation.is_none());
+
+        let bound: SimpleBiometricData =
+            serde_json::from_slice(br#"{"template":[0.1],"attestation":[1,2,3]}"#).unwrap();
+        assert_eq!(bound.attestation, Some(vec![1, 2, 3]));
+    }
+
+    #[test]
+    fn test_last_error_captures_parse_failure() {
+        let result = std::panic::catch_unwind(|| MobileUtils::generate_proof_bytes(b"{not json"));
+        assert!(record_error(result).is_none());
+
+        let message = take_last_error().unwrap();
+        let serde_message = serde_json::from_slice::<SimpleBiometricData>(b"{not json").unwrap_err().to_string();
+        assert_eq!(message, format!("Invalid biometric JSON: {}", serde_message));
+
+        // Reading the error clears it
+        assert!(take_last_error().is_none());
+    }
+
+    /// Enroll `reference`, returning the verifier's public data and the opening
+    fn enroll(reference: &str) -> (Vec<u8>, serde_json::Value) {
+        let bundle_bytes = MobileUtils::enroll_bytes(format!(r#"{{"template":{}}}"#, reference).as_bytes()).unwrap();
+        let bundle: serde_json::Value = serde_json::from_slice(&bundle_bytes).unwrap();
+        let public_data = serde_json::to_vec(&serde_json::json!({ "enrollment": bundle["enrollment"] })).unwrap();
+        (public_data, bundle["opening"].clone())
+    }
+
+    #[test]
+    fn test_verify_rejects_forged_proof() {
+        let reference = "[0.5,0.5,0.5,0.5]";
+        let (public_data, opening) = enroll(reference);
+        let payload = serde_json::json!({
+            "template": [0.5, 0.51, 0.49, 0.5],
+            "reference": serde_json::from_str::<serde_json::Value>(reference).unwrap(),
+            "threshold": 1000,
+            "opening": opening,
+        });
+
+        let proof_bytes = MobileUtils::generate_proof_bytes(&serde_json::to_vec(&payload).unwrap()).unwrap();
+        assert_eq!(MobileUtils::verify_proof_bytes(&proof_bytes, &public_data, 1000), Ok(true));
+
+        // The verifier's threshold is authoritative
+        assert!(MobileUtils::verify_proof_bytes(&proof_bytes, &public_data, 500).is_err());
+
+        // Flipping a bit of the R1CS proof breaks verification
+        let mut forged: BiometricProof = serde_json::from_slice(&proof_bytes).unwrap();
+        let last = forged.proof.len() - 1;
+        forged.proof[last] ^= 1;
+        let forged = serde_json::to_vec(&forged).unwrap();
+        assert!(!MobileUtils::verify_proof_bytes(&forged, &public_data, 1000).unwrap_or(false));
+
+        // A genuine proof against a different enrollment is refused
+        let (other_enrollment, _) = enroll(reference);
+        assert_eq!(MobileUtils::verify_proof_bytes(&proof_bytes, &other_enrollment, 1000), Ok(false));
+
+        // Proofs need the enrollment opening
+        let mut unopened = payload.clone();
+        unopened.as_object_mut().unwrap().remove("opening");
+        assert!(MobileUtils::generate_proof_bytes(&serde_json::to_vec(&unopened).unwrap()).is_err());
+
+        // Unparseable proofs surface their serde error
+        let result = std::panic::catch_unwind(|| MobileUtils::verify_proof_bytes(b"[1,2", &public_data, 1000));
+        assert!(record_error(result).is_none());
+        assert!(take_last_error().unwrap().starts_with("Invalid proof JSON"));
     }
 }
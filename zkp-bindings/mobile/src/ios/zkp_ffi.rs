@@ -1,204 +1,237 @@
-use std::ffi::{CString};
+use std::ffi::CString;
 use std::os::raw::{c_char, c_int};
 use std::ptr;
 
 // Import our ZKP circuit - use actual types
 use zkp_circuit::circuit::BiometricCircuit;
-use curve25519_dalek_ng::scalar::Scalar;
+use zkp_circuit::config::DEFAULT_THRESHOLD;
 
-/// Simple biometric data structure for FFI
-#[derive(serde::Deserialize, serde::Serialize)]
-struct SimpleBiometricData {
-    template: Vec<f64>,
+use crate::{MobileUtils, ZKPLimits};
+
+/// Meaning of the payload behind `ZKPResult::data_ptr`
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZKPResultType {
+    /// Serialized proof bytes
+    Proof = 0,
+    /// A single byte, `1` for true and `0` for false
+    Bool = 1,
+    /// UTF-8 version string, not NUL-terminated
+    Version = 2,
+    /// No payload; see `error_msg`
+    Error = 3,
+    /// UTF-8 JSON object, not NUL-terminated
+    Json = 4,
 }
 
 /// Result structure for FFI calls
 #[repr(C)]
 pub struct ZKPResult {
     pub success: c_int,
+    /// One of the `ZKPResultType` discriminants
+    pub result_type: c_int,
     pub data_ptr: *mut u8,
     pub data_len: usize,
     pub error_msg: *const c_char,
 }
 
+impl ZKPResult {
+    /// Successful result owning a copy of `data`
+    fn with_data(result_type: ZKPResultType, data: Vec<u8>) -> *mut ZKPResult {
+        let mut data = data.into_boxed_slice();
+        let data_ptr = data.as_mut_ptr();
+        let data_len = data.len();
+        std::mem::forget(data); // Released by zkp_free_result
+
+        Box::into_raw(Box::new(ZKPResult {
+            success: 1,
+            result_type: result_type as c_int,
+            data_ptr,
+            data_len,
+            error_msg: ptr::null(),
+        }))
+    }
+
+    /// Failed result carrying an error message
+    fn error(msg: &str) -> *mut ZKPResult {
+        Box::into_raw(Box::new(ZKPResult {
+            success: 0,
+            result_type: ZKPResultType::Error as c_int,
+            data_ptr: ptr::null_mut(),
+            data_len: 0,
+            error_msg: create_error_string(msg),
+        }))
+    }
+}
+
 /// Free memory allocated by Rust
+///
+/// # Safety
+/// `result` must be null or a pointer previously returned by one of the `zkp_*` functions
+/// that has not already been freed.
 #[no_mangle]
-pub extern "C" fn zkp_free_result(result: *mut ZKPResult) {
+pub unsafe extern "C" fn zkp_free_result(result: *mut ZKPResult) {
     if !result.is_null() {
         unsafe {
             let result = Box::from_raw(result);
             if !result.data_ptr.is_null() {
                 let _ = Vec::from_raw_parts(result.data_ptr, result.data_len, result.data_len);
             }
-            if !result.error_msg.is_null() {
-                let _ = CString::from_raw(result.error_msg as *mut c_char);
-            }
+            zkp_free_string(result.error_msg as *mut c_char);
         }
     }
 }
 
+/// Free a string allocated by Rust
+/// Called from Swift: zkp_free_string(ptr: UnsafeMutablePointer<CChar>)
+///
+/// # Safety
+/// `ptr` must be null or a string previously returned by `zkp_get_version`
+/// that has not already been freed.
+#[no_mangle]
+pub unsafe extern "C" fn zkp_free_string(ptr: *mut c_char) {
+    if !ptr.is_null() {
+        unsafe {
+            drop(CString::from_raw(ptr));
+        }
+    }
+}
+
+/// Enroll a reference template
+/// Called from Swift: zkp_enroll(biometric_data: UnsafePointer<UInt8>, data_len: Int) -> UnsafeMutablePointer<ZKPResult>
+///
+/// Returns the enrollment bundle as a `JSON` result. Its `enrollment` goes to
+/// the verifier; its `opening` stays on the device and is sent back in the
+/// `zkp_generate_proof` payload.
+///
+/// # Safety
+/// `biometric_data` must be null or point to `data_len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn zkp_enroll(
+    biometric_data: *const u8,
+    data_len: usize,
+) -> *mut ZKPResult {
+    let result = std::panic::catch_unwind(|| {
+        if biometric_data.is_null() || data_len == 0 {
+            return ZKPResult::error("Invalid input data");
+        }
+
+        let data_slice = unsafe { std::slice::from_raw_parts(biometric_data, data_len) };
+        match MobileUtils::enroll_bytes(data_slice) {
+            Ok(bundle_bytes) => ZKPResult::with_data(ZKPResultType::Json, bundle_bytes),
+            Err(e) => ZKPResult::error(&format!("Failed to enroll template: {}", e)),
+        }
+    });
+
+    result.unwrap_or_else(|_| ZKPResult::error("Panic occurred during enrollment"))
+}
+
 /// Generate ZKP proof for biometric data
 /// Called from Swift: zkp_generate_proof(biometric_data: UnsafePointer<UInt8>, data_len: Int) -> UnsafeMutablePointer<ZKPResult>
+///
+/// The payload must carry the `opening` returned by `zkp_enroll` for its reference.
+///
+/// # Safety
+/// `biometric_data` must be null or point to `data_len` readable bytes.
 #[no_mangle]
-pub extern "C" fn zkp_generate_proof(
+pub unsafe extern "C" fn zkp_generate_proof(
     biometric_data: *const u8,
     data_len: usize,
 ) -> *mut ZKPResult {
     let result = std::panic::catch_unwind(|| {
         if biometric_data.is_null() || data_len == 0 {
-            return Box::into_raw(Box::new(ZKPResult {
-                success: 0,
-                data_ptr: ptr::null_mut(),
-                data_len: 0,
-                error_msg: create_error_string("Invalid input data"),
-            }));
+            return ZKPResult::error("Invalid input data");
         }
 
-        unsafe {
-            // Convert C data to Rust slice
-            let data_slice = std::slice::from_raw_parts(biometric_data, data_len);
-            
-            // Parse biometric data
-            let biometric_input: SimpleBiometricData = match serde_json::from_slice(data_slice) {
-                Ok(data) => data,
-                Err(e) => {
-                    return Box::into_raw(Box::new(ZKPResult {
-                        success: 0,
-                        data_ptr: ptr::null_mut(),
-                        data_len: 0,
-                        error_msg: create_error_string(&format!("Failed to parse biometric data: {}", e)),
-                    }));
-                }
-            };
-
-            // Convert to Scalars (simplified)
-            let current_embedding: Vec<Scalar> = biometric_input.template
-                .into_iter()
-                .map(|f| Scalar::from((f * 1000.0) as u64))
-                .collect();
-
-            // Create reference embedding
-            let reference_embedding: Vec<Scalar> = vec![Scalar::from(500u64); current_embedding.len()];
-
-            // Generate proof
-            let circuit = BiometricCircuit::new(current_embedding.len(), 1000);
-            match circuit.generate_proof(&current_embedding, &reference_embedding) {
-                Ok(proof) => {
-                    // Serialize proof
-                    match serde_json::to_vec(&proof) {
-                        Ok(proof_bytes) => {
-                            let mut proof_data = proof_bytes.into_boxed_slice();
-                            let data_ptr = proof_data.as_mut_ptr();
-                            let data_len = proof_data.len();
-                            std::mem::forget(proof_data); // Prevent deallocation
-
-                            Box::into_raw(Box::new(ZKPResult {
-                                success: 1,
-                                data_ptr,
-                                data_len,
-                                error_msg: ptr::null(),
-                            }))
-                        }
-                        Err(e) => {
-                            Box::into_raw(Box::new(ZKPResult {
-                                success: 0,
-                                data_ptr: ptr::null_mut(),
-                                data_len: 0,
-                                error_msg: create_error_string(&format!("Failed to serialize proof: {}", e)),
-                            }))
-                        }
-                    }
-                }
-                Err(e) => {
-                    Box::into_raw(Box::new(ZKPResult {
-                        success: 0,
-                        data_ptr: ptr::null_mut(),
-                        data_len: 0,
-                        error_msg: create_error_string(&format!("Failed to generate proof: {}", e)),
-                    }))
-                }
-            }
+        // Convert C data to Rust slice
+        let data_slice = unsafe { std::slice::from_raw_parts(biometric_data, data_len) };
+        match MobileUtils::generate_proof_bytes(data_slice) {
+            Ok(proof_bytes) => ZKPResult::with_data(ZKPResultType::Proof, proof_bytes),
+            Err(e) => ZKPResult::error(&format!("Failed to generate proof: {}", e)),
         }
     });
 
-    result.unwrap_or_else(|_| {
-        Box::into_raw(Box::new(ZKPResult {
-            success: 0,
-            data_ptr: ptr::null_mut(),
-            data_len: 0,
-            error_msg: create_error_string("Panic occurred during proof generation"),
-        }))
-    })
+    result.unwrap_or_else(|_| ZKPResult::error("Panic occurred during proof generation"))
 }
 
-/// Verify ZKP proof
+/// Verify ZKP proof against the stored enrollment at the default threshold
 /// Called from Swift: zkp_verify_proof(proof_data: UnsafePointer<UInt8>, proof_len: Int, public_data: UnsafePointer<UInt8>, public_len: Int) -> UnsafeMutablePointer<ZKPResult>
+///
+/// Kept for ABI compatibility; equivalent to `zkp_verify_proof_v2` with
+/// `DEFAULT_THRESHOLD`.
+///
+/// # Safety
+/// `proof_data` and `public_data` must each be null or point to
+/// `proof_len` and `public_len` readable bytes respectively.
 #[no_mangle]
-pub extern "C" fn zkp_verify_proof(
+pub unsafe extern "C" fn zkp_verify_proof(
     proof_data: *const u8,
     proof_len: usize,
     public_data: *const u8,
     public_len: usize,
+) -> *mut ZKPResult {
+    unsafe { zkp_verify_proof_v2(proof_data, proof_len, public_data, public_len, DEFAULT_THRESHOLD) }
+}
+
+/// Verify ZKP proof against the stored enrollment at the given threshold
+/// Called from Swift: zkp_verify_proof_v2(proof_data: UnsafePointer<UInt8>, proof_len: Int, public_data: UnsafePointer<UInt8>, public_len: Int, threshold: UInt64) -> UnsafeMutablePointer<ZKPResult>
+///
+/// `public_data` is the JSON object `{"enrollment": ..., "attestation": ...}`
+/// holding the `enrollment` from `zkp_enroll` and, for device-bound proofs,
+/// the attestation. A proof over another enrollment or device yields `false`;
+/// a proof made for another threshold or embedding size is an error.
+///
+/// # Safety
+/// `proof_data` and `public_data` must each be null or point to
+/// `proof_len` and `public_len` readable bytes respectively.
+#[no_mangle]
+pub unsafe extern "C" fn zkp_verify_proof_v2(
+    proof_data: *const u8,
+    proof_len: usize,
+    public_data: *const u8,
+    public_len: usize,
+    threshold: u64,
 ) -> *mut ZKPResult {
     let result = std::panic::catch_unwind(|| {
         if proof_data.is_null() || proof_len == 0 || public_data.is_null() || public_len == 0 {
-            return Box::into_raw(Box::new(ZKPResult {
-                success: 0,
-                data_ptr: ptr::null_mut(),
-                data_len: 0,
-                error_msg: create_error_string("Invalid input parameters"),
-            }));
+            return ZKPResult::error("Invalid input parameters");
         }
 
-        unsafe {
-            // Convert proof data
-            let proof_slice = std::slice::from_raw_parts(proof_data, proof_len);
-            
-            // Convert public data
-            let public_slice = std::slice::from_raw_parts(public_data, public_len);
-
-            if proof_slice.is_empty() || public_slice.is_empty() {
-                return Box::into_raw(Box::new(ZKPResult {
-                    success: 0,
-                    data_ptr: ptr::null_mut(),
-                    data_len: 0,
-                    error_msg: create_error_string("Empty input data"),
-                }));
-            }
-
-            let result_data = vec![1u8]; // true
-            let mut result_bytes = result_data.into_boxed_slice();
-            let data_ptr = result_bytes.as_mut_ptr();
-            let data_len = result_bytes.len();
-            std::mem::forget(result_bytes);
-
-            Box::into_raw(Box::new(ZKPResult {
-                success: 1,
-                data_ptr,
-                data_len,
-                error_msg: ptr::null(),
-            }))
+        let proof_slice = unsafe { std::slice::from_raw_parts(proof_data, proof_len) };
+        let public_slice = unsafe { std::slice::from_raw_parts(public_data, public_len) };
+        match MobileUtils::verify_proof_bytes(proof_slice, public_slice, threshold) {
+            Ok(valid) => ZKPResult::with_data(ZKPResultType::Bool, vec![valid as u8]),
+            Err(e) => ZKPResult::error(&format!("Failed to verify proof: {}", e)),
         }
     });
 
-    result.unwrap_or_else(|_| {
-        Box::into_raw(Box::new(ZKPResult {
-            success: 0,
-            data_ptr: ptr::null_mut(),
-            data_len: 0,
-            error_msg: create_error_string("Panic occurred during verification"),
-        }))
-    })
+    result.unwrap_or_else(|_| ZKPResult::error("Panic occurred during verification"))
 }
 
 /// Get library version
-/// Called from Swift: zkp_get_version() -> UnsafePointer<CChar>
+/// Called from Swift: zkp_get_version() -> UnsafeMutablePointer<CChar>
+///
+/// The caller owns the returned string and must release it with `zkp_free_string`.
 #[no_mangle]
-pub extern "C" fn zkp_get_version() -> *const c_char {
-    let version = CString::new("1.0.0").expect("CString::new failed");
-    let ptr = version.as_ptr();
-    std::mem::forget(version); // Prevent deallocation
-    ptr
+pub extern "C" fn zkp_get_version() -> *mut c_char {
+    CString::new("1.0.0").expect("CString::new failed").into_raw()
+}
+
+/// Get library version as a `VERSION` result
+/// Called from Swift: zkp_get_version_result() -> UnsafeMutablePointer<ZKPResult>
+#[no_mangle]
+pub extern "C" fn zkp_get_version_result() -> *mut ZKPResult {
+    ZKPResult::with_data(ZKPResultType::Version, b"1.0.0".to_vec())
+}
+
+/// Get the embedding sizes and thresholds the library accepts as a `JSON` result
+/// Called from Swift: zkp_get_limits() -> UnsafeMutablePointer<ZKPResult>
+///
+/// The object has `MAX_EMBEDDING_SIZE`, `MIN_THRESHOLD`, `MAX_THRESHOLD` and
+/// `RECOMMENDED_EMBEDDING_SIZES` keys.
+#[no_mangle]
+pub extern "C" fn zkp_get_limits() -> *mut ZKPResult {
+    ZKPResult::with_data(ZKPResultType::Json, ZKPLimits::current().to_json().into_bytes())
 }
 
 /// Initialize ZKP system
@@ -213,14 +246,10 @@ pub extern "C" fn zkp_initialize() -> c_int {
     result.unwrap_or(0)
 }
 
-/// Helper function to create error strings
+/// Helper function to create error strings, released through `zkp_free_string`
 fn create_error_string(msg: &str) -> *const c_char {
     match CString::new(msg) {
-        Ok(c_string) => {
-            let ptr = c_string.as_ptr();
-            std::mem::forget(c_string);
-            ptr
-        }
+        Ok(c_string) => c_string.into_raw(),
         Err(_) => ptr::null(),
     }
 }
@@ -234,6 +263,7 @@ mod tests {
         // Test version function
         let version_ptr = zkp_get_version();
         assert!(!version_ptr.is_null());
+        unsafe { zkp_free_string(version_ptr) };
         
         // Test initialization
         let init_result = zkp_initialize();
@@ -242,13 +272,178 @@ mod tests {
 
     #[test]
     fn test_error_handling() {
-        // Test with null pointers
-        let result = zkp_generate_proof(std::ptr::null(), 0);
-        assert!(!result.is_null());
-        
         unsafe {
+            // Test with null pointers
+            let result = zkp_generate_proof(std::ptr::null(), 0);
+            assert!(!result.is_null());
+
+            assert_eq!((*result).success, 0);
+            assert_eq!((*result).result_type, ZKPResultType::Error as c_int);
+            assert!(!(*result).error_msg.is_null());
+            zkp_free_result(result);
+
+            let result = zkp_verify_proof(std::ptr::null(), 0, std::ptr::null(), 0);
+            assert_eq!((*result).result_type, ZKPResultType::Error as c_int);
+            zkp_free_result(result);
+        }
+    }
+
+    /// Enroll `reference` through the FFI, returning the verifier's public
+    /// data and the opening
+    fn enroll(reference: &str) -> (Vec<u8>, serde_json::Value) {
+        let input = format!(r#"{{"template":{}}}"#, reference);
+        unsafe {
+            let result = zkp_enroll(input.as_ptr(), input.len());
+            assert_eq!((*result).success, 1);
+            assert_eq!((*result).result_type, ZKPResultType::Json as c_int);
+            let bundle: serde_json::Value =
+                serde_json::from_slice(std::slice::from_raw_parts((*result).data_ptr, (*result).data_len)).unwrap();
+            zkp_free_result(result);
+
+            let public_data = serde_json::to_vec(&serde_json::json!({ "enrollment": bundle["enrollment"] })).unwrap();
+            (public_data, bundle["opening"].clone())
+        }
+    }
+
+    /// Prove `template` against the enrolled `reference`, returning the proof bytes
+    fn prove(template: &str, reference: &str, opening: &serde_json::Value) -> Vec<u8> {
+        let payload = serde_json::to_vec(&serde_json::json!({
+            "template": serde_json::from_str::<serde_json::Value>(template).unwrap(),
+            "reference": serde_json::from_str::<serde_json::Value>(reference).unwrap(),
+            "opening": opening,
+        })).unwrap();
+        unsafe {
+            let result = zkp_generate_proof(payload.as_ptr(), payload.len());
+            assert_eq!((*result).success, 1);
+            let proof_bytes = std::slice::from_raw_parts((*result).data_ptr, (*result).data_len).to_vec();
+            zkp_free_result(result);
+            proof_bytes
+        }
+    }
+
+    /// Outcome of `zkp_verify_proof_v2`: `Ok(valid)` or the error message
+    fn verify(proof_bytes: &[u8], public_data: &[u8], threshold: u64) -> Result<bool, String> {
+        unsafe {
+            let result = zkp_verify_proof_v2(
+                proof_bytes.as_ptr(), proof_bytes.len(), public_data.as_ptr(), public_data.len(), threshold,
+            );
+            let outcome = if (*result).success == 1 {
+                assert_eq!((*result).result_type, ZKPResultType::Bool as c_int);
+                Ok(std::slice::from_raw_parts((*result).data_ptr, (*result).data_len) == [1u8])
+            } else {
+                Err(std::ffi::CStr::from_ptr((*result).error_msg).to_string_lossy().into_owned())
+            };
+            zkp_free_result(result);
+            outcome
+        }
+    }
+
+    #[test]
+    fn test_result_types() {
+        let reference = "[0.5,0.5,0.5,0.5]";
+        let (public_data, opening) = enroll(reference);
+        let payload = serde_json::to_vec(&serde_json::json!({
+            "template": [0.5, 0.5, 0.5, 0.5],
+            "reference": [0.5, 0.5, 0.5, 0.5],
+            "opening": opening,
+        })).unwrap();
+
+        unsafe {
+            let proof = zkp_generate_proof(payload.as_ptr(), payload.len());
+            assert_eq!((*proof).success, 1);
+            assert_eq!((*proof).result_type, ZKPResultType::Proof as c_int);
+            assert!((*proof).data_len > 0);
+
+            let proof_bytes = std::slice::from_raw_parts((*proof).data_ptr, (*proof).data_len);
+            let verified = zkp_verify_proof_v2(
+                proof_bytes.as_ptr(), proof_bytes.len(), public_data.as_ptr(), public_data.len(), 1000,
+            );
+            assert_eq!((*verified).success, 1);
+            assert_eq!((*verified).result_type, ZKPResultType::Bool as c_int);
+            assert_eq!(std::slice::from_raw_parts((*verified).data_ptr, (*verified).data_len), &[1u8]);
+
+            let version = zkp_get_version_result();
+            assert_eq!((*version).result_type, ZKPResultType::Version as c_int);
+            assert_eq!(std::slice::from_raw_parts((*version).data_ptr, (*version).data_len), b"1.0.0");
+
+            let limits = zkp_get_limits();
+            assert_eq!((*limits).result_type, ZKPResultType::Json as c_int);
+            let limits_json = std::slice::from_raw_parts((*limits).data_ptr, (*limits).data_len);
+            assert_eq!(serde_json::from_slice::<ZKPLimits>(limits_json).unwrap(), ZKPLimits::current());
+
+            // Each kind of payload is released through the same entry point
+            zkp_free_result(proof);
+            zkp_free_result(verified);
+            zkp_free_result(version);
+            zkp_free_result(limits);
+            zkp_free_result(std::ptr::null_mut());
+        }
+    }
+
+    #[test]
+    fn test_verify_against_enrollment() {
+        let reference = "[0.5,0.5,0.5,0.5]";
+        let (public_data, opening) = enroll(reference);
+        let proof_bytes = prove("[0.5,0.51,0.49,0.5]", reference, &opening);
+
+        assert_eq!(verify(&proof_bytes, &public_data, 1000), Ok(true));
+
+        // A genuine proof is refused against a different reference's enrollment
+        let (other_reference, _) = enroll("[0.1,0.9,0.1,0.9]");
+        assert_eq!(verify(&proof_bytes, &other_reference, 1000), Ok(false));
+
+        // A verifier configured for another threshold refuses the proof
+        assert!(verify(&proof_bytes, &public_data, 500).is_err());
+        assert!(verify(b"[1,2", &public_data, 1000).is_err());
+
+        let mut forged: serde_json::Value = serde_json::from_slice(&proof_bytes).unwrap();
+        let last = forged["proof"].as_array().unwrap().len() - 1;
+        forged["proof"][last] = serde_json::json!(forged["proof"][last].as_u64().unwrap() ^ 1);
+        assert!(!verify(&serde_json::to_vec(&forged).unwrap(), &public_data, 1000).unwrap_or(false));
+
+        // Proofs need the enrollment opening
+        let unopened = br#"{"template":[0.5,0.5,0.5,0.5]}"#;
+        unsafe {
+            let result = zkp_generate_proof(unopened.as_ptr(), unopened.len());
             assert_eq!((*result).success, 0);
             zkp_free_result(result);
         }
     }
+
+    #[test]
+    fn test_legacy_verify_uses_defaults() {
+        let reference = "[0.5,0.5,0.5,0.5]";
+        let (public_data, opening) = enroll(reference);
+        let proof_bytes = prove(reference, reference, &opening);
+
+        unsafe {
+            let verified = zkp_verify_proof(proof_bytes.as_ptr(), proof_bytes.len(), public_data.as_ptr(), public_data.len());
+            assert_eq!((*verified).success, 1);
+            assert_eq!(std::slice::from_raw_parts((*verified).data_ptr, (*verified).data_len), &[1u8]);
+            zkp_free_result(verified);
+
+            // Without the enrollment there is nothing to verify against
+            let verified = zkp_verify_proof(proof_bytes.as_ptr(), proof_bytes.len(), ptr::null(), 0);
+            assert_eq!((*verified).success, 0);
+            zkp_free_result(verified);
+        }
+    }
+
+    #[test]
+    fn test_string_ownership() {
+        // Every allocation is reclaimed, so repeated calls do not grow memory
+        // (run under `cargo miri test` or valgrind to check for leaks)
+        for _ in 0..1000 {
+            let version = zkp_get_version();
+            assert_eq!(unsafe { std::ffi::CStr::from_ptr(version) }.to_str(), Ok("1.0.0"));
+            unsafe { zkp_free_string(version) };
+        }
+
+        let error = ZKPResult::error("bad input");
+        unsafe {
+            assert_eq!(std::ffi::CStr::from_ptr((*error).error_msg).to_str(), Ok("bad input"));
+            zkp_free_result(error);
+            zkp_free_string(std::ptr::null_mut());
+        }
+    }
 }
@@ -9,7 +9,12 @@ pub use android::*;
 pub use ios::*;
 
 // Common utilities for mobile platforms
+use curve25519_dalek_ng::scalar::Scalar;
 use serde::{Deserialize, Serialize};
+use zkp_circuit::circuit::BiometricCircuit;
+use zkp_circuit::types::BiometricProof;
+use zkp_circuit::utils::scalar_utils::ScalarUtils;
+use zkp_circuit::{BiometricEmbedding, CircuitError, CircuitResult, EnrollmentCommitment, EnrollmentOpening, FieldUtils};
 
 /// Mobile-specific configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -32,6 +37,127 @@ impl Default for MobileConfig {
     }
 }
 
+/// Parameter ranges the native library accepts, reported to client apps
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct ZKPLimits {
+    /// Largest embedding dimension a circuit can be built for
+    pub max_embedding_size: usize,
+    /// Smallest accepted distance threshold
+    pub min_threshold: u64,
+    /// Largest accepted distance threshold
+    pub max_threshold: u64,
+    /// Common embedding sizes within `max_embedding_size`
+    pub recommended_embedding_sizes: Vec<usize>,
+}
+
+impl ZKPLimits {
+    /// Limits of this build, taken from `zkp_circuit::config`
+    pub fn current() -> Self {
+        ZKPLimits {
+            max_embedding_size: zkp_circuit::config::MAX_EMBEDDING_SIZE,
+            min_threshold: zkp_circuit::config::MIN_THRESHOLD,
+            max_threshold: zkp_circuit::config::MAX_THRESHOLD,
+            recommended_embedding_sizes: zkp_circuit::ValidationUtils::recommended_embedding_sizes(),
+        }
+    }
+
+    /// JSON encoding shared by the JNI and FFI bindings
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("limits always serialize")
+    }
+}
+
+/// Biometric payload accepted by the platform bindings
+///
+/// The template may arrive under any of the field names integrators commonly
+/// use (`template`, `embedding`, `vector`). Values are scaled by
+/// `scale_factor` (default 1000) before being committed; without a
+/// `reference` the fixed demo reference is used. Quantization rounds to the
+/// nearest integer, exactly as the WASM bindings do. Proofs are made for
+/// `threshold` (default `DEFAULT_THRESHOLD`) against the enrollment `opening`
+/// returned when the reference was enrolled.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct SimpleBiometricData {
+    #[serde(alias = "embedding", alias = "vector")]
+    pub template: Vec<f64>,
+    /// Multiplier applied to each float before integer conversion
+    #[serde(default)]
+    pub scale_factor: Option<f64>,
+    /// Reference template to compare against, in the same units as `template`
+    #[serde(default)]
+    pub reference: Option<Vec<f64>>,
+    /// Optional platform attestation token the proof is bound to
+    #[serde(default)]
+    pub attestation: Option<Vec<u8>>,
+    /// Distance threshold the proof is made for
+    #[serde(default)]
+    pub threshold: Option<u64>,
+    /// Opening of the reference's enrollment, kept on the device
+    #[serde(default)]
+    pub opening: Option<EnrollmentOpening>,
+}
+
+impl SimpleBiometricData {
+    const DEFAULT_SCALE_FACTOR: i64 = 1000;
+    const DEFAULT_REFERENCE_VALUE: u64 = 500;
+
+    fn scale_factor(&self) -> CircuitResult<i64> {
+        match self.scale_factor {
+            None => Ok(Self::DEFAULT_SCALE_FACTOR),
+            Some(scale) if scale.fract() == 0.0 && scale.abs() < i64::MAX as f64 => Ok(scale as i64),
+            Some(scale) => Err(CircuitError::InvalidParameter(
+                format!("Scale factor {} must be a whole number", scale)
+            )),
+        }
+    }
+
+    /// Quantize with the shared `ScalarUtils::quantize` so every binding agrees
+    fn scale(&self, values: &[f64]) -> CircuitResult<Vec<Scalar>> {
+        FieldUtils::embedding_to_scalars(&ScalarUtils::quantize(values, self.scale_factor()?)?)
+    }
+
+    /// Scaled template as an embedding, for enrollment
+    pub fn template_embedding(&self) -> CircuitResult<BiometricEmbedding> {
+        let scale_factor = self.scale_factor()?;
+        Ok(BiometricEmbedding::new(ScalarUtils::quantize(&self.template, scale_factor)?)?.with_scale_factor(scale_factor))
+    }
+
+    pub fn threshold(&self) -> u64 {
+        self.threshold.unwrap_or(zkp_circuit::config::DEFAULT_THRESHOLD)
+    }
+
+    /// Scaled probe embedding
+    pub fn current_embedding(&self) -> CircuitResult<Vec<Scalar>> {
+        self.scale(&self.template)
+    }
+
+    /// Scaled reference embedding, or the demo reference when none was sent
+    pub fn reference_embedding(&self) -> CircuitResult<Vec<Scalar>> {
+        match &self.reference {
+            Some(reference) => self.scale(reference),
+            None => Ok(vec![Scalar::from(Self::DEFAULT_REFERENCE_VALUE); self.template.len()]),
+        }
+    }
+}
+
+/// Result of enrolling a template: the commitments for the verifier and the
+/// opening that stays on the device
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct EnrollmentBundle {
+    pub enrollment: EnrollmentCommitment,
+    pub opening: EnrollmentOpening,
+}
+
+/// Verifier-side public data: the stored enrollment and, for device-bound
+/// proofs, the attestation the proof must be bound to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct VerificationData {
+    pub enrollment: EnrollmentCommitment,
+    #[serde(default)]
+    pub attestation: Option<Vec<u8>>,
+}
+
 /// Mobile platform utilities
 pub struct MobileUtils;
 
@@ -62,6 +188,78 @@ impl MobileUtils {
         }
     }
     
+    /// Derive the proof's device binding from a caller-supplied attestation
+    /// blob (Play Integrity token on Android, DeviceCheck token on iOS)
+    pub fn device_binding(attestation: Option<&[u8]>) -> Option<Vec<u8>> {
+        attestation
+            .filter(|blob| !blob.is_empty())
+            .map(zkp_circuit::types::ProofPublicInputs::device_binding_for)
+    }
+    
+    /// Enroll the payload's template, producing the serialized enrollment bundle
+    ///
+    /// The bundle's `enrollment` goes to the verifier; its `opening` stays on the
+    /// device and is sent back in the proof payload.
+    pub(crate) fn enroll_bytes(data_bytes: &[u8]) -> Result<Vec<u8>, String> {
+        let biometric_input: SimpleBiometricData = serde_json::from_slice(data_bytes)
+            .map_err(|e| format!("Invalid biometric JSON: {}", e))?;
+
+        let template = biometric_input.template_embedding().map_err(|e| e.to_string())?;
+        let circuit = BiometricCircuit::new(template.data.len(), biometric_input.threshold());
+        let (enrollment, opening) = circuit.enroll(&template).map_err(|e| e.to_string())?;
+
+        serde_json::to_vec(&EnrollmentBundle { enrollment, opening })
+            .map_err(|e| format!("Failed to serialize enrollment: {}", e))
+    }
+
+    /// Parse the JSON biometric payload and produce serialized proof bytes
+    ///
+    /// The payload must carry the enrollment opening of its reference.
+    pub(crate) fn generate_proof_bytes(data_bytes: &[u8]) -> Result<Vec<u8>, String> {
+        // Parse biometric data (assume JSON format)
+        let biometric_input: SimpleBiometricData = serde_json::from_slice(data_bytes)
+            .map_err(|e| format!("Invalid biometric JSON: {}", e))?;
+
+        let device_binding = Self::device_binding(biometric_input.attestation.as_deref());
+        let opening = biometric_input.opening.as_ref()
+            .ok_or_else(|| "Missing enrollment opening".to_string())?;
+
+        // Convert to Scalars (simplified)
+        let current_embedding: Vec<Scalar> = biometric_input.current_embedding().map_err(|e| e.to_string())?;
+        let reference_embedding: Vec<Scalar> = biometric_input.reference_embedding().map_err(|e| e.to_string())?;
+
+        // Generate proof using our circuit
+        let circuit = BiometricCircuit::new(current_embedding.len(), biometric_input.threshold());
+        let proof = circuit
+            .prove_enrolled_scalars(&current_embedding, &reference_embedding, opening, device_binding.as_deref())
+            .map_err(|e| e.to_string())?;
+
+        // Serialize proof to bytes
+        serde_json::to_vec(&proof).map_err(|e| format!("Failed to serialize proof: {}", e))
+    }
+
+    /// Verify serialized proof bytes against the JSON public data at `threshold`
+    ///
+    /// The public data carries the stored enrollment (and attestation, if the
+    /// proof must be device-bound). A proof over another enrollment or device is
+    /// `Ok(false)`; unreadable input, tampered enrollments and proofs describing
+    /// another circuit are errors.
+    pub(crate) fn verify_proof_bytes(proof_bytes: &[u8], public_bytes: &[u8], threshold: u64) -> Result<bool, String> {
+        let proof: BiometricProof = serde_json::from_slice(proof_bytes)
+            .map_err(|e| format!("Invalid proof JSON: {}", e))?;
+        let public_input: VerificationData = serde_json::from_slice(public_bytes)
+            .map_err(|e| format!("Invalid public data JSON: {}", e))?;
+
+        let device_binding = Self::device_binding(public_input.attestation.as_deref());
+        if proof.public_inputs.check_device_binding(device_binding.as_deref()).is_err() {
+            return Ok(false);
+        }
+
+        // The proof must commit to the enrolled reference, not one of its choosing
+        let circuit = BiometricCircuit::new(public_input.enrollment.embedding_size(), threshold);
+        circuit.verify_enrolled(&proof, &public_input.enrollment).map_err(|e| e.to_string())
+    }
+
     /// Check if platform supports hardware security
     pub fn supports_hardware_security() -> bool {
         // Both Android and iOS support hardware-backed security
@@ -80,6 +278,82 @@ mod tests {
         assert!(config.security_level >= 1 && config.security_level <= 5);
     }
 
+    #[test]
+    fn test_limits_json() {
+        let json: serde_json::Value = serde_json::from_str(&ZKPLimits::current().to_json()).unwrap();
+        assert_eq!(json["MAX_EMBEDDING_SIZE"], zkp_circuit::config::MAX_EMBEDDING_SIZE);
+        assert_eq!(json["MIN_THRESHOLD"], zkp_circuit::config::MIN_THRESHOLD);
+        assert_eq!(json["MAX_THRESHOLD"], zkp_circuit::config::MAX_THRESHOLD);
+        assert_eq!(
+            json["RECOMMENDED_EMBEDDING_SIZES"],
+            serde_json::json!(zkp_circuit::ValidationUtils::recommended_embedding_sizes())
+        );
+    }
+
+    #[test]
+    fn test_biometric_field_aliases() {
+        let payloads: [&[u8]; 3] = [
+            br#"{"template":[0.1,0.2,0.3]}"#,
+            br#"{"embedding":[0.1,0.2,0.3]}"#,
+            br#"{"vector":[0.1,0.2,0.3]}"#,
+        ];
+
+        let expected = vec![Scalar::from(100u64), Scalar::from(200u64), Scalar::from(300u64)];
+        for payload in payloads {
+            let data: SimpleBiometricData = serde_json::from_slice(payload).unwrap();
+            assert_eq!(data.current_embedding().unwrap(), expected);
+            assert_eq!(data.reference_embedding().unwrap(), vec![Scalar::from(500u64); 3]);
+        }
+    }
+
+    #[test]
+    fn test_biometric_scale_and_reference() {
+        let data: SimpleBiometricData = serde_json::from_slice(
+            br#"{"vector":[1.5,2.0],"scale_factor":10.0,"reference":[1.0,3.0]}"#
+        ).unwrap();
+
+        assert_eq!(data.current_embedding().unwrap(), vec![Scalar::from(15u64), Scalar::from(20u64)]);
+        assert_eq!(data.reference_embedding().unwrap(), vec![Scalar::from(10u64), Scalar::from(30u64)]);
+
+        // Fractional scales cannot be applied exactly
+        let fractional: SimpleBiometricData = serde_json::from_slice(
+            br#"{"vector":[1.5],"scale_factor":2.5}"#
+        ).unwrap();
+        assert!(fractional.current_embedding().is_err());
+
+        // Unknown shapes are still rejected
+        assert!(serde_json::from_slice::<SimpleBiometricData>(br#"{"features":[1.0]}"#).is_err());
+    }
+
+    #[test]
+    fn test_quantization_matches_core() {
+        // JNI and FFI both parse this payload; WASM goes through from_floats
+        let template = [0.1234, -0.1236, 0.0005, -0.0005, 0.0029];
+        let data = SimpleBiometricData {
+            template: template.to_vec(),
+            scale_factor: None,
+            reference: None,
+            attestation: None,
+            threshold: None,
+            opening: None,
+        };
+        let wasm = zkp_circuit::BiometricEmbedding::from_floats(template.to_vec(), 1000).unwrap();
+
+        assert_eq!(data.current_embedding().unwrap(), FieldUtils::embedding_to_scalars(&wasm.data).unwrap());
+        assert_eq!(wasm.data, vec![123, -124, 1, -1, 3]);
+    }
+
+    #[test]
+    fn test_device_binding() {
+        assert_eq!(MobileUtils::device_binding(None), None);
+        assert_eq!(MobileUtils::device_binding(Some(&[])), None);
+        
+        let device_a = MobileUtils::device_binding(Some(b"device-a")).unwrap();
+        let device_b = MobileUtils::device_binding(Some(b"device-b")).unwrap();
+        assert_eq!(device_a.len(), 32);
+        assert_ne!(device_a, device_b);
+    }
+
     #[test]
     fn test_hardware_security() {
         // Test that the function returns a boolean
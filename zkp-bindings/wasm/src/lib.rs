@@ -3,7 +3,8 @@ use serde::{Deserialize, Serialize};
 
 // Import our ZKP circuit - use actual types
 use zkp_circuit::circuit::BiometricCircuit;
-use curve25519_dalek_ng::scalar::Scalar;
+use zkp_circuit::types::{BiometricEmbedding, BiometricProof, EnrollmentCommitment, EnrollmentOpening, ProofMetadata, ProofPublicInputs, SerializableCommitment};
+use zkp_circuit::utils::SerializationUtils;
 
 // When the `wee_alloc` feature is enabled, use `wee_alloc` as the global allocator
 #[cfg(feature = "wee_alloc")]
@@ -83,12 +84,59 @@ impl WasmProofData {
     }
 }
 
+/// Public data returned with a WASM proof, serialized as JSON in `public_params`
+///
+/// Carries everything `verify_proof_bytes` needs besides the proof bytes and
+/// the stored enrollment: the compressed commitments (probe first, then
+/// reference), the public inputs and the proof metadata.
+/// `threshold`, `embedding_size` and the hex `commitment_hash` repeat the
+/// public inputs at the top level for JavaScript callers and must agree with
+/// them.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct WasmPublicParams {
+    threshold: u64,
+    embedding_size: usize,
+    commitment_hash: String,
+    public_inputs: ProofPublicInputs,
+    commitments: Vec<SerializableCommitment>,
+    metadata: ProofMetadata,
+}
+
+impl WasmPublicParams {
+    fn new(public_inputs: ProofPublicInputs, commitments: Vec<SerializableCommitment>, metadata: ProofMetadata) -> Self {
+        Self {
+            threshold: public_inputs.threshold,
+            embedding_size: public_inputs.embedding_size,
+            commitment_hash: hex::encode(&public_inputs.commitment_hash),
+            public_inputs,
+            commitments,
+            metadata,
+        }
+    }
+
+    /// Reject params whose summary fields disagree with the public inputs
+    fn check_summary(&self) -> Result<(), String> {
+        let inputs = &self.public_inputs;
+        if self.threshold != inputs.threshold
+            || self.embedding_size != inputs.embedding_size
+            || self.commitment_hash != hex::encode(&inputs.commitment_hash)
+        {
+            return Err("Invalid public params: summary does not match public inputs".to_string());
+        }
+        Ok(())
+    }
+}
+
 /// Main ZKP interface for WebAssembly
 #[wasm_bindgen]
 pub struct ZKPBiometric {
     embedding_size: usize,
     threshold: u64,
     initialized: bool,
+    /// Enrolled template that proofs are made against
+    reference_template: Option<Vec<f64>>,
+    /// Blindings of the enrolled template's commitments; never leaves the instance
+    enrollment_opening: Option<EnrollmentOpening>,
 }
 
 #[wasm_bindgen]
@@ -99,6 +147,8 @@ impl ZKPBiometric {
             embedding_size: 128,
             threshold: 1000,
             initialized: false,
+            reference_template: None,
+            enrollment_opening: None,
         }
     }
 
@@ -114,52 +164,55 @@ impl ZKPBiometric {
         Ok(())
     }
 
+    /// Enroll the reference template that `generate_proof` proves against
+    ///
+    /// Returns the enrollment JSON for the verifier to store; the blindings
+    /// that open it stay in this instance.
+    #[wasm_bindgen]
+    pub fn set_reference_template(&mut self, template: Vec<f64>) -> Result<String, JsValue> {
+        self.try_set_reference_template(template)
+            .map_err(|e| JsValue::from_str(&e))
+    }
+
     /// Generate a proof for biometric data
+    ///
+    /// Errors unless a reference was enrolled with `set_reference_template`.
     #[wasm_bindgen]
     pub fn generate_proof(&self, biometric_data: &WasmBiometricData) -> Result<WasmProofData, JsValue> {
-        if !self.initialized {
-            return Err(JsValue::from_str("ZKP system not initialized"));
-        }
-
-        // Convert to Scalars (simplified)
-        let current_embedding: Vec<Scalar> = biometric_data.template
-            .iter()
-            .map(|&f| Scalar::from((f * 1000.0) as u64))
-            .collect();
+        self.try_generate_proof(biometric_data)
+            .map_err(|e| JsValue::from_str(&e))
+    }
 
-        // Create reference embedding
-        let reference_embedding: Vec<Scalar> = vec![Scalar::from(500u64); current_embedding.len()];
+    /// Verify proof bytes and the `public_params` returned with them against
+    /// the stored `enrollment` JSON from `set_reference_template`
+    ///
+    /// Returns false when the proof does not verify or was made against a
+    /// different enrollment; malformed input is an error.
+    #[wasm_bindgen]
+    pub fn verify_proof_bytes(&self, proof_bytes: &[u8], public_params: &str, enrollment: &str) -> Result<bool, JsValue> {
+        self.try_verify_proof_bytes(proof_bytes, public_params, enrollment)
+            .map_err(|e| JsValue::from_str(&e))
+    }
 
-        // Generate proof
-        let circuit = BiometricCircuit::new(current_embedding.len(), self.threshold);
-        match circuit.generate_proof(&current_embedding, &reference_embedding) {
-            Ok(proof) => {
-                // Serialize proof to bytes
-                match serde_json::to_vec(&proof) {
-                    Ok(proof_bytes) => {
-                        let public_params = "{}".to_string(); // Simplified
-                        Ok(WasmProofData::new(proof_bytes, public_params))
-                    }
-                    Err(e) => Err(JsValue::from_str(&format!("Serialization failed: {}", e))),
-                }
-            }
-            Err(e) => Err(JsValue::from_str(&format!("Proof generation failed: {}", e))),
-        }
+    /// `verify_proof_bytes` for a proof as returned by `generate_proof`
+    #[wasm_bindgen]
+    pub fn verify_proof(&self, proof_data: &WasmProofData, enrollment: &str) -> Result<bool, JsValue> {
+        self.try_verify_proof_bytes(&proof_data.proof_bytes, &proof_data.public_params, enrollment)
+            .map_err(|e| JsValue::from_str(&e))
     }
 
-    /// Verify a proof against public biometric data
+    /// Encode a proof as the canonical base64 string, e.g. for localStorage
     #[wasm_bindgen]
-    pub fn verify_proof(
-        &self,
-        _proof_data: &WasmProofData,
-        _public_data: &WasmBiometricData,
-    ) -> Result<bool, JsValue> {
-        if !self.initialized {
-            return Err(JsValue::from_str("ZKP system not initialized"));
-        }
+    pub fn proof_to_base64(&self, proof: &WasmProofData) -> Result<String, JsValue> {
+        self.try_proof_to_base64(proof)
+            .map_err(|e| JsValue::from_str(&e))
+    }
 
-        // For demo purposes, just return true
-        Ok(true)
+    /// Decode a proof written by `proof_to_base64`
+    #[wasm_bindgen]
+    pub fn proof_from_base64(&self, encoded: &str) -> Result<WasmProofData, JsValue> {
+        Self::try_proof_from_base64(encoded)
+            .map_err(|e| JsValue::from_str(&e))
     }
 
     /// Get library version
@@ -168,11 +221,184 @@ impl ZKPBiometric {
         "1.0.0".to_string()
     }
 
+    /// Wipe cached state and mark the instance uninitialized
+    ///
+    /// The enrolled reference and its opening are overwritten and dropped,
+    /// and calls that need the system error until `initialize` runs again.
+    /// Any other cached circuit or blinding material must be cleared here as
+    /// well.
+    #[wasm_bindgen]
+    pub fn dispose(&mut self) {
+        self.clear_reference_template();
+        self.initialized = false;
+    }
+
     /// Check if the system is initialized
     #[wasm_bindgen]
     pub fn is_initialized(&self) -> bool {
         self.initialized
     }
+
+    /// Embedding size the system is configured for
+    #[wasm_bindgen(getter)]
+    pub fn embedding_size(&self) -> usize {
+        self.embedding_size
+    }
+
+    /// Distance threshold the system is configured for
+    #[wasm_bindgen(getter)]
+    pub fn threshold(&self) -> u64 {
+        self.threshold
+    }
+}
+
+impl Default for ZKPBiometric {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ZKPBiometric {
+    /// Fixed-point scale applied to float templates before proving
+    const SCALE_FACTOR: i64 = 1000;
+
+    /// Overwrite the enrolled reference before dropping it
+    ///
+    /// The opening zeroizes itself when dropped.
+    fn clear_reference_template(&mut self) {
+        if let Some(mut template) = self.reference_template.take() {
+            for value in template.iter_mut() {
+                // Volatile so the wipe is not optimized away as a dead store
+                unsafe { std::ptr::write_volatile(value, 0.0) };
+            }
+        }
+        self.enrollment_opening = None;
+    }
+
+    /// `set_reference_template` with plain string errors, so it also runs off-wasm
+    fn try_set_reference_template(&mut self, template: Vec<f64>) -> Result<String, String> {
+        self.clear_reference_template();
+
+        let reference = BiometricEmbedding::from_floats(template.clone(), Self::SCALE_FACTOR)
+            .map_err(|e| format!("Invalid reference: {}", e))?;
+        let circuit = BiometricCircuit::new(reference.size, self.threshold);
+        let (enrollment, opening) = circuit.enroll(&reference)
+            .map_err(|e| format!("Enrollment failed: {}", e))?;
+
+        self.reference_template = Some(template);
+        self.enrollment_opening = Some(opening);
+        serde_json::to_string(&enrollment)
+            .map_err(|e| format!("Serialization failed: {}", e))
+    }
+
+    /// `generate_proof` with plain string errors, so it also runs off-wasm
+    fn try_generate_proof(&self, biometric_data: &WasmBiometricData) -> Result<WasmProofData, String> {
+        if !self.initialized {
+            return Err("ZKP system not initialized".to_string());
+        }
+
+        let (Some(reference), Some(opening)) = (&self.reference_template, &self.enrollment_opening) else {
+            return Err("No reference template enrolled".to_string());
+        };
+        if reference.len() != biometric_data.template.len() {
+            return Err(format!(
+                "Template has {} values, enrolled reference has {}",
+                biometric_data.template.len(),
+                reference.len()
+            ));
+        }
+
+        let current = BiometricEmbedding::from_floats(biometric_data.template.clone(), Self::SCALE_FACTOR)
+            .map_err(|e| format!("Invalid template: {}", e))?;
+        let reference = BiometricEmbedding::from_floats(reference.clone(), Self::SCALE_FACTOR)
+            .map_err(|e| format!("Invalid reference: {}", e))?;
+
+        // Generate proof
+        let circuit = BiometricCircuit::new(current.size, self.threshold);
+        let proof = circuit.prove_enrolled(&current, &reference, opening)
+            .map_err(|e| format!("Proof generation failed: {}", e))?;
+
+        Self::to_wasm_proof(proof)
+    }
+
+    /// `proof_to_base64` with plain string errors, so it also runs off-wasm
+    fn try_proof_to_base64(&self, proof: &WasmProofData) -> Result<String, String> {
+        let proof = self.decode_proof(&proof.proof_bytes, &proof.public_params)?;
+        SerializationUtils::proof_to_base64(&proof)
+            .map_err(|e| format!("Serialization failed: {}", e))
+    }
+
+    /// `proof_from_base64` with plain string errors, so it also runs off-wasm
+    fn try_proof_from_base64(encoded: &str) -> Result<WasmProofData, String> {
+        let proof = SerializationUtils::proof_from_base64(encoded)
+            .map_err(|e| format!("Invalid proof encoding: {}", e))?;
+        Self::to_wasm_proof(proof)
+    }
+
+    /// Split a packaged proof into its bytes and `public_params` JSON
+    fn to_wasm_proof(proof: BiometricProof) -> Result<WasmProofData, String> {
+        let public_params = WasmPublicParams::new(proof.public_inputs, proof.commitments, proof.metadata);
+        let public_params = serde_json::to_string(&public_params)
+            .map_err(|e| format!("Serialization failed: {}", e))?;
+        Ok(WasmProofData::new(proof.proof, public_params))
+    }
+
+    /// `verify_proof_bytes` with plain string errors, so it also runs off-wasm
+    ///
+    /// The payload's reference commitments are only trusted once they match
+    /// the enrollment's.
+    fn try_verify_proof_bytes(&self, proof_bytes: &[u8], public_params: &str, enrollment: &str) -> Result<bool, String> {
+        let proof = self.decode_proof(proof_bytes, public_params)?;
+        let enrollment: EnrollmentCommitment = serde_json::from_str(enrollment)
+            .map_err(|e| format!("Invalid enrollment: {}", e))?;
+
+        if enrollment.embedding_size() != proof.public_inputs.embedding_size {
+            return Err(format!(
+                "Enrollment has {} values, proof was made for {}",
+                enrollment.embedding_size(),
+                proof.public_inputs.embedding_size
+            ));
+        }
+
+        let circuit = self.proof_circuit(&proof.public_inputs)?;
+        circuit.verify_enrolled(&proof, &enrollment)
+            .map_err(|e| format!("Proof verification failed: {}", e))
+    }
+
+    /// Circuit for the threshold and embedding size a proof was made under
+    ///
+    /// This instance's threshold is the loosest it accepts, so a proof under
+    /// a stricter threshold verifies but one under a looser threshold is refused.
+    fn proof_circuit(&self, inputs: &ProofPublicInputs) -> Result<BiometricCircuit, String> {
+        if inputs.threshold > self.threshold {
+            return Err(format!(
+                "Proof threshold {} is looser than the configured {}",
+                inputs.threshold, self.threshold
+            ));
+        }
+        Ok(BiometricCircuit::new(inputs.embedding_size, inputs.threshold))
+    }
+
+    /// Rebuild the packaged proof from its bytes and `public_params` JSON
+    ///
+    /// The metadata is carried over as generated, so the proof keeps its
+    /// original timestamp, version and nonce.
+    fn decode_proof(&self, proof_bytes: &[u8], public_params: &str) -> Result<BiometricProof, String> {
+        if !self.initialized {
+            return Err("ZKP system not initialized".to_string());
+        }
+
+        let public_params: WasmPublicParams = serde_json::from_str(public_params)
+            .map_err(|e| format!("Invalid public params: {}", e))?;
+        public_params.check_summary()?;
+
+        Ok(BiometricProof {
+            proof: proof_bytes.to_vec(),
+            commitments: public_params.commitments,
+            public_inputs: public_params.public_inputs,
+            metadata: public_params.metadata,
+        })
+    }
 }
 
 /// Utility functions for biometric processing in WASM
@@ -239,6 +465,8 @@ impl BiometricUtils {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use zkp_circuit::crypto::FieldUtils;
+    use zkp_circuit::PedersenGens;
 
     #[test]
     fn test_wasm_interface() {
@@ -251,6 +479,192 @@ mod tests {
         assert_eq!(zkp.get_version(), "1.0.0");
     }
 
+    #[test]
+    fn test_dispose_requires_reinitialization() {
+        let mut zkp = ZKPBiometric::new();
+        zkp.initialize().unwrap();
+        zkp.try_set_reference_template(vec![0.5; 4]).unwrap();
+        
+        zkp.dispose();
+        assert!(!zkp.is_initialized());
+        assert!(zkp.reference_template.is_none());
+        assert!(zkp.enrollment_opening.is_none());
+        
+        let data = WasmBiometricData::new(vec![0.5; 4], None);
+        assert!(zkp.try_generate_proof(&data).is_err());
+        
+        zkp.initialize().unwrap();
+        assert!(zkp.is_initialized());
+    }
+
+    #[test]
+    fn test_generated_proof_verifies_from_returned_data() {
+        let mut zkp = ZKPBiometric::new();
+        zkp.initialize().unwrap();
+        let enrollment = zkp.try_set_reference_template(vec![0.5; 4]).unwrap();
+
+        let data = WasmBiometricData::new(vec![0.5, 0.51, 0.49, 0.5], None);
+        let proof = zkp.try_generate_proof(&data).unwrap();
+
+        let public_params: serde_json::Value = serde_json::from_str(&proof.public_params()).unwrap();
+        assert_eq!(public_params["threshold"], 1000);
+        assert_eq!(public_params["embedding_size"], 4);
+        assert_eq!(public_params["commitment_hash"].as_str().unwrap().len(), 64);
+        assert_eq!(public_params["public_inputs"]["threshold"], 1000);
+        assert_eq!(public_params["public_inputs"]["embedding_size"], 4);
+        assert_eq!(public_params["commitments"].as_array().unwrap().len(), 8);
+
+        assert!(zkp.try_verify_proof_bytes(&proof.proof_bytes(), &proof.public_params(), &enrollment).unwrap());
+
+        // Tampered proof bytes no longer verify
+        let mut tampered = proof.proof_bytes();
+        tampered[0] ^= 1;
+        assert!(!zkp.try_verify_proof_bytes(&tampered, &proof.public_params(), &enrollment).unwrap_or(false));
+    }
+
+    #[test]
+    fn test_verification_uses_proof_params() {
+        let mut zkp = ZKPBiometric::new();
+        zkp.initialize().unwrap();
+        let enrollment = zkp.try_set_reference_template(vec![0.13, -0.40, 0.32]).unwrap();
+
+        let proof = zkp.try_generate_proof(&WasmBiometricData::new(vec![0.12, -0.40, 0.33], None)).unwrap();
+        let mut public_params: serde_json::Value = serde_json::from_str(&proof.public_params()).unwrap();
+        assert_eq!(public_params["threshold"], zkp.threshold());
+        assert_eq!(public_params["embedding_size"], 3);
+
+        // A verifier configured more loosely accepts the stricter proof
+        let mut loose = ZKPBiometric::new();
+        loose.initialize().unwrap();
+        loose.threshold = 5000;
+        assert!(loose.try_verify_proof_bytes(&proof.proof_bytes(), &proof.public_params(), &enrollment).unwrap());
+
+        // A stricter one refuses it
+        let mut strict = ZKPBiometric::new();
+        strict.initialize().unwrap();
+        strict.threshold = 500;
+        assert!(strict.try_verify_proof_bytes(&proof.proof_bytes(), &proof.public_params(), &enrollment).is_err());
+
+        // The summary must agree with the public inputs
+        public_params["threshold"] = 500.into();
+        let edited = public_params.to_string();
+        assert!(zkp
+            .try_verify_proof_bytes(&proof.proof_bytes(), &edited, &enrollment)
+            .unwrap_err()
+            .starts_with("Invalid public params"));
+    }
+
+    #[test]
+    fn test_proof_against_enrolled_reference() {
+        let mut zkp = ZKPBiometric::new();
+        zkp.initialize().unwrap();
+
+        let probe = WasmBiometricData::new(vec![0.12, -0.40, 0.33, 0.81], None);
+        assert_eq!(zkp.try_generate_proof(&probe).err().unwrap(), "No reference template enrolled");
+
+        // Squared distance 10² + 10² = 200 after scaling by 1000
+        let enrollment = zkp.try_set_reference_template(vec![0.13, -0.40, 0.32, 0.81]).unwrap();
+        let proof = zkp.try_generate_proof(&probe).unwrap();
+        assert!(zkp.try_verify_proof_bytes(&proof.proof_bytes(), &proof.public_params(), &enrollment).unwrap());
+
+        // A template far from the enrolled one cannot be proven
+        let impostor = WasmBiometricData::new(vec![0.90, 0.10, -0.50, 0.20], None);
+        assert!(zkp.try_generate_proof(&impostor).is_err());
+
+        let short = WasmBiometricData::new(vec![0.12, -0.40], None);
+        assert!(zkp.try_generate_proof(&short).is_err());
+    }
+
+    #[test]
+    fn test_verify_proof_against_enrollment() {
+        let mut zkp = ZKPBiometric::new();
+        zkp.initialize().unwrap();
+        let enrolled = vec![0.13, -0.40, 0.32, 0.81];
+        let enrollment = zkp.try_set_reference_template(enrolled.clone()).unwrap();
+
+        let probe = WasmBiometricData::new(vec![0.12, -0.40, 0.33, 0.81], None);
+        let proof = zkp.try_generate_proof(&probe).unwrap();
+        let verify = |proof: &WasmProofData, enrollment: &str| {
+            zkp.try_verify_proof_bytes(&proof.proof_bytes(), &proof.public_params(), enrollment)
+        };
+        assert_eq!(verify(&proof, &enrollment), Ok(true));
+
+        // Corrupting the first proof point makes verification fail
+        let mut corrupted = proof.proof_bytes();
+        corrupted[1] ^= 1;
+        let corrupted = WasmProofData::new(corrupted, proof.public_params());
+        assert_eq!(verify(&corrupted, &enrollment), Ok(false));
+
+        // So does checking against another enrollment of the same template
+        let mut other = ZKPBiometric::new();
+        other.initialize().unwrap();
+        let reenrolled = other.try_set_reference_template(enrolled).unwrap();
+        assert_eq!(verify(&proof, &reenrolled), Ok(false));
+
+        // A self-consistent proof over a reference of the prover's choosing is refused
+        let forged = other.try_generate_proof(&probe).unwrap();
+        assert_eq!(verify(&forged, &enrollment), Ok(false));
+
+        let malformed = WasmProofData::new(proof.proof_bytes(), "{".to_string());
+        assert!(verify(&malformed, &enrollment).unwrap_err().starts_with("Invalid public params"));
+        assert!(verify(&proof, "{").unwrap_err().starts_with("Invalid enrollment"));
+    }
+
+    #[test]
+    fn test_reference_quantized_like_other_bindings() {
+        let mut zkp = ZKPBiometric::new();
+        zkp.initialize().unwrap();
+        let enrolled = vec![0.1234, -0.1236, 0.0005, -0.0005];
+        zkp.try_set_reference_template(enrolled.clone()).unwrap();
+
+        let proof = zkp.try_generate_proof(&WasmBiometricData::new(enrolled.clone(), None)).unwrap();
+        let public_params: WasmPublicParams = serde_json::from_str(&proof.public_params()).unwrap();
+
+        // Same rounding the mobile bindings apply via ScalarUtils::quantize
+        let quantized = zkp_circuit::utils::scalar_utils::ScalarUtils::quantize(&enrolled, 1000).unwrap();
+        assert_eq!(quantized, vec![123, -124, 1, -1]);
+        let blindings = zkp.enrollment_opening.as_ref().unwrap().blindings().unwrap();
+        let expected: Vec<SerializableCommitment> = FieldUtils::embedding_to_scalars(&quantized)
+            .unwrap()
+            .iter()
+            .zip(blindings.iter())
+            .map(|(v, blinding)| PedersenGens::default().commit(*v, *blinding).into())
+            .collect();
+        assert_eq!(public_params.commitments[4..], expected[..]);
+    }
+
+    #[test]
+    fn test_proof_base64_round_trip() {
+        let mut zkp = ZKPBiometric::new();
+        zkp.initialize().unwrap();
+        let enrollment = zkp.try_set_reference_template(vec![0.13, -0.40, 0.32, 0.81]).unwrap();
+
+        let proof = zkp.try_generate_proof(&WasmBiometricData::new(vec![0.12, -0.40, 0.33, 0.81], None)).unwrap();
+        let encoded = zkp.try_proof_to_base64(&proof).unwrap();
+        assert_eq!(
+            SerializationUtils::proof_from_base64(&encoded).unwrap().proof,
+            proof.proof_bytes()
+        );
+
+        let decoded = ZKPBiometric::try_proof_from_base64(&encoded).unwrap();
+        assert_eq!(decoded.proof_bytes(), proof.proof_bytes());
+        assert_eq!(decoded.public_params(), proof.public_params());
+
+        // The metadata is the prover's, not restamped on the way through
+        let original: WasmPublicParams = serde_json::from_str(&proof.public_params()).unwrap();
+        let mut aged = original.clone();
+        aged.metadata.timestamp -= 3600;
+        aged.metadata.nonce = vec![7; 16];
+        let aged = WasmProofData::new(proof.proof_bytes(), serde_json::to_string(&aged).unwrap());
+        let metadata = SerializationUtils::proof_from_base64(&zkp.try_proof_to_base64(&aged).unwrap()).unwrap().metadata;
+        assert_eq!(metadata.timestamp, original.metadata.timestamp - 3600);
+        assert_eq!(metadata.nonce, vec![7; 16]);
+        assert_eq!(metadata.version, original.metadata.version);
+        assert!(zkp.try_verify_proof_bytes(&decoded.proof_bytes(), &decoded.public_params(), &enrollment).unwrap());
+
+        assert!(ZKPBiometric::try_proof_from_base64("not base64!").unwrap_err().starts_with("Invalid proof encoding"));
+    }
+
     #[test]
     fn test_biometric_utils() {
         let template = vec![1.0, 2.0, 3.0, 4.0, 5.0];
@@ -0,0 +1,21 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use zkp_circuit::circuit::BiometricCircuit;
+use zkp_circuit::Scalar;
+
+fn bench_proof_generation(c: &mut Criterion) {
+    let circuit = BiometricCircuit::new(4, 1000);
+
+    let current_embedding: Vec<Scalar> = (1..=4u64).map(Scalar::from).collect();
+    let reference_embedding: Vec<Scalar> = (2..=5u64).map(Scalar::from).collect();
+
+    c.bench_function("generate_proof_4", |b| {
+        b.iter(|| {
+            circuit
+                .generate_proof(black_box(&current_embedding), black_box(&reference_embedding))
+                .unwrap()
+        })
+    });
+}
+
+criterion_group!(benches, bench_proof_generation);
+criterion_main!(benches);
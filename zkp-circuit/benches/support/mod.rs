@@ -0,0 +1,56 @@
+//! Input construction shared by the verification benchmarks and their smoke test
+
+use zkp_circuit::circuit::BiometricCircuit;
+use zkp_circuit::{CompressedRistretto, PedersenGens, Scalar};
+
+/// Embedding sizes the verification benchmarks sweep over
+pub const EMBEDDING_SIZES: [usize; 3] = [128, 256, 512];
+
+/// A proof together with everything needed to verify it
+pub struct ProofInputs {
+    pub circuit: BiometricCircuit,
+    pub current: Vec<Scalar>,
+    pub reference: Vec<Scalar>,
+    pub proof: Vec<u8>,
+    pub commitments: Vec<CompressedRistretto>,
+}
+
+/// Deterministic probe/reference pair that stays well under the threshold
+pub fn embeddings(embedding_size: usize) -> (Vec<Scalar>, Vec<Scalar>) {
+    let current: Vec<u64> = (0..embedding_size as u64).map(|i| 10 + i % 7).collect();
+    let reference: Vec<u64> = current
+        .iter()
+        .enumerate()
+        .map(|(i, &v)| if i % 8 == 0 { v + 1 } else { v })
+        .collect();
+    
+    (
+        current.into_iter().map(Scalar::from).collect(),
+        reference.into_iter().map(Scalar::from).collect(),
+    )
+}
+
+/// Generate a proof and its public commitments through the public API
+pub fn proof_inputs(embedding_size: usize) -> ProofInputs {
+    let circuit = BiometricCircuit::new(embedding_size, 1000);
+    let (current, reference) = embeddings(embedding_size);
+    let proof = circuit
+        .generate_proof(&current, &reference)
+        .expect("benchmark inputs must be provable");
+    
+    // Public commitments are the current embedding's followed by the reference's
+    let pc_gens = PedersenGens::default();
+    let commitments = current
+        .iter()
+        .chain(&reference)
+        .map(|v| pc_gens.commit(*v, Scalar::from(0u64)).compress())
+        .collect();
+    
+    ProofInputs {
+        circuit,
+        current,
+        reference,
+        proof,
+        commitments,
+    }
+}
@@ -0,0 +1,61 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+mod support;
+
+use support::{proof_inputs, EMBEDDING_SIZES};
+
+/// Number of proofs checked per batch verification iteration
+const BATCH_SIZE: usize = 8;
+
+fn bench_generate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("generate");
+    for size in EMBEDDING_SIZES {
+        let inputs = proof_inputs(size);
+        group.bench_with_input(BenchmarkId::from_parameter(size), &inputs, |b, inputs| {
+            b.iter(|| {
+                inputs
+                    .circuit
+                    .generate_proof(black_box(&inputs.current), black_box(&inputs.reference))
+                    .unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_verify_single(c: &mut Criterion) {
+    let mut group = c.benchmark_group("verify_single");
+    for size in EMBEDDING_SIZES {
+        let inputs = proof_inputs(size);
+        group.bench_with_input(BenchmarkId::from_parameter(size), &inputs, |b, inputs| {
+            b.iter(|| {
+                inputs
+                    .circuit
+                    .verify_proof(black_box(&inputs.proof), black_box(&inputs.commitments))
+                    .unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_verify_batch(c: &mut Criterion) {
+    let mut group = c.benchmark_group("verify_batch");
+    for size in EMBEDDING_SIZES {
+        let batch: Vec<_> = (0..BATCH_SIZE).map(|_| proof_inputs(size)).collect();
+        group.bench_with_input(BenchmarkId::from_parameter(size), &batch, |b, batch| {
+            b.iter(|| {
+                batch.iter().all(|inputs| {
+                    inputs
+                        .circuit
+                        .verify_proof(black_box(&inputs.proof), black_box(&inputs.commitments))
+                        .unwrap()
+                })
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_generate, bench_verify_single, bench_verify_batch);
+criterion_main!(benches);
@@ -1,5 +1,5 @@
 use zkp_circuit::circuit::BiometricCircuit;
-use curve25519_dalek_ng::scalar::Scalar;
+use zkp_circuit::{PedersenGens, Scalar};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("Biometric ZKP");
@@ -27,9 +27,15 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         Ok(proof) => {
             println!("Proof generated successfully! Size: {} bytes", proof.len());
             
-            // Verify proof (simplified)
-            let dummy_commitments = vec![];
-            match circuit.verify_proof(&proof, &dummy_commitments) {
+            // Commit to current then reference values, matching the prover's order
+            let pc_gens = PedersenGens::default();
+            let commitments: Vec<_> = current_embedding
+                .iter()
+                .chain(reference_embedding.iter())
+                .map(|v| pc_gens.commit(*v, Scalar::from(0u64)).compress())
+                .collect();
+            
+            match circuit.verify_proof(&proof, &commitments) {
                 Ok(valid) => {
                     println!("Proof verification result: {}", valid);
                 }
@@ -0,0 +1,57 @@
+//! Curve backend selection
+//!
+//! The crate builds against `curve25519-dalek-ng` with `bulletproofs` 4.x by
+//! default, or upstream `curve25519-dalek` 4.x with `bulletproofs` 5.x with
+//! feature `dalek4`. Crate code imports curve and proof types through this
+//! module so the choice is made in one place.
+
+#[cfg(all(feature = "dalek-ng", feature = "dalek4"))]
+compile_error!("features `dalek-ng` and `dalek4` are mutually exclusive; enable at most one curve backend");
+
+#[cfg(not(feature = "dalek4"))]
+pub(crate) use ::bulletproofs;
+#[cfg(not(feature = "dalek4"))]
+pub(crate) use ::curve25519_dalek_ng as curve25519;
+
+#[cfg(feature = "dalek4")]
+pub(crate) use ::bulletproofs5 as bulletproofs;
+#[cfg(feature = "dalek4")]
+pub(crate) use ::curve25519_dalek as curve25519;
+
+/// Name of the curve crate the library was built against
+#[cfg(not(feature = "dalek4"))]
+pub const CURVE_BACKEND: &str = "curve25519-dalek-ng";
+#[cfg(feature = "dalek4")]
+pub const CURVE_BACKEND: &str = "curve25519-dalek";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::curve25519::ristretto::CompressedRistretto;
+    use super::curve25519::scalar::Scalar;
+    use crate::utils::scalar_utils::ScalarUtils;
+    
+    #[test]
+    fn test_backend_selected() {
+        #[cfg(not(feature = "dalek4"))]
+        assert_eq!(CURVE_BACKEND, "curve25519-dalek-ng");
+        #[cfg(feature = "dalek4")]
+        assert_eq!(CURVE_BACKEND, "curve25519-dalek");
+    }
+    
+    #[test]
+    fn test_scalar_round_trip() {
+        let value = Scalar::from(123_456_789u64);
+        let restored = Scalar::from_bytes_mod_order(value.to_bytes());
+        assert_eq!(value, restored);
+        
+        assert_eq!(ScalarUtils::zero() + ScalarUtils::one(), ScalarUtils::one());
+        assert_eq!(value * value.invert(), ScalarUtils::one());
+        
+        // Commitments survive compression under both backends
+        let pc_gens = bulletproofs::PedersenGens::default();
+        let point = pc_gens.commit(value, ScalarUtils::one());
+        let compressed = CompressedRistretto(point.compress().to_bytes());
+        assert_eq!(compressed.decompress(), Some(point));
+    }
+}
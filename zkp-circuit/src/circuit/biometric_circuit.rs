@@ -1,30 +1,214 @@
-use bulletproofs::{BulletproofGens, PedersenGens, r1cs::{Prover, Verifier, Variable}};
-use curve25519_dalek_ng::scalar::Scalar;
-use curve25519_dalek_ng::ristretto::CompressedRistretto;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use alloc::sync::Arc;
+use alloc::{format, vec};
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+#[cfg(feature = "std")]
+use std::sync::{Mutex, OnceLock, PoisonError};
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+use crate::backend::bulletproofs::{BulletproofGens, PedersenGens, r1cs::{ConstraintSystem, LinearCombination, Prover, R1CSProof, Variable, Verifier}};
+use crate::backend::curve25519::scalar::Scalar;
+use crate::backend::curve25519::ristretto::CompressedRistretto;
 use merlin::Transcript;
+use rand::{CryptoRng, RngCore};
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::types::{BiometricEmbedding, BiometricProof, BucketedProof, ChunkedProof, CircuitError, CircuitResult, ConstraintDescription, EnrollmentCommitment, EnrollmentOpening, ProofEstimate, AggregateMatchProof, ContinuityProof, FusedProof, GateGroup, GateKind, IntervalProof, ProofPublicInputs, ProofWitness, SerializableCommitment, ThresholdBuckets, VerificationPolicy};
+use crate::config::{SimilarityMetric, AGGREGATION_SIZE, COORDINATE_RANGE_BITS, COSINE_RANGE_BITS, COSINE_SCALE, MAX_EMBEDDING_VALUE, MAX_GENERATOR_CAPACITY, RANGE_BITS, TRANSCRIPT_LABEL};
+use crate::crypto::{CommitmentScheme, FieldUtils};
+#[cfg(feature = "poseidon")]
+use crate::crypto::{CircuitHash, LinkingTag};
+#[cfg(feature = "poseidon")]
+use crate::types::LinkableProof;
+use crate::circuit::constraints::{BiometricConstraints, VerificationConstraints};
+use crate::circuit::gadgets::{BiometricGadgets, CosineScoreWitness};
+use crate::utils::scalar_utils::ScalarUtils;
+use crate::proof::build_transcript;
 
-use crate::types::{CircuitError, CircuitResult};
-use crate::crypto::CommitmentScheme;
-use crate::circuit::gadgets::BiometricGadgets;
+/// Point in time a proof is abandoned at; see `prove_with_deadline`
+#[cfg(feature = "std")]
+type Deadline = Instant;
+/// Without a clock no deadline can be set
+#[cfg(not(feature = "std"))]
+type Deadline = core::convert::Infallible;
 
 /// Simplified biometric ZKP circuit
 pub struct BiometricCircuit {
     pub embedding_size: usize,
     pub threshold: u64,
     pub pedersen_gens: PedersenGens,
-    pub bulletproof_gens: BulletproofGens,
+    /// Shared with every circuit built for the same capacity; see `shared_gens`
+    pub bulletproof_gens: Arc<BulletproofGens>,
     pub commitment_scheme: CommitmentScheme,
+    pub metric: SimilarityMetric,
+    /// Bits `threshold - distance` is decomposed over by the threshold gate
+    pub range_bits: usize,
+    /// Deployment identifier bound into every proof transcript
+    pub domain: Vec<u8>,
+    /// Whether packaged proofs bind their commitment hash in-circuit; see
+    /// `with_commitment_binding`
+    pub bind_commitment_hash: bool,
+    /// Whether `bulletproof_gens` were chosen by the caller, in which case
+    /// proofs needing more fail with `CapacityExceeded` instead of growing them
+    pub fixed_gens: bool,
+}
+
+/// Public statement a distance proof is checked against
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct DistanceStatement {
+    pub embedding_size: usize,
+    pub threshold: u64,
+    pub metric: SimilarityMetric,
+    pub range_bits: usize,
+    /// Hash of the committed embeddings bound in-circuit, if any
+    pub commitment_hash: Option<Scalar>,
+}
+
+/// Proof fields `verify_with_policy` relies on, bound into the transcript of
+/// every packaged proof so they cannot be altered after proving
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ProofStamp {
+    pub timestamp: u64,
+    pub liveness_score: Option<u64>,
+}
+
+impl ProofStamp {
+    /// The current time (zero without `std`) and no liveness score
+    fn now() -> Self {
+        Self {
+            timestamp: crate::types::proof_data::current_timestamp().unwrap_or_default(),
+            liveness_score: None,
+        }
+    }
+    
+    /// The stamp a packaged proof records
+    pub(crate) fn of(proof: &BiometricProof) -> Self {
+        Self {
+            timestamp: proof.metadata.timestamp,
+            liveness_score: proof.public_inputs.liveness_score,
+        }
+    }
+    
+    /// Absorb the stamp into `transcript`
+    pub(crate) fn bind(&self, transcript: &mut Transcript) {
+        transcript.append_u64(b"timestamp", self.timestamp);
+        match self.liveness_score {
+            Some(score) => transcript.append_message(b"liveness_score", &score.to_le_bytes()),
+            None => transcript.append_message(b"liveness_score", b""),
+        }
+    }
 }
 
 impl BiometricCircuit {
     /// Create a new biometric circuit
+    ///
+    /// Bulletproof generators are sized for the proof `generate_proof` makes,
+    /// up to `MAX_GENERATOR_CAPACITY`; larger proofs allocate their own.
     pub fn new(embedding_size: usize, threshold: u64) -> Self {
         Self {
             embedding_size,
             threshold,
             pedersen_gens: PedersenGens::default(),
-            bulletproof_gens: BulletproofGens::new(64, 1),
+            bulletproof_gens: Self::sized_gens(embedding_size, SimilarityMetric::default()),
+            commitment_scheme: CommitmentScheme::new(),
+            metric: SimilarityMetric::default(),
+            range_bits: RANGE_BITS,
+            domain: Vec::new(),
+            bind_commitment_hash: false,
+            fixed_gens: false,
+        }
+    }
+    
+    /// Create a circuit that thresholds on `metric`
+    ///
+    /// For `SimilarityMetric::CosineSimilarity` the threshold is the minimum
+    /// similarity in units of `1 / COSINE_SCALE`.
+    pub fn with_metric(embedding_size: usize, threshold: u64, metric: SimilarityMetric) -> Self {
+        Self {
+            metric,
+            bulletproof_gens: Self::sized_gens(embedding_size, metric),
+            ..Self::new(embedding_size, threshold)
+        }
+    }
+    
+    /// Create a circuit with explicit generators
+    ///
+    /// Prover and verifier must use the same generators; building both from
+    /// the same deterministic inputs makes proofs portable across devices.
+    /// Proofs needing more multipliers than `bulletproof_gens` holds fail with
+    /// `CapacityExceeded`.
+    pub fn with_gens(
+        embedding_size: usize,
+        threshold: u64,
+        pedersen_gens: PedersenGens,
+        bulletproof_gens: BulletproofGens,
+    ) -> Self {
+        Self {
+            embedding_size,
+            threshold,
+            pedersen_gens,
+            bulletproof_gens: Arc::new(bulletproof_gens),
             commitment_scheme: CommitmentScheme::new(),
+            metric: SimilarityMetric::default(),
+            range_bits: RANGE_BITS,
+            domain: Vec::new(),
+            bind_commitment_hash: false,
+            fixed_gens: true,
+        }
+    }
+    
+    /// Create a circuit whose Pedersen generators are rotated to `epoch`
+    ///
+    /// Commitments and proofs made with this circuit only verify under the
+    /// same epoch; record it with `BiometricProof::with_generator_epoch`.
+    pub fn with_generator_epoch(embedding_size: usize, threshold: u64, epoch: u64) -> Self {
+        let commitment_scheme = CommitmentScheme::for_epoch(epoch);
+        Self {
+            embedding_size,
+            threshold,
+            pedersen_gens: commitment_scheme.pedersen_gens(),
+            bulletproof_gens: Self::sized_gens(embedding_size, SimilarityMetric::default()),
+            commitment_scheme,
+            metric: SimilarityMetric::default(),
+            range_bits: RANGE_BITS,
+            domain: Vec::new(),
+            bind_commitment_hash: false,
+            fixed_gens: false,
+        }
+    }
+    
+    /// Bind the circuit's proofs to a deployment `domain`
+    ///
+    /// Proofs only verify under a circuit with the same domain.
+    pub fn with_domain(mut self, domain: &[u8]) -> Self {
+        self.domain = domain.to_vec();
+        self
+    }
+    
+    /// Bind the commitment hash of packaged proofs in-circuit
+    ///
+    /// `prove` then records the Poseidon hash of the current then reference
+    /// coordinates as the commitment hash and constrains it against the
+    /// committed embeddings, and `verify` enforces it in the proof instead of
+    /// rehashing the commitments. Costs `poseidon_multipliers(2 · n)` extra
+    /// multipliers; chunked and raw-byte proofs are unaffected.
+    #[cfg(feature = "poseidon")]
+    pub fn with_commitment_binding(mut self) -> Self {
+        self.bind_commitment_hash = true;
+        self
+    }
+    
+    /// Create the verifying circuit described by a proof's public inputs
+    ///
+    /// Uses the recorded generator epoch when present and the default
+    /// generators otherwise.
+    pub fn from_public_inputs(public_inputs: &ProofPublicInputs) -> Self {
+        match public_inputs.generator_epoch {
+            Some(epoch) => Self::with_generator_epoch(public_inputs.embedding_size, public_inputs.threshold, epoch),
+            None => Self::new(public_inputs.embedding_size, public_inputs.threshold),
         }
     }
     
@@ -34,79 +218,3964 @@ impl BiometricCircuit {
         current_embedding: &[Scalar],
         reference_embedding: &[Scalar],
     ) -> CircuitResult<Vec<u8>> {
-        if current_embedding.len() != self.embedding_size {
-            return Err(CircuitError::InvalidParameter("Invalid embedding size".to_string()));
+        self.prove_distance(current_embedding, reference_embedding, self.proof_transcript(None), None, None)
+    }
+    
+    /// Quantize float embeddings with `scale_factor` and prove their similarity
+    ///
+    /// The scale factor is recorded in the public inputs, so the verifier can
+    /// quantize its reference identically with `verify_proof_from_floats`.
+    /// The probe is committed under random blindings; the reference, which
+    /// that verifier already holds in the clear, under zero blinding, and only
+    /// the probe commitments are carried in the proof.
+    #[cfg(feature = "std")]
+    pub fn generate_proof_from_floats(
+        &self,
+        current_embedding: &[f64],
+        reference_embedding: &[f64],
+        scale_factor: i64,
+    ) -> CircuitResult<BiometricProof> {
+        let current = BiometricEmbedding::from_floats(current_embedding.to_vec(), scale_factor)?;
+        let reference = BiometricEmbedding::from_floats(reference_embedding.to_vec(), scale_factor)?;
+        
+        let mut blindings = Self::random_blindings(self.embedding_size, &mut rand::rngs::OsRng);
+        blindings.resize(2 * self.embedding_size, ScalarUtils::zero());
+        let mut proof = self.prove_before(&current, &reference, &blindings, &[], ProofStamp::now(), None)?;
+        
+        proof.commitments.truncate(self.embedding_size);
+        if !self.bind_commitment_hash {
+            proof.public_inputs.commitment_hash = BiometricProof::hash_commitments(&proof.commitments);
+        }
+        Ok(proof.with_scale_factor(scale_factor))
+    }
+    
+    /// Prove similarity of two embeddings and package the result
+    ///
+    /// The returned proof carries the proof bytes, the compressed commitments
+    /// (current embedding first, then reference), their hash, and metadata,
+    /// ready to serialize and check with `verify`. Every coordinate is
+    /// committed under a fresh random blinding, so the published commitments
+    /// do not reveal the embeddings; use `prove_enrolled` for proofs that
+    /// `verify_enrolled` can tie to a stored reference.
+    ///
+    /// Under `SquaredDistance`, a pair farther apart than the threshold fails
+    /// with `ThresholdExceeded` before any proving work is done. Embeddings
+    /// recorded as quantized at different scales are an `InvalidEmbedding`.
+    #[cfg(feature = "std")]
+    pub fn prove(&self, current: &BiometricEmbedding, reference: &BiometricEmbedding) -> CircuitResult<BiometricProof> {
+        self.prove_with_rng(current, reference, &mut rand::rngs::OsRng)
+    }
+    
+    /// `prove` with the commitment blindings drawn from `rng`
+    pub fn prove_with_rng<R: RngCore + CryptoRng>(
+        &self,
+        current: &BiometricEmbedding,
+        reference: &BiometricEmbedding,
+        rng: &mut R,
+    ) -> CircuitResult<BiometricProof> {
+        let blindings = Self::random_blindings(2 * self.embedding_size, rng);
+        self.prove_before(current, reference, &blindings, &[], ProofStamp::now(), None)
+    }
+    
+    /// `prove`, bound to a verifier-issued freshness `challenge`
+    ///
+    /// The challenge is absorbed into the transcript and recorded in the
+    /// public inputs; `verify_with_challenge` rejects the proof for any other
+    /// challenge, so a captured proof cannot be replayed in a later session.
+    #[cfg(feature = "std")]
+    pub fn prove_with_challenge(
+        &self,
+        current: &BiometricEmbedding,
+        reference: &BiometricEmbedding,
+        challenge: &[u8],
+    ) -> CircuitResult<BiometricProof> {
+        let blindings = Self::random_blindings(2 * self.embedding_size, &mut rand::rngs::OsRng);
+        self.prove_before(current, reference, &blindings, challenge, ProofStamp::now(), None)
+    }
+    
+    /// `prove`, abandoned with `ProofGenerationFailed` once `deadline` passes
+    ///
+    /// The deadline is checked before each coordinate's constraints are added
+    /// and once more before the proof itself is computed, so a caller can give
+    /// up on a proof, e.g. when the app is backgrounded, without waiting for
+    /// the whole circuit. The final proving step is not interruptible.
+    #[cfg(feature = "std")]
+    pub fn prove_with_deadline(
+        &self,
+        current: &BiometricEmbedding,
+        reference: &BiometricEmbedding,
+        deadline: Instant,
+    ) -> CircuitResult<BiometricProof> {
+        let blindings = Self::random_blindings(2 * self.embedding_size, &mut rand::rngs::OsRng);
+        self.prove_before(current, reference, &blindings, &[], ProofStamp::now(), Some(deadline))
+    }
+    
+    /// Prove similarity to an enrolled reference and package the result
+    ///
+    /// The reference is committed under the blindings in `opening`, so the
+    /// proof carries exactly the commitments `enroll` returned and
+    /// `verify_enrolled` accepts it; the probe gets fresh random blindings.
+    #[cfg(feature = "std")]
+    pub fn prove_enrolled(
+        &self,
+        current: &BiometricEmbedding,
+        reference: &BiometricEmbedding,
+        opening: &EnrollmentOpening,
+    ) -> CircuitResult<BiometricProof> {
+        self.prove_enrolled_with_rng(current, reference, opening, &mut rand::rngs::OsRng)
+    }
+    
+    /// `prove_enrolled` with the probe blindings drawn from `rng`
+    pub fn prove_enrolled_with_rng<R: RngCore + CryptoRng>(
+        &self,
+        current: &BiometricEmbedding,
+        reference: &BiometricEmbedding,
+        opening: &EnrollmentOpening,
+        rng: &mut R,
+    ) -> CircuitResult<BiometricProof> {
+        let blindings = self.enrolled_blindings(opening, rng)?;
+        self.prove_before(current, reference, &blindings, &[], ProofStamp::now(), None)
+    }
+    
+    /// `prove_enrolled_with_rng`, stamped with an explicit `timestamp` and
+    /// the capture's `liveness_score`
+    ///
+    /// Both are absorbed into the transcript and recorded in the proof, so
+    /// `verify_with_policy` can rely on them: a proof whose timestamp or
+    /// liveness score is changed afterwards no longer verifies. Without `std`
+    /// this is the only way to give a packaged proof a timestamp.
+    pub fn prove_enrolled_at<R: RngCore + CryptoRng>(
+        &self,
+        current: &BiometricEmbedding,
+        reference: &BiometricEmbedding,
+        opening: &EnrollmentOpening,
+        timestamp: u64,
+        liveness_score: Option<u64>,
+        rng: &mut R,
+    ) -> CircuitResult<BiometricProof> {
+        let blindings = self.enrolled_blindings(opening, rng)?;
+        self.prove_before(current, reference, &blindings, &[], ProofStamp { timestamp, liveness_score }, None)
+    }
+    
+    /// `prove_scalars` against an enrolled reference
+    ///
+    /// The reference is committed under the blindings in `opening`, as in
+    /// `prove_enrolled`, and the device binding is recorded as in
+    /// `prove_scalars`.
+    #[cfg(feature = "std")]
+    pub fn prove_enrolled_scalars(
+        &self,
+        current_embedding: &[Scalar],
+        reference_embedding: &[Scalar],
+        opening: &EnrollmentOpening,
+        device_binding: Option<&[u8]>,
+    ) -> CircuitResult<BiometricProof> {
+        let blindings = self.enrolled_blindings(opening, &mut rand::rngs::OsRng)?;
+        self.prove_blinded_scalars(current_embedding, reference_embedding, &blindings, device_binding)
+    }
+    
+    /// Fresh probe blindings followed by the enrolled reference blindings
+    fn enrolled_blindings<R: RngCore + CryptoRng>(
+        &self,
+        opening: &EnrollmentOpening,
+        rng: &mut R,
+    ) -> CircuitResult<Zeroizing<Vec<Scalar>>> {
+        if opening.embedding_size() != self.embedding_size {
+            return Err(CircuitError::ParameterMismatch(
+                format!(
+                    "Enrollment opening has {} blindings, circuit embedding size is {}",
+                    opening.embedding_size(),
+                    self.embedding_size
+                )
+            ));
         }
         
-        // Create transcript
-        let mut transcript = Transcript::new(b"biometric_proof");
-        let mut prover = Prover::new(&self.pedersen_gens, &mut transcript);
+        let mut blindings = Self::random_blindings(self.embedding_size, rng);
+        blindings.extend(opening.blindings()?.iter());
+        Ok(blindings)
+    }
+    
+    /// `prove_with_challenge` under explicit commitment `blindings` and
+    /// `stamp`, checking `deadline` while building constraints
+    ///
+    /// `blindings` holds one scalar per committed coordinate, current
+    /// embedding first, then reference.
+    fn prove_before(
+        &self,
+        current: &BiometricEmbedding,
+        reference: &BiometricEmbedding,
+        blindings: &[Scalar],
+        challenge: &[u8],
+        stamp: ProofStamp,
+        deadline: Option<Deadline>,
+    ) -> CircuitResult<BiometricProof> {
+        Self::check_deadline(deadline)?;
+        crate::utils::ValidationUtils::validate_same_scale(current, reference)?;
+        if self.metric == SimilarityMetric::SquaredDistance {
+            ProofWitness::new(current.data.clone(), reference.data.clone(), Vec::new())?
+                .validate_threshold(self.threshold)?;
+        }
         
-        // Commit to embedding values
-        let mut current_vars = Vec::new();
-        let mut reference_vars = Vec::new();
+        let current = Zeroizing::new(FieldUtils::embedding_to_scalars(&current.data)?);
+        let reference = Zeroizing::new(FieldUtils::embedding_to_scalars(&reference.data)?);
+        let mut transcript = self.bound_transcript(None, challenge);
+        stamp.bind(&mut transcript);
+        let commitment_hash = self.commitment_binding(&current, &reference)?;
+        let (proof, commitments) =
+            self.prove_blinded_distance(&current, &reference, blindings, transcript, commitment_hash, deadline)?;
+        
+        let mut proof = self.package(proof, commitments, commitment_hash, stamp);
+        proof.public_inputs.challenge = challenge.to_vec();
+        Ok(proof)
+    }
+    
+    /// Prove similarity of embeddings already in the field and package the result
+    ///
+    /// A device binding is absorbed into the transcript and recorded in the
+    /// public inputs, so `verify` checks the proof against it. Commitments are
+    /// blinded as in `prove`.
+    #[cfg(feature = "std")]
+    pub fn prove_scalars(
+        &self,
+        current_embedding: &[Scalar],
+        reference_embedding: &[Scalar],
+        device_binding: Option<&[u8]>,
+    ) -> CircuitResult<BiometricProof> {
+        self.prove_scalars_with_rng(current_embedding, reference_embedding, device_binding, &mut rand::rngs::OsRng)
+    }
+    
+    /// `prove_scalars` with the commitment blindings drawn from `rng`
+    pub fn prove_scalars_with_rng<R: RngCore + CryptoRng>(
+        &self,
+        current_embedding: &[Scalar],
+        reference_embedding: &[Scalar],
+        device_binding: Option<&[u8]>,
+        rng: &mut R,
+    ) -> CircuitResult<BiometricProof> {
+        let blindings = Self::random_blindings(2 * self.embedding_size, rng);
+        self.prove_blinded_scalars(current_embedding, reference_embedding, &blindings, device_binding)
+    }
+    
+    /// `prove_scalars` under explicit commitment `blindings`, current first
+    fn prove_blinded_scalars(
+        &self,
+        current_embedding: &[Scalar],
+        reference_embedding: &[Scalar],
+        blindings: &[Scalar],
+        device_binding: Option<&[u8]>,
+    ) -> CircuitResult<BiometricProof> {
+        let stamp = ProofStamp::now();
+        let mut transcript = self.proof_transcript(device_binding);
+        stamp.bind(&mut transcript);
+        let commitment_hash = self.commitment_binding(current_embedding, reference_embedding)?;
+        let (proof, commitments) = self.prove_blinded_distance(
+            current_embedding,
+            reference_embedding,
+            blindings,
+            transcript,
+            commitment_hash,
+            None,
+        )?;
+        
+        let mut proof = self.package(proof, commitments, commitment_hash, stamp);
+        proof.public_inputs.device_binding = device_binding.map(<[u8]>::to_vec);
+        Ok(proof)
+    }
+    
+    /// Prove similarity of embeddings of any size via chunking and package the result
+    ///
+    /// Runs `generate_chunked_proof`, so embeddings larger than
+    /// `MAX_EMBEDDING_SIZE` can be proven with a circuit sized to them. The
+    /// partial distance commitments follow the embedding commitments, and the
+    /// chunk size and count are recorded in the metadata for `verify`.
+    #[cfg(feature = "std")]
+    pub fn prove_chunked(
+        &self,
+        current_embedding: &[Scalar],
+        reference_embedding: &[Scalar],
+        chunk_size: usize,
+    ) -> CircuitResult<BiometricProof> {
+        self.prove_chunked_with_rng(current_embedding, reference_embedding, chunk_size, &mut rand::rngs::OsRng)
+    }
+    
+    /// `prove_chunked` with the embedding and partial-commitment blindings
+    /// drawn from `rng`
+    pub fn prove_chunked_with_rng<R: RngCore + CryptoRng>(
+        &self,
+        current_embedding: &[Scalar],
+        reference_embedding: &[Scalar],
+        chunk_size: usize,
+        rng: &mut R,
+    ) -> CircuitResult<BiometricProof> {
+        let stamp = ProofStamp::now();
+        let blindings = Self::random_blindings(2 * self.embedding_size, rng);
+        let (chunked, mut commitments) =
+            self.chunked_proof(current_embedding, reference_embedding, &blindings, chunk_size, Some(stamp), rng)?;
+        let chunk_count = chunked.partial_commitments.len();
         
-        for i in 0..self.embedding_size {
-            let (_, curr_var) = prover.commit(current_embedding[i], Scalar::zero());
-            let (_, ref_var) = prover.commit(reference_embedding[i], Scalar::zero());
-            current_vars.push(curr_var);
-            reference_vars.push(ref_var);
+        for partial in &chunked.partial_commitments {
+            commitments.push(SerializableCommitment::from_bytes(partial)?);
         }
         
-        // Use distance gadget
-        let _distance_var = BiometricGadgets::distance_gadget(
-            &mut prover,
-            &current_vars,
-            &reference_vars,
+        let mut proof = self.package(chunked.proof, commitments, None, stamp);
+        proof.metadata.chunk_size = Some(chunk_size);
+        proof.metadata.chunk_count = Some(chunk_count);
+        Ok(proof)
+    }
+    
+    /// Verify a proof produced by `prove`
+    ///
+    /// The public inputs must describe this circuit, the recorded circuit
+    /// parameters must be consistent with them, and the commitment hash must
+    /// match the carried commitments; a device binding recorded in the public
+    /// inputs is checked as part of the transcript. Proofs from
+    /// `prove_in_range` are checked against their recorded distance range.
+    pub fn verify(&self, proof: &BiometricProof) -> CircuitResult<bool> {
+        Self::check_proof_params(
+            proof,
+            self.embedding_size,
+            self.threshold,
+            self.commitment_scheme.epoch,
+            self.range_bits,
+            self.bind_commitment_hash,
         )?;
         
-        // Generate proof
-        let proof = prover.prove(&self.bulletproof_gens).map_err(|_| {
+        let inputs = &proof.public_inputs;
+        if let Some((min_distance, max_distance)) = inputs.distance_range {
+            return self.verify_range(proof, min_distance, max_distance);
+        }
+        let Some(chunk_size) = proof.metadata.chunk_size else {
+            let commitments = Self::decode_commitments(&proof.commitments);
+            return self.verify_distance(
+                &proof.proof,
+                &commitments,
+                inputs.device_binding.as_deref(),
+                &inputs.challenge,
+                Self::bound_commitment_hash(proof, self.bind_commitment_hash)?,
+                Some(ProofStamp::of(proof)),
+            );
+        };
+        
+        if inputs.device_binding.is_some() {
+            return Err(CircuitError::InvalidParameter(
+                "Chunked proofs cannot carry a device binding".to_string()
+            ));
+        }
+        
+        if !inputs.challenge.is_empty() {
+            return Err(CircuitError::InvalidParameter(
+                "Chunked proofs cannot carry a challenge".to_string()
+            ));
+        }
+        
+        let embedding_count = (self.embedding_size * 2).min(proof.commitments.len());
+        let (embedding, partials) = proof.commitments.split_at(embedding_count);
+        if proof.metadata.chunk_count != Some(partials.len()) {
+            return Err(CircuitError::ProofVerificationFailed(
+                format!("Proof records {:?} chunks but carries {}", proof.metadata.chunk_count, partials.len())
+            ));
+        }
+        
+        let chunked = ChunkedProof {
+            proof: proof.proof.clone(),
+            chunk_size,
+            partial_commitments: partials.iter().map(|c| c.as_bytes().to_vec()).collect(),
+        };
+        self.check_chunked_proof(&chunked, &Self::decode_commitments(embedding), Some(ProofStamp::of(proof)))
+    }
+    
+    /// Checks `verify` makes before touching the proof itself
+    ///
+    /// The proof's version must be supported, the public inputs must describe
+    /// the verifying circuit, the recorded circuit parameters must be
+    /// consistent with them, and the commitment hash must match the carried
+    /// commitments unless it is bound in-circuit instead.
+    pub(crate) fn check_proof_params(
+        proof: &BiometricProof,
+        embedding_size: usize,
+        threshold: u64,
+        generator_epoch: Option<u64>,
+        range_bits: usize,
+        bind_commitment_hash: bool,
+    ) -> CircuitResult<()> {
+        if !proof.is_version_supported() {
+            return Err(CircuitError::ProofVerificationFailed(
+                format!("Unsupported proof version {:?}", proof.metadata.version)
+            ));
+        }
+        
+        let inputs = &proof.public_inputs;
+        if inputs.embedding_size != embedding_size || inputs.threshold != threshold {
+            return Err(CircuitError::InvalidParameter(
+                format!(
+                    "Proof is for embedding size {} and threshold {}, circuit has {} and {}",
+                    inputs.embedding_size, inputs.threshold, embedding_size, threshold
+                )
+            ));
+        }
+        
+        if inputs.generator_epoch != generator_epoch {
+            return Err(CircuitError::InvalidParameter(
+                format!("Proof uses generator epoch {:?}, circuit has {:?}", inputs.generator_epoch, generator_epoch)
+            ));
+        }
+        
+        crate::utils::ValidationUtils::validate_circuit_params(proof)?;
+        if proof.metadata.circuit_params.range_bits != range_bits {
+            return Err(CircuitError::ParameterMismatch(
+                format!(
+                    "Proof uses {} range bits, circuit has {}",
+                    proof.metadata.circuit_params.range_bits, range_bits
+                )
+            ));
+        }
+        
+        if Self::bound_commitment_hash(proof, bind_commitment_hash)?.is_none() && !proof.verify_commitment_hash()? {
+            return Err(CircuitError::InvalidCommitment("Commitment hash mismatch".to_string()));
+        }
+        
+        Ok(())
+    }
+    
+    /// Verify a proof produced by `prove_with_challenge`
+    ///
+    /// The proof must carry exactly the challenge the verifier issued; a
+    /// mismatch is a `ProofVerificationFailed` error. Otherwise as `verify`.
+    pub fn verify_with_challenge(&self, proof: &BiometricProof, expected_challenge: &[u8]) -> CircuitResult<bool> {
+        proof.public_inputs.check_challenge(expected_challenge)?;
+        self.verify(proof)
+    }
+    
+    /// Verify many packaged proofs against this circuit
+    ///
+    /// Generators sized for the circuit are built once and shared by every
+    /// proof. The r1cs verifier has no multi-proof batch check, so each proof
+    /// is still checked on its own (in parallel with the `rayon` feature).
+    /// Returns one result per proof, in order; a proof that is malformed or
+    /// describes another circuit yields `false` rather than failing the batch.
+    pub fn verify_batch(&self, proofs: &[BiometricProof]) -> CircuitResult<Vec<bool>> {
+        let shared = BiometricCircuit {
+            embedding_size: self.embedding_size,
+            threshold: self.threshold,
+            pedersen_gens: self.pedersen_gens,
+            bulletproof_gens: self.gens_for(self.metric_multipliers())?,
+            commitment_scheme: self.commitment_scheme.clone(),
+            metric: self.metric,
+            range_bits: self.range_bits,
+            domain: self.domain.clone(),
+            bind_commitment_hash: self.bind_commitment_hash,
+            fixed_gens: self.fixed_gens,
+        };
+        let verify_one = |proof: &BiometricProof| shared.verify(proof).unwrap_or(false);
+        
+        #[cfg(feature = "rayon")]
+        let results = {
+            use rayon::prelude::*;
+            proofs.par_iter().map(verify_one).collect()
+        };
+        
+        #[cfg(not(feature = "rayon"))]
+        let results = proofs.iter().map(verify_one).collect();
+        
+        Ok(results)
+    }
+    
+    /// Verify a packaged proof against enrolled reference commitments under a policy
+    ///
+    /// The policy's threshold must match both the circuit and the proof, its
+    /// metric the circuit's; the proof must be no older than the maximum age
+    /// and carry a liveness score of at least the floor, when those are set.
+    /// Both fields are bound into the proof's transcript by the prover (see
+    /// `prove_enrolled_at`), so altering either makes `verify` fail.
+    /// Policy violations are errors. A proof over another reference, or one
+    /// that fails `verify`, yields `false`.
+    pub fn verify_with_policy(
+        &self,
+        proof: &BiometricProof,
+        reference: &[CompressedRistretto],
+        policy: &VerificationPolicy,
+    ) -> CircuitResult<bool> {
+        if policy.threshold != self.threshold || policy.threshold != proof.public_inputs.threshold {
+            return Err(CircuitError::ParameterMismatch(
+                format!(
+                    "Policy threshold {} does not match circuit threshold {} and proof threshold {}",
+                    policy.threshold, self.threshold, proof.public_inputs.threshold
+                )
+            ));
+        }
+        
+        if policy.metric != self.metric {
+            return Err(CircuitError::ParameterMismatch(
+                format!("Policy requires {:?}, circuit uses {:?}", policy.metric, self.metric)
+            ));
+        }
+        
+        if let Some(max_age) = policy.max_proof_age {
+            let now = crate::types::proof_data::current_timestamp().ok_or_else(|| {
+                CircuitError::InvalidParameter("Proof age checks need the `std` system clock".to_string())
+            })?;
+            let age = now.saturating_sub(proof.metadata.timestamp);
+            if age > max_age {
+                return Err(CircuitError::ProofVerificationFailed(
+                    format!("Proof is {} seconds old, policy allows {}", age, max_age)
+                ));
+            }
+        }
+        
+        if let Some(min_liveness) = policy.min_liveness {
+            match proof.public_inputs.liveness_score {
+                Some(score) if score >= min_liveness => {}
+                Some(score) => {
+                    return Err(CircuitError::ProofVerificationFailed(
+                        format!("Liveness score {} is below the policy floor {}", score, min_liveness)
+                    ));
+                }
+                None => {
+                    return Err(CircuitError::ProofVerificationFailed(
+                        "Proof does not record a liveness score".to_string()
+                    ));
+                }
+            }
+        }
+        
+        let expected: Vec<SerializableCommitment> = reference.iter().copied().map(SerializableCommitment).collect();
+        if !self.commits_to_reference(proof, &expected) {
+            return Ok(false);
+        }
+        
+        self.verify(proof)
+    }
+    
+    /// Commit to a reference embedding for storage at enrollment
+    ///
+    /// Every coordinate is committed under a fresh random blinding. The
+    /// commitment bundle goes to the verifier, which checks later proofs
+    /// against it with `verify_enrolled`; the opening stays on the enrolling
+    /// device, where `prove_enrolled` needs it.
+    #[cfg(feature = "std")]
+    pub fn enroll(&self, reference: &BiometricEmbedding) -> CircuitResult<(EnrollmentCommitment, EnrollmentOpening)> {
+        self.enroll_with_rng(reference, &mut rand::rngs::OsRng)
+    }
+    
+    /// `enroll` with the blindings drawn from `rng`
+    pub fn enroll_with_rng<R: RngCore + CryptoRng>(
+        &self,
+        reference: &BiometricEmbedding,
+        rng: &mut R,
+    ) -> CircuitResult<(EnrollmentCommitment, EnrollmentOpening)> {
+        if reference.data.len() != self.embedding_size {
+            return Err(CircuitError::InvalidParameter("Invalid embedding size".to_string()));
+        }
+        
+        let reference_scalars = Zeroizing::new(FieldUtils::embedding_to_scalars(&reference.data)?);
+        let blindings = Self::random_blindings(self.embedding_size, rng);
+        let commitments = reference_scalars
+            .iter()
+            .zip(blindings.iter())
+            .map(|(v, blinding)| self.pedersen_gens.commit(*v, *blinding).into())
+            .collect();
+        
+        Ok((
+            EnrollmentCommitment::new(commitments, reference.scale_factor, self.commitment_scheme.epoch),
+            EnrollmentOpening::new(&blindings),
+        ))
+    }
+    
+    /// Verify a packaged proof against an enrollment from `enroll`
+    ///
+    /// The proof's reference commitments must be exactly the stored blinded
+    /// ones, which only a `prove_enrolled` proof with the matching opening
+    /// carries. An enrollment whose hash no longer matches its commitments, or made
+    /// for another embedding size, generator epoch or scale, is an error. A
+    /// proof over another reference, or one that fails `verify`, yields
+    /// `false`.
+    pub fn verify_enrolled(&self, proof: &BiometricProof, enrollment: &EnrollmentCommitment) -> CircuitResult<bool> {
+        enrollment.verify_commitment_hash()?;
+        
+        if enrollment.embedding_size() != self.embedding_size {
+            return Err(CircuitError::ParameterMismatch(
+                format!(
+                    "Enrollment has {} commitments, circuit embedding size is {}",
+                    enrollment.embedding_size(),
+                    self.embedding_size
+                )
+            ));
+        }
+        
+        if enrollment.generator_epoch != self.commitment_scheme.epoch {
+            return Err(CircuitError::ParameterMismatch(
+                format!(
+                    "Enrollment generator epoch {:?} does not match circuit epoch {:?}",
+                    enrollment.generator_epoch, self.commitment_scheme.epoch
+                )
+            ));
+        }
+        
+        if let (Some(enrolled), Some(proved)) = (enrollment.scale_factor, proof.public_inputs.scale_factor) {
+            if enrolled != proved {
+                return Err(CircuitError::InvalidEmbedding(
+                    format!("Enrollment quantized with scale {}, proof with {}", enrolled, proved)
+                ));
+            }
+        }
+        
+        if !self.commits_to_reference(proof, &enrollment.reference_commitments) {
+            return Ok(false);
+        }
+        
+        self.verify(proof)
+    }
+    
+    /// Whether the proof's reference commitments are exactly `reference`
+    fn commits_to_reference(&self, proof: &BiometricProof, reference: &[SerializableCommitment]) -> bool {
+        proof.commitments.get(self.embedding_size..2 * self.embedding_size) == Some(reference)
+    }
+    
+    /// Generate a proof bound to a device attestation hash
+    ///
+    /// The binding is absorbed into the transcript, so the proof only verifies
+    /// when the verifier is given the same binding.
+    pub fn generate_proof_with_device_binding(
+        &self,
+        current_embedding: &[Scalar],
+        reference_embedding: &[Scalar],
+        device_binding: &[u8],
+    ) -> CircuitResult<Vec<u8>> {
+        self.prove_distance(current_embedding, reference_embedding, self.proof_transcript(Some(device_binding)), None, None)
+    }
+    
+    /// Generate a proof tagged with a linking tag for `context`
+    ///
+    /// The tag is `LinkingTag::derive` of the reference embedding and
+    /// context. It is absorbed into the transcript and constrained in-circuit
+    /// against the committed reference, so `verify_linkable_proof` only
+    /// accepts the tag of the reference the proof is over, and proofs over the
+    /// same reference and context can be linked by comparing tags. Costs
+    /// `poseidon_multipliers(n + 1)` extra multipliers.
+    #[cfg(feature = "poseidon")]
+    pub fn generate_linkable_proof(
+        &self,
+        current_embedding: &[Scalar],
+        reference_embedding: &[Scalar],
+        context: &[u8],
+    ) -> CircuitResult<LinkableProof> {
+        self.require_squared_distance()?;
+        
+        if current_embedding.len() != self.embedding_size || reference_embedding.len() != self.embedding_size {
+            return Err(CircuitError::InvalidParameter("Invalid embedding size".to_string()));
+        }
+        
+        let distance = Self::witness_distance(current_embedding, reference_embedding)?;
+        if distance > self.threshold {
+            return Err(CircuitError::ThresholdExceeded { expected: self.threshold, actual: distance });
+        }
+        
+        let tag = LinkingTag::derive(reference_embedding, context);
+        let mut transcript = self.linkable_transcript(context, &tag);
+        let mut prover = Prover::new(&self.pedersen_gens, &mut transcript);
+        
+        let (current_vars, reference_vars) =
+            Self::commit_embeddings(&mut prover, current_embedding, reference_embedding);
+        Self::bound_coordinates(&mut prover, &current_vars, &reference_vars, Some((current_embedding, reference_embedding)))?;
+        BiometricGadgets::linking_tag_gadget(&mut prover, &reference_vars, LinkingTag::context_scalar(context), tag)?;
+        
+        let distance_var = BiometricGadgets::distance_gadget(&mut prover, &current_vars, &reference_vars)?;
+        BiometricConstraints::add_threshold_constraint_with_bits(&mut prover, distance_var, self.threshold, self.range_bits)?;
+        
+        let bulletproof_gens = self.gens_for(self.linkable_multipliers())?;
+        let proof = prover.prove(&bulletproof_gens).map_err(|_| {
             CircuitError::ProofGenerationFailed("Failed to generate proof".to_string())
         })?;
         
-        Ok(proof.to_bytes())
+        Ok(LinkableProof {
+            proof: proof.to_bytes(),
+            context: context.to_vec(),
+            tag: tag.to_bytes().to_vec(),
+        })
     }
     
-    /// Verify a biometric proof
-    pub fn verify_proof(
+    /// Verify a linkable proof against the zero-blinding embedding commitments
+    ///
+    /// The tag must be a canonical scalar encoding; anything else is an error.
+    /// A proof whose tag is not the one derived from the committed reference
+    /// under its context, or that fails the threshold, yields `Ok(false)`.
+    #[cfg(feature = "poseidon")]
+    pub fn verify_linkable_proof(
         &self,
-        proof_bytes: &[u8],
+        linkable: &LinkableProof,
         public_commitments: &[CompressedRistretto],
     ) -> CircuitResult<bool> {
-        // Create transcript for verification
-        let mut transcript = Transcript::new(b"biometric_proof");
+        self.require_squared_distance()?;
+        
+        let tag = <[u8; 32]>::try_from(linkable.tag.as_slice())
+            .ok()
+            .map(Scalar::from_bytes_mod_order)
+            .filter(|tag| tag.as_bytes()[..] == linkable.tag[..])
+            .ok_or_else(|| CircuitError::ProofVerificationFailed("Linking tag is not a canonical scalar".to_string()))?;
+        let proof = R1CSProof::from_bytes(&linkable.proof).map_err(|_| {
+            CircuitError::ProofVerificationFailed("Malformed proof bytes".to_string())
+        })?;
+        
+        let mut transcript = self.linkable_transcript(&linkable.context, &tag);
         let mut verifier = Verifier::new(&mut transcript);
         
-        // Commit to public values
-        for commitment in public_commitments {
-            let _var = verifier.commit(*commitment);
-        }
+        let (current_vars, reference_vars) =
+            Self::commit_public_embeddings(&mut verifier, public_commitments, self.embedding_size)
+                .ok_or_else(|| CircuitError::ProofVerificationFailed(
+                    format!("Expected {} commitments, got {}", self.embedding_size * 2, public_commitments.len())
+                ))?;
+        Self::bound_coordinates(&mut verifier, &current_vars, &reference_vars, None)?;
+        BiometricGadgets::linking_tag_gadget(
+            &mut verifier,
+            &reference_vars,
+            LinkingTag::context_scalar(&linkable.context),
+            tag,
+        )?;
+        
+        let distance_var = BiometricGadgets::distance_gadget(&mut verifier, &current_vars, &reference_vars)?;
+        VerificationConstraints::add_threshold_constraint_with_bits(&mut verifier, distance_var, self.threshold, self.range_bits)?;
         
-        // In full implementation, would recreate same constraints and verify
-        // Success
-        Ok(true)
+        let bulletproof_gens = self.gens_for(self.linkable_multipliers())?;
+        Ok(verifier.verify(&proof, &self.pedersen_gens, &bulletproof_gens).is_ok())
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
     
-    #[test]
-    fn test_circuit_creation() {
-        let circuit = BiometricCircuit::new(128, 1000);
-        assert_eq!(circuit.embedding_size, 128);
-        assert_eq!(circuit.threshold, 1000);
+    /// Prove the distance gate over zero-blinding commitments
+    ///
+    /// For the raw-byte APIs, whose callers supply the commitments to verify
+    /// against out of band.
+    fn prove_distance(
+        &self,
+        current_embedding: &[Scalar],
+        reference_embedding: &[Scalar],
+        transcript: Transcript,
+        commitment_hash: Option<Scalar>,
+        deadline: Option<Deadline>,
+    ) -> CircuitResult<Vec<u8>> {
+        let (proof, _) = self.prove_blinded_distance(
+            current_embedding,
+            reference_embedding,
+            &self.zero_blindings(),
+            transcript,
+            commitment_hash,
+            deadline,
+        )?;
+        Ok(proof)
     }
     
-    #[test]
-    fn test_proof_generation() {
-        let circuit = BiometricCircuit::new(4, 100);
-        let current = vec![Scalar::from(1u64), Scalar::from(2u64), Scalar::from(3u64), Scalar::from(4u64)];
-        let reference = vec![Scalar::from(2u64), Scalar::from(3u64), Scalar::from(4u64), Scalar::from(5u64)];
+    /// Prove the distance gate with the embeddings committed under `blindings`
+    ///
+    /// Returns the proof bytes and the commitments, current embedding first.
+    fn prove_blinded_distance(
+        &self,
+        current_embedding: &[Scalar],
+        reference_embedding: &[Scalar],
+        blindings: &[Scalar],
+        mut transcript: Transcript,
+        commitment_hash: Option<Scalar>,
+        deadline: Option<Deadline>,
+    ) -> CircuitResult<(Vec<u8>, Vec<SerializableCommitment>)> {
+        if current_embedding.len() != self.embedding_size || reference_embedding.len() != self.embedding_size {
+            return Err(CircuitError::InvalidParameter("Invalid embedding size".to_string()));
+        }
         
-        let result = circuit.generate_proof(&current, &reference);
-        assert!(result.is_ok());
+        let mut prover = Prover::new(&self.pedersen_gens, &mut transcript);
+        
+        // Commit to embedding values
+        let (current_vars, reference_vars, commitments) =
+            Self::commit_blinded_embeddings(&mut prover, current_embedding, reference_embedding, blindings)?;
+        Self::bound_coordinates_before(
+            &mut prover,
+            &current_vars,
+            &reference_vars,
+            Some((current_embedding, reference_embedding)),
+            deadline,
+        )?;
+        Self::bind_commitment_hash(&mut prover, &current_vars, &reference_vars, commitment_hash)?;
+        
+        match self.metric {
+            SimilarityMetric::SquaredDistance => {
+                // Use distance gadget
+                let distance_var = BiometricGadgets::distance_gadget(
+                    &mut prover,
+                    &current_vars,
+                    &reference_vars,
+                )?;
+                
+                // Enforce distance ≤ threshold
+                BiometricConstraints::add_threshold_constraint_with_bits(
+                    &mut prover,
+                    distance_var,
+                    self.threshold,
+                    self.range_bits,
+                )?;
+            }
+            SimilarityMetric::CosineSimilarity => {
+                let witness = self.cosine_witness(current_embedding, reference_embedding)?;
+                BiometricGadgets::cosine_similarity_gadget(
+                    &mut prover,
+                    &current_vars,
+                    &reference_vars,
+                    self.threshold,
+                    COSINE_SCALE,
+                    Some(witness),
+                )?;
+            }
+        }
+        
+        // Generate proof
+        Self::check_deadline(deadline)?;
+        let bulletproof_gens = self.gens_for(self.metric_multipliers())?;
+        let proof = prover.prove(&bulletproof_gens).map_err(|_| {
+            CircuitError::ProofGenerationFailed("Failed to generate proof".to_string())
+        })?;
+        
+        Ok((proof.to_bytes(), commitments))
+    }
+    
+    /// Generate a session-continuity proof
+    ///
+    /// Proves both `distance(current, reference) ≤ threshold` and
+    /// `distance(current, previous) ≤ continuity_threshold`, so a subject swap
+    /// between frames fails even when the new subject also resembles the
+    /// enrollment. Both thresholds are public.
+    pub fn generate_continuity_proof(
+        &self,
+        current_embedding: &[Scalar],
+        previous_embedding: &[Scalar],
+        reference_embedding: &[Scalar],
+        continuity_threshold: u64,
+    ) -> CircuitResult<ContinuityProof> {
+        self.require_squared_distance()?;
+        
+        if [current_embedding, previous_embedding, reference_embedding]
+            .iter()
+            .any(|embedding| embedding.len() != self.embedding_size)
+        {
+            return Err(CircuitError::InvalidParameter("Invalid embedding size".to_string()));
+        }
+        
+        let mut transcript = self.continuity_transcript(continuity_threshold);
+        let mut prover = Prover::new(&self.pedersen_gens, &mut transcript);
+        
+        let (current_vars, reference_vars) =
+            Self::commit_embeddings(&mut prover, current_embedding, reference_embedding);
+        Self::bound_coordinates(&mut prover, &current_vars, &reference_vars, Some((current_embedding, reference_embedding)))?;
+        
+        let previous_vars: Vec<Variable> = previous_embedding
+            .iter()
+            .enumerate()
+            .map(|(i, value)| {
+                prover.transcript().append_u64(b"previous_coordinate", i as u64);
+                prover.commit(*value, ScalarUtils::zero()).1
+            })
+            .collect();
+        Self::bound_coordinates(&mut prover, &previous_vars, &[], Some((previous_embedding, &[])))?;
+        
+        let enrollment_distance = BiometricGadgets::distance_gadget(&mut prover, &current_vars, &reference_vars)?;
+        BiometricConstraints::add_threshold_constraint(&mut prover, enrollment_distance, self.threshold)?;
+        
+        let continuity_distance = BiometricGadgets::distance_gadget(&mut prover, &current_vars, &previous_vars)?;
+        BiometricConstraints::add_threshold_constraint(&mut prover, continuity_distance, continuity_threshold)?;
+        
+        let bulletproof_gens = self.gens_for(Self::continuity_multipliers(self.embedding_size))?;
+        let proof = prover.prove(&bulletproof_gens).map_err(|_| {
+            CircuitError::ProofGenerationFailed("Failed to generate proof".to_string())
+        })?;
+        
+        Ok(ContinuityProof {
+            proof: proof.to_bytes(),
+            continuity_threshold,
+        })
+    }
+    
+    /// Verify a continuity proof
+    ///
+    /// `public_commitments` are the current and reference commitments as for
+    /// `verify_proof`; `previous_commitments` are the previous probe's.
+    pub fn verify_continuity_proof(
+        &self,
+        continuity: &ContinuityProof,
+        public_commitments: &[CompressedRistretto],
+        previous_commitments: &[CompressedRistretto],
+    ) -> CircuitResult<bool> {
+        self.require_squared_distance()?;
+        
+        if previous_commitments.len() != self.embedding_size {
+            return Err(CircuitError::ProofVerificationFailed(
+                format!("Expected {} previous commitments, got {}", self.embedding_size, previous_commitments.len())
+            ));
+        }
+        
+        let proof = R1CSProof::from_bytes(&continuity.proof).map_err(|_| {
+            CircuitError::ProofVerificationFailed("Malformed proof bytes".to_string())
+        })?;
+        
+        let mut transcript = self.continuity_transcript(continuity.continuity_threshold);
+        let mut verifier = Verifier::new(&mut transcript);
+        
+        let (current_vars, reference_vars) =
+            Self::commit_public_embeddings(&mut verifier, public_commitments, self.embedding_size)
+                .ok_or_else(|| CircuitError::ProofVerificationFailed(
+                    format!(
+                        "Expected {} commitments, got {}",
+                        self.embedding_size * 2,
+                        public_commitments.len()
+                    )
+                ))?;
+        Self::bound_coordinates(&mut verifier, &current_vars, &reference_vars, None)?;
+        
+        let previous_vars: Vec<Variable> = previous_commitments
+            .iter()
+            .enumerate()
+            .map(|(i, commitment)| {
+                verifier.transcript().append_u64(b"previous_coordinate", i as u64);
+                verifier.commit(*commitment)
+            })
+            .collect();
+        Self::bound_coordinates(&mut verifier, &previous_vars, &[], None)?;
+        
+        let enrollment_distance = BiometricGadgets::distance_gadget(&mut verifier, &current_vars, &reference_vars)?;
+        VerificationConstraints::add_threshold_constraint(&mut verifier, enrollment_distance, self.threshold)?;
+        
+        let continuity_distance = BiometricGadgets::distance_gadget(&mut verifier, &current_vars, &previous_vars)?;
+        VerificationConstraints::add_threshold_constraint(&mut verifier, continuity_distance, continuity.continuity_threshold)?;
+        
+        let bulletproof_gens = self.gens_for(Self::continuity_multipliers(self.embedding_size))?;
+        Ok(verifier.verify(&proof, &self.pedersen_gens, &bulletproof_gens).is_ok())
+    }
+    
+    /// Generate one proof that every `(current, reference, threshold)` pair
+    /// matches
+    ///
+    /// Proves `distance(current, reference) ≤ threshold` for each pair in a
+    /// single R1CS proof. Up to `AGGREGATION_SIZE` pairs, each of the
+    /// circuit's embedding size; the per-pair thresholds are public.
+    pub fn generate_aggregate_match_proof(
+        &self,
+        pairs: &[(&[Scalar], &[Scalar], u64)],
+    ) -> CircuitResult<AggregateMatchProof> {
+        self.require_squared_distance()?;
+        Self::check_aggregation_size(pairs.len())?;
+        
+        if pairs
+            .iter()
+            .any(|(current, reference, _)| current.len() != self.embedding_size || reference.len() != self.embedding_size)
+        {
+            return Err(CircuitError::InvalidParameter("Invalid embedding size".to_string()));
+        }
+        
+        let thresholds: Vec<u64> = pairs.iter().map(|(_, _, threshold)| *threshold).collect();
+        let mut transcript = self.aggregate_transcript(&thresholds);
+        let mut prover = Prover::new(&self.pedersen_gens, &mut transcript);
+        
+        for (current, reference, threshold) in pairs {
+            let (current_vars, reference_vars) = Self::commit_embeddings(&mut prover, current, reference);
+            Self::bound_coordinates(&mut prover, &current_vars, &reference_vars, Some((current, reference)))?;
+            
+            let distance = BiometricGadgets::distance_gadget(&mut prover, &current_vars, &reference_vars)?;
+            BiometricConstraints::add_threshold_constraint(&mut prover, distance, *threshold)?;
+        }
+        
+        let bulletproof_gens = self.gens_for(Self::aggregate_multipliers(self.embedding_size, pairs.len()))?;
+        let proof = prover.prove(&bulletproof_gens).map_err(|_| {
+            CircuitError::ProofGenerationFailed("Failed to generate proof".to_string())
+        })?;
+        
+        Ok(AggregateMatchProof {
+            proof: proof.to_bytes(),
+            thresholds,
+        })
+    }
+    
+    /// Verify an aggregate match proof
+    ///
+    /// `public_commitments` holds each pair's current and reference
+    /// commitments, as for `verify_proof`, concatenated in proving order.
+    pub fn verify_aggregate_match_proof(
+        &self,
+        aggregate: &AggregateMatchProof,
+        public_commitments: &[CompressedRistretto],
+    ) -> CircuitResult<bool> {
+        self.require_squared_distance()?;
+        Self::check_aggregation_size(aggregate.thresholds.len())?;
+        
+        let pair_commitments = self.embedding_size * 2;
+        if public_commitments.len() != pair_commitments * aggregate.thresholds.len() {
+            return Err(CircuitError::ProofVerificationFailed(
+                format!(
+                    "Expected {} commitments, got {}",
+                    pair_commitments * aggregate.thresholds.len(),
+                    public_commitments.len()
+                )
+            ));
+        }
+        
+        let proof = R1CSProof::from_bytes(&aggregate.proof).map_err(|_| {
+            CircuitError::ProofVerificationFailed("Malformed proof bytes".to_string())
+        })?;
+        
+        let mut transcript = self.aggregate_transcript(&aggregate.thresholds);
+        let mut verifier = Verifier::new(&mut transcript);
+        
+        for (commitments, threshold) in public_commitments.chunks(pair_commitments).zip(&aggregate.thresholds) {
+            let (current_vars, reference_vars) =
+                Self::commit_public_embeddings(&mut verifier, commitments, self.embedding_size)
+                    .ok_or_else(|| CircuitError::ProofVerificationFailed("Commitment count mismatch".to_string()))?;
+            Self::bound_coordinates(&mut verifier, &current_vars, &reference_vars, None)?;
+            
+            let distance = BiometricGadgets::distance_gadget(&mut verifier, &current_vars, &reference_vars)?;
+            VerificationConstraints::add_threshold_constraint(&mut verifier, distance, *threshold)?;
+        }
+        
+        let bulletproof_gens = self.gens_for(Self::aggregate_multipliers(self.embedding_size, aggregate.thresholds.len()))?;
+        Ok(verifier.verify(&proof, &self.pedersen_gens, &bulletproof_gens).is_ok())
+    }
+    
+    /// Generate a proof that the distance falls in one of the public buckets
+    ///
+    /// Only the matched bucket is revealed, not the exact distance or the
+    /// operator's configured threshold: the transcript is bound to the
+    /// bucket's bounds instead, and `verify_bucketed_proof` checks it.
+    pub fn generate_bucketed_proof(
+        &self,
+        current_embedding: &[Scalar],
+        reference_embedding: &[Scalar],
+        buckets: &ThresholdBuckets,
+    ) -> CircuitResult<BucketedProof> {
+        self.require_squared_distance()?;
+        
+        if current_embedding.len() != self.embedding_size || reference_embedding.len() != self.embedding_size {
+            return Err(CircuitError::InvalidParameter("Invalid embedding size".to_string()));
+        }
+        
+        // Select the bucket from the witness distance
+        let distance = Self::witness_distance(current_embedding, reference_embedding)?;
+        let bucket_index = buckets.bucket_for(distance).ok_or(CircuitError::ThresholdExceeded {
+            expected: *buckets.upper_bounds.last().unwrap_or(&0),
+            actual: distance,
+        })?;
+        let (lower, upper) = buckets.bounds(bucket_index).ok_or_else(|| {
+            CircuitError::InvalidParameter(format!("Unknown bucket {}", bucket_index))
+        })?;
+        
+        // Prove lower ≤ distance ≤ upper for the matched bucket only
+        let proof = self.prove_bounded_distance(
+            current_embedding,
+            reference_embedding,
+            self.bucket_transcript(lower, upper),
+            lower,
+            upper,
+        )?;
+        
+        Ok(BucketedProof {
+            proof,
+            bucket_index,
+            bucket_label: buckets.labels[bucket_index].clone(),
+        })
+    }
+    
+    /// Verify a bucketed proof against the public bucket table and the
+    /// zero-blinding embedding commitments
+    ///
+    /// The proof is checked against the bounds of the bucket it claims, so
+    /// claiming any other bucket fails with `Ok(false)`. A bucket index
+    /// outside the table or a label that does not match it is an error.
+    pub fn verify_bucketed_proof(
+        &self,
+        bucketed: &BucketedProof,
+        buckets: &ThresholdBuckets,
+        public_commitments: &[CompressedRistretto],
+    ) -> CircuitResult<bool> {
+        self.require_squared_distance()?;
+        
+        let (lower, upper) = buckets.bounds(bucketed.bucket_index).ok_or_else(|| {
+            CircuitError::ProofVerificationFailed(format!("Unknown bucket {}", bucketed.bucket_index))
+        })?;
+        if buckets.labels[bucketed.bucket_index] != bucketed.bucket_label {
+            return Err(CircuitError::ProofVerificationFailed(
+                format!("Bucket {} is not labelled {:?}", bucketed.bucket_index, bucketed.bucket_label)
+            ));
+        }
+        
+        self.check_bounded_distance(
+            &bucketed.proof,
+            public_commitments,
+            self.bucket_transcript(lower, upper),
+            lower,
+            upper,
+        )
+    }
+    
+    /// Prove `lower ≤ distance ≤ upper` over zero-blinding commitments
+    fn prove_bounded_distance(
+        &self,
+        current_embedding: &[Scalar],
+        reference_embedding: &[Scalar],
+        mut transcript: Transcript,
+        lower: u64,
+        upper: u64,
+    ) -> CircuitResult<Vec<u8>> {
+        let mut prover = Prover::new(&self.pedersen_gens, &mut transcript);
+        
+        let (current_vars, reference_vars) =
+            Self::commit_embeddings(&mut prover, current_embedding, reference_embedding);
+        
+        Self::bound_coordinates(&mut prover, &current_vars, &reference_vars, Some((current_embedding, reference_embedding)))?;
+        
+        let distance_var = BiometricGadgets::distance_gadget(
+            &mut prover,
+            &current_vars,
+            &reference_vars,
+        )?;
+        BiometricConstraints::add_interval_constraint(&mut prover, distance_var, lower, upper)?;
+        
+        let bulletproof_gens = self.gens_for(Self::range_multipliers(self.embedding_size))?;
+        let proof = prover.prove(&bulletproof_gens).map_err(|_| {
+            CircuitError::ProofGenerationFailed("Failed to generate proof".to_string())
+        })?;
+        Ok(proof.to_bytes())
+    }
+    
+    /// Check a proof of `lower ≤ distance ≤ upper` made under `transcript`
+    ///
+    /// Malformed proof bytes or a commitment count that does not match the
+    /// circuit are errors; a well-formed proof that fails to verify is `Ok(false)`.
+    fn check_bounded_distance(
+        &self,
+        proof_bytes: &[u8],
+        public_commitments: &[CompressedRistretto],
+        mut transcript: Transcript,
+        lower: u64,
+        upper: u64,
+    ) -> CircuitResult<bool> {
+        let r1cs_proof = R1CSProof::from_bytes(proof_bytes).map_err(|_| {
+            CircuitError::ProofVerificationFailed("Malformed proof bytes".to_string())
+        })?;
+        let mut verifier = Verifier::new(&mut transcript);
+        
+        let (current_vars, reference_vars) =
+            Self::commit_public_embeddings(&mut verifier, public_commitments, self.embedding_size)
+                .ok_or_else(|| CircuitError::ProofVerificationFailed(
+                    format!("Expected {} commitments, got {}", self.embedding_size * 2, public_commitments.len())
+                ))?;
+        Self::bound_coordinates(&mut verifier, &current_vars, &reference_vars, None)?;
+        
+        let distance_var = BiometricGadgets::distance_gadget(&mut verifier, &current_vars, &reference_vars)?;
+        VerificationConstraints::add_interval_constraint(&mut verifier, distance_var, lower, upper)?;
+        
+        let bulletproof_gens = self.gens_for(Self::range_multipliers(self.embedding_size))?;
+        Ok(verifier.verify(&r1cs_proof, &self.pedersen_gens, &bulletproof_gens).is_ok())
+    }
+    
+    /// Generate a proof that `low < distance < high`
+    ///
+    /// Both bounds are public and bound into the transcript; the exact
+    /// distance stays hidden. A lower bound lets analytics reject probes that
+    /// are suspiciously identical to the reference, such as a replayed
+    /// template. Check the result with `verify_interval_proof`.
+    pub fn generate_interval_proof(
+        &self,
+        current_embedding: &[Scalar],
+        reference_embedding: &[Scalar],
+        low: u64,
+        high: u64,
+    ) -> CircuitResult<IntervalProof> {
+        self.require_squared_distance()?;
+        
+        if current_embedding.len() != self.embedding_size || reference_embedding.len() != self.embedding_size {
+            return Err(CircuitError::InvalidParameter("Invalid embedding size".to_string()));
+        }
+        
+        Self::check_interval(low, high)?;
+        
+        let distance = Self::witness_distance(current_embedding, reference_embedding)?;
+        if distance >= high {
+            return Err(CircuitError::ThresholdExceeded { expected: high, actual: distance });
+        }
+        if distance <= low {
+            return Err(CircuitError::ProofGenerationFailed(
+                format!("Distance is not above the lower bound {}", low)
+            ));
+        }
+        
+        // Strict bounds on integers: low + 1 ≤ distance ≤ high - 1
+        let proof = self.prove_bounded_distance(
+            current_embedding,
+            reference_embedding,
+            self.interval_transcript(low, high),
+            low + 1,
+            high - 1,
+        )?;
+        
+        Ok(IntervalProof {
+            proof,
+            low,
+            high,
+        })
+    }
+    
+    /// Verify an interval proof against the zero-blinding embedding commitments
+    ///
+    /// The proof is checked against the bounds it records, which are bound
+    /// into its transcript, so a proof presented with any other interval
+    /// yields `Ok(false)`. An interval containing no integer is an error.
+    pub fn verify_interval_proof(
+        &self,
+        interval: &IntervalProof,
+        public_commitments: &[CompressedRistretto],
+    ) -> CircuitResult<bool> {
+        self.require_squared_distance()?;
+        Self::check_interval(interval.low, interval.high)?;
+        
+        self.check_bounded_distance(
+            &interval.proof,
+            public_commitments,
+            self.interval_transcript(interval.low, interval.high),
+            interval.low + 1,
+            interval.high - 1,
+        )
+    }
+    
+    /// Reject an open interval `(low, high)` with no integer distance in it
+    fn check_interval(low: u64, high: u64) -> CircuitResult<()> {
+        if high <= low.saturating_add(1) {
+            return Err(CircuitError::InvalidParameter(
+                format!("Interval ({}, {}) contains no integer distance", low, high)
+            ));
+        }
+        Ok(())
+    }
+    
+    /// Prove `min_distance ≤ distance ≤ max_distance` and package the result
+    ///
+    /// Like `prove`, but the distance is also bounded from below, so a probe
+    /// that matches the reference too perfectly, such as a replayed template,
+    /// is rejected along with one that is too far. Both bounds are recorded in
+    /// the public inputs and `max_distance` may not exceed the circuit
+    /// threshold. A distance above the range fails with `ThresholdExceeded`,
+    /// one below it with `ProofGenerationFailed`, before any proving work.
+    /// Commitments are blinded as in `prove`.
+    #[cfg(feature = "std")]
+    pub fn prove_in_range(
+        &self,
+        current: &BiometricEmbedding,
+        reference: &BiometricEmbedding,
+        min_distance: u64,
+        max_distance: u64,
+    ) -> CircuitResult<BiometricProof> {
+        self.prove_in_range_with_rng(current, reference, min_distance, max_distance, &mut rand::rngs::OsRng)
+    }
+    
+    /// `prove_in_range` with the commitment blindings drawn from `rng`
+    pub fn prove_in_range_with_rng<R: RngCore + CryptoRng>(
+        &self,
+        current: &BiometricEmbedding,
+        reference: &BiometricEmbedding,
+        min_distance: u64,
+        max_distance: u64,
+        rng: &mut R,
+    ) -> CircuitResult<BiometricProof> {
+        self.require_squared_distance()?;
+        self.check_distance_range(min_distance, max_distance)?;
+        crate::utils::ValidationUtils::validate_same_scale(current, reference)?;
+        
+        let current = FieldUtils::embedding_to_scalars(&current.data)?;
+        let reference = FieldUtils::embedding_to_scalars(&reference.data)?;
+        if current.len() != self.embedding_size || reference.len() != self.embedding_size {
+            return Err(CircuitError::InvalidParameter("Invalid embedding size".to_string()));
+        }
+        
+        let distance = Self::witness_distance(&current, &reference)?;
+        if distance > max_distance {
+            return Err(CircuitError::ThresholdExceeded { expected: max_distance, actual: distance });
+        }
+        if distance < min_distance {
+            return Err(CircuitError::ProofGenerationFailed(
+                format!("Distance is below the lower bound {}", min_distance)
+            ));
+        }
+        
+        let stamp = ProofStamp::now();
+        let mut transcript = self.range_transcript(None, &[], min_distance, max_distance);
+        stamp.bind(&mut transcript);
+        let mut prover = Prover::new(&self.pedersen_gens, &mut transcript);
+        
+        let blindings = Self::random_blindings(2 * self.embedding_size, rng);
+        let (current_vars, reference_vars, commitments) =
+            Self::commit_blinded_embeddings(&mut prover, &current, &reference, &blindings)?;
+        Self::bound_coordinates(&mut prover, &current_vars, &reference_vars, Some((&current, &reference)))?;
+        
+        let distance_var = BiometricGadgets::distance_gadget(&mut prover, &current_vars, &reference_vars)?;
+        BiometricConstraints::add_interval_constraint(&mut prover, distance_var, min_distance, max_distance)?;
+        
+        let bulletproof_gens = self.gens_for(Self::range_multipliers(self.embedding_size))?;
+        let proof = prover.prove(&bulletproof_gens).map_err(|_| {
+            CircuitError::ProofGenerationFailed("Failed to generate proof".to_string())
+        })?;
+        
+        let mut proof = self.package(proof.to_bytes(), commitments, None, stamp);
+        proof.public_inputs.distance_range = Some((min_distance, max_distance));
+        Ok(proof)
+    }
+    
+    /// Check a `prove_in_range` proof against the range it records
+    ///
+    /// The range must be one `prove_in_range` accepts for this circuit.
+    fn verify_range(&self, proof: &BiometricProof, min_distance: u64, max_distance: u64) -> CircuitResult<bool> {
+        self.require_squared_distance()?;
+        self.check_distance_range(min_distance, max_distance)?;
+        
+        let inputs = &proof.public_inputs;
+        let mut transcript =
+            self.range_transcript(inputs.device_binding.as_deref(), &inputs.challenge, min_distance, max_distance);
+        ProofStamp::of(proof).bind(&mut transcript);
+        self.check_bounded_distance(
+            &proof.proof,
+            &Self::decode_commitments(&proof.commitments),
+            transcript,
+            min_distance,
+            max_distance,
+        )
+    }
+    
+    /// Generate a proof over an embedding split into chunks of `chunk_size`
+    ///
+    /// Each chunk's partial squared distance is committed separately and the
+    /// partials are summed under one `distance ≤ threshold` constraint, so the
+    /// result verifies as a single proof with `verify_chunked_proof`.
+    #[cfg(feature = "std")]
+    pub fn generate_chunked_proof(
+        &self,
+        current_embedding: &[Scalar],
+        reference_embedding: &[Scalar],
+        chunk_size: usize,
+    ) -> CircuitResult<ChunkedProof> {
+        self.generate_chunked_proof_with_rng(current_embedding, reference_embedding, chunk_size, &mut rand::rngs::OsRng)
+    }
+    
+    /// `generate_chunked_proof` with partial-commitment blindings drawn from `rng`
+    ///
+    /// A seeded RNG makes the partial commitments reproducible; the proof
+    /// bytes are not, since the Bulletproofs prover adds its own randomness.
+    pub fn generate_chunked_proof_with_rng<R: RngCore + CryptoRng>(
+        &self,
+        current_embedding: &[Scalar],
+        reference_embedding: &[Scalar],
+        chunk_size: usize,
+        rng: &mut R,
+    ) -> CircuitResult<ChunkedProof> {
+        let (chunked, _) =
+            self.chunked_proof(current_embedding, reference_embedding, &self.zero_blindings(), chunk_size, None, rng)?;
+        Ok(chunked)
+    }
+    
+    /// Chunked proof with the embeddings committed under `blindings`, bound
+    /// to `stamp` when packaged
+    ///
+    /// Returns the proof and the embedding commitments, current first.
+    fn chunked_proof<R: RngCore + CryptoRng>(
+        &self,
+        current_embedding: &[Scalar],
+        reference_embedding: &[Scalar],
+        blindings: &[Scalar],
+        chunk_size: usize,
+        stamp: Option<ProofStamp>,
+        rng: &mut R,
+    ) -> CircuitResult<(ChunkedProof, Vec<SerializableCommitment>)> {
+        self.require_squared_distance()?;
+        
+        if current_embedding.len() != self.embedding_size || reference_embedding.len() != self.embedding_size {
+            return Err(CircuitError::InvalidParameter("Invalid embedding size".to_string()));
+        }
+        
+        if chunk_size == 0 {
+            return Err(CircuitError::InvalidParameter("Chunk size must be positive".to_string()));
+        }
+        
+        let distance = Self::witness_distance(current_embedding, reference_embedding)?;
+        if distance > self.threshold {
+            return Err(CircuitError::ThresholdExceeded { expected: self.threshold, actual: distance });
+        }
+        
+        let mut transcript = self.chunked_transcript(chunk_size);
+        if let Some(stamp) = stamp {
+            stamp.bind(&mut transcript);
+        }
+        let mut prover = Prover::new(&self.pedersen_gens, &mut transcript);
+        
+        let (current_vars, reference_vars, commitments) =
+            Self::commit_blinded_embeddings(&mut prover, current_embedding, reference_embedding, blindings)?;
+        
+        Self::bound_coordinates(&mut prover, &current_vars, &reference_vars, Some((current_embedding, reference_embedding)))?;
+        
+        let mut partial_commitments = Vec::new();
+        let mut total_lc = LinearCombination::default();
+        
+        for (chunk, (current_chunk, reference_chunk)) in current_vars
+            .chunks(chunk_size)
+            .zip(reference_vars.chunks(chunk_size))
+            .enumerate()
+        {
+            let range = chunk * chunk_size..(chunk * chunk_size + current_chunk.len());
+            let partial = Self::witness_distance(&current_embedding[range.clone()], &reference_embedding[range])?;
+            
+            let partial_lc = BiometricGadgets::squared_distance_gadget(&mut prover, current_chunk, reference_chunk)?;
+            // The prover wipes its own copy of the blinding when dropped
+            let mut blinding = ScalarUtils::random(rng);
+            let (partial_commitment, partial_var) = prover.commit(Scalar::from(partial), blinding);
+            blinding.zeroize();
+            prover.constrain(partial_lc - partial_var);
+            
+            total_lc = total_lc + partial_var;
+            partial_commitments.push(partial_commitment.to_bytes().to_vec());
+        }
+        
+        // threshold - Σ partials ∈ [0, 2^RANGE_BITS)
+        BiometricGadgets::range_gadget(
+            &mut prover,
+            LinearCombination::from(Scalar::from(self.threshold)) - total_lc,
+            Some(self.threshold - distance),
+            RANGE_BITS,
+        )?;
+        
+        let bulletproof_gens = self.gens_for(Self::coordinate_multipliers(self.embedding_size) + self.embedding_size + RANGE_BITS)?;
+        let proof = prover.prove(&bulletproof_gens).map_err(|_| {
+            CircuitError::ProofGenerationFailed("Failed to generate proof".to_string())
+        })?;
+        
+        let chunked = ChunkedProof {
+            proof: proof.to_bytes(),
+            chunk_size,
+            partial_commitments,
+        };
+        Ok((chunked, commitments))
+    }
+    
+    /// Verify a chunked proof against the embedding commitments
+    ///
+    /// Returns `Ok(false)` when the proof does not verify and an error when it
+    /// is malformed.
+    pub fn verify_chunked_proof(
+        &self,
+        chunked: &ChunkedProof,
+        public_commitments: &[CompressedRistretto],
+    ) -> CircuitResult<bool> {
+        self.check_chunked_proof(chunked, public_commitments, None)
+    }
+    
+    /// `verify_chunked_proof`, with the transcript bound to a packaged proof's `stamp`
+    fn check_chunked_proof(
+        &self,
+        chunked: &ChunkedProof,
+        public_commitments: &[CompressedRistretto],
+        stamp: Option<ProofStamp>,
+    ) -> CircuitResult<bool> {
+        self.require_squared_distance()?;
+        
+        if chunked.chunk_size == 0 {
+            return Err(CircuitError::ProofVerificationFailed("Chunk size must be positive".to_string()));
+        }
+        
+        if chunked.partial_commitments.len() != self.embedding_size.div_ceil(chunked.chunk_size) {
+            return Err(CircuitError::ProofVerificationFailed(
+                format!("Expected one partial commitment per chunk, got {}", chunked.partial_commitments.len())
+            ));
+        }
+        
+        let proof = R1CSProof::from_bytes(&chunked.proof).map_err(|_| {
+            CircuitError::ProofVerificationFailed("Malformed proof bytes".to_string())
+        })?;
+        
+        let partial_commitments = chunked
+            .partial_commitments
+            .iter()
+            .map(|bytes| {
+                bytes.as_slice().try_into().map(CompressedRistretto).map_err(|_| {
+                    CircuitError::InvalidCommitment(format!("Invalid partial commitment length: {}", bytes.len()))
+                })
+            })
+            .collect::<CircuitResult<Vec<_>>>()?;
+        
+        let mut transcript = self.chunked_transcript(chunked.chunk_size);
+        if let Some(stamp) = stamp {
+            stamp.bind(&mut transcript);
+        }
+        let mut verifier = Verifier::new(&mut transcript);
+        
+        let Some((current_vars, reference_vars)) =
+            Self::commit_public_embeddings(&mut verifier, public_commitments, self.embedding_size)
+        else {
+            return Ok(false);
+        };
+        Self::bound_coordinates(&mut verifier, &current_vars, &reference_vars, None)?;
+        
+        let mut total_lc = LinearCombination::default();
+        
+        for ((current_chunk, reference_chunk), partial_commitment) in current_vars
+            .chunks(chunked.chunk_size)
+            .zip(reference_vars.chunks(chunked.chunk_size))
+            .zip(&partial_commitments)
+        {
+            let partial_lc = BiometricGadgets::squared_distance_gadget(&mut verifier, current_chunk, reference_chunk)?;
+            let partial_var = verifier.commit(*partial_commitment);
+            verifier.constrain(partial_lc - partial_var);
+            
+            total_lc = total_lc + partial_var;
+        }
+        
+        BiometricGadgets::range_gadget(
+            &mut verifier,
+            LinearCombination::from(Scalar::from(self.threshold)) - total_lc,
+            None,
+            RANGE_BITS,
+        )?;
+        
+        let bulletproof_gens = self.gens_for(Self::coordinate_multipliers(self.embedding_size) + self.embedding_size + RANGE_BITS)?;
+        Ok(verifier.verify(&proof, &self.pedersen_gens, &bulletproof_gens).is_ok())
+    }
+    
+    /// Verify a biometric proof
+    pub fn verify_proof(
+        &self,
+        proof_bytes: &[u8],
+        public_commitments: &[CompressedRistretto],
+    ) -> CircuitResult<bool> {
+        self.verify_distance(proof_bytes, public_commitments, None, &[], None, None)
+    }
+    
+    /// Verify a proof against the device binding carried in its public inputs
+    ///
+    /// When `expected_binding` is set the public inputs must carry that exact
+    /// binding, so a proof produced on one device is rejected for another.
+    pub fn verify_proof_with_device_binding(
+        &self,
+        proof_bytes: &[u8],
+        public_commitments: &[CompressedRistretto],
+        public_inputs: &ProofPublicInputs,
+        expected_binding: Option<&[u8]>,
+    ) -> CircuitResult<bool> {
+        public_inputs.check_device_binding(expected_binding)?;
+        self.verify_distance(
+            proof_bytes,
+            public_commitments,
+            public_inputs.device_binding.as_deref(),
+            &public_inputs.challenge,
+            None,
+            None,
+        )
+    }
+    
+    /// Rebuild the constraints of `prove_distance` and check the proof against them
+    ///
+    /// Malformed proof bytes or a commitment count that does not match the
+    /// circuit are errors; a well-formed proof that fails to verify is `Ok(false)`.
+    /// Packaged proofs pass their `stamp`, raw proof bytes none.
+    fn verify_distance(
+        &self,
+        proof_bytes: &[u8],
+        public_commitments: &[CompressedRistretto],
+        device_binding: Option<&[u8]>,
+        challenge: &[u8],
+        commitment_hash: Option<Scalar>,
+        stamp: Option<ProofStamp>,
+    ) -> CircuitResult<bool> {
+        let bulletproof_gens = self.gens_for(self.metric_multipliers())?;
+        let mut transcript = self.bound_transcript(device_binding, challenge);
+        if let Some(stamp) = stamp {
+            stamp.bind(&mut transcript);
+        }
+        Self::check_distance_proof(
+            proof_bytes,
+            public_commitments,
+            transcript,
+            DistanceStatement {
+                embedding_size: self.embedding_size,
+                threshold: self.threshold,
+                metric: self.metric,
+                range_bits: self.range_bits,
+                commitment_hash,
+            },
+            &self.pedersen_gens,
+            &bulletproof_gens,
+        )
+    }
+    
+    /// `verify_distance` against explicit parameters and generators
+    pub(crate) fn check_distance_proof(
+        proof_bytes: &[u8],
+        public_commitments: &[CompressedRistretto],
+        mut transcript: Transcript,
+        statement: DistanceStatement,
+        pedersen_gens: &PedersenGens,
+        bulletproof_gens: &BulletproofGens,
+    ) -> CircuitResult<bool> {
+        let proof = R1CSProof::from_bytes(proof_bytes).map_err(|_| {
+            CircuitError::ProofVerificationFailed("Malformed proof bytes".to_string())
+        })?;
+        
+        let mut verifier = Verifier::new(&mut transcript);
+        
+        // Replay the prover's commitment order
+        let (current_vars, reference_vars) =
+            Self::commit_public_embeddings(&mut verifier, public_commitments, statement.embedding_size)
+                .ok_or_else(|| CircuitError::ProofVerificationFailed(
+                    format!(
+                        "Expected {} commitments, got {}",
+                        statement.embedding_size * 2,
+                        public_commitments.len()
+                    )
+                ))?;
+        Self::bound_coordinates(&mut verifier, &current_vars, &reference_vars, None)?;
+        Self::bind_commitment_hash(&mut verifier, &current_vars, &reference_vars, statement.commitment_hash)?;
+        
+        // Same gadgets as the prover
+        match statement.metric {
+            SimilarityMetric::SquaredDistance => {
+                let distance_var = BiometricGadgets::distance_gadget(
+                    &mut verifier,
+                    &current_vars,
+                    &reference_vars,
+                )?;
+                VerificationConstraints::add_threshold_constraint_with_bits(
+                    &mut verifier,
+                    distance_var,
+                    statement.threshold,
+                    statement.range_bits,
+                )?;
+            }
+            SimilarityMetric::CosineSimilarity => {
+                BiometricGadgets::cosine_similarity_gadget(
+                    &mut verifier,
+                    &current_vars,
+                    &reference_vars,
+                    statement.threshold,
+                    COSINE_SCALE,
+                    None,
+                )?;
+            }
+        }
+        
+        Ok(verifier.verify(&proof, pedersen_gens, bulletproof_gens).is_ok())
+    }
+    
+    /// Generate a proof that a blend of squared distance and cosine distance
+    /// is within `threshold`
+    ///
+    /// With `alpha = alpha_numerator / alpha_denominator`, proves
+    /// `alpha · distance + (1 - alpha) · (COSINE_SCALE - score) ≤ threshold`,
+    /// where `score = floor(cos · COSINE_SCALE)`. Both scores are computed in
+    /// the circuit; the weights and threshold are public in the result.
+    pub fn generate_fused_proof(
+        &self,
+        probe: &[Scalar],
+        reference: &[Scalar],
+        alpha_numerator: u64,
+        alpha_denominator: u64,
+        threshold: u64,
+    ) -> CircuitResult<FusedProof> {
+        if probe.len() != self.embedding_size || reference.len() != self.embedding_size {
+            return Err(CircuitError::InvalidParameter("Invalid embedding size".to_string()));
+        }
+        Self::check_fusion_weights(alpha_numerator, alpha_denominator)?;
+        
+        let distance = Self::witness_distance(probe, reference)?;
+        let witness = Self::cosine_score_witness(probe, reference)?;
+        
+        let fused = alpha_numerator as u128 * distance as u128
+            + (alpha_denominator - alpha_numerator) as u128 * (COSINE_SCALE - witness.score) as u128;
+        let limit = threshold as u128 * alpha_denominator as u128;
+        if fused > limit {
+            return Err(CircuitError::ThresholdExceeded {
+                expected: threshold,
+                actual: u64::try_from(fused.div_ceil(alpha_denominator as u128)).unwrap_or(u64::MAX),
+            });
+        }
+        let slack = u64::try_from(limit - fused).map_err(|_| {
+            CircuitError::InvalidParameter("Fused threshold too large".to_string())
+        })?;
+        
+        let mut transcript = self.fused_transcript(alpha_numerator, alpha_denominator, threshold);
+        let mut prover = Prover::new(&self.pedersen_gens, &mut transcript);
+        
+        let (current_vars, reference_vars) = Self::commit_embeddings(&mut prover, probe, reference);
+        
+        Self::bound_coordinates(&mut prover, &current_vars, &reference_vars, Some((probe, reference)))?;
+        let distance_lc = BiometricGadgets::squared_distance_gadget(&mut prover, &current_vars, &reference_vars)?;
+        let score_lc = BiometricGadgets::cosine_score_gadget(
+            &mut prover,
+            &current_vars,
+            &reference_vars,
+            COSINE_SCALE,
+            Some(witness),
+        )?;
+        BiometricGadgets::range_gadget(
+            &mut prover,
+            Self::fused_slack(distance_lc, score_lc, alpha_numerator, alpha_denominator, threshold),
+            Some(slack),
+            64,
+        )?;
+        
+        let bulletproof_gens = self.gens_for(Self::fused_multipliers(self.embedding_size))?;
+        let proof = prover.prove(&bulletproof_gens).map_err(|_| {
+            CircuitError::ProofGenerationFailed("Failed to generate proof".to_string())
+        })?;
+        
+        Ok(FusedProof {
+            proof: proof.to_bytes(),
+            alpha_numerator,
+            alpha_denominator,
+            threshold,
+        })
+    }
+    
+    /// Verify a fused proof against the embedding commitments
+    pub fn verify_fused_proof(
+        &self,
+        fused: &FusedProof,
+        public_commitments: &[CompressedRistretto],
+    ) -> CircuitResult<bool> {
+        Self::check_fusion_weights(fused.alpha_numerator, fused.alpha_denominator)?;
+        
+        let proof = R1CSProof::from_bytes(&fused.proof).map_err(|_| {
+            CircuitError::ProofVerificationFailed("Malformed proof bytes".to_string())
+        })?;
+        
+        let mut transcript = self.fused_transcript(fused.alpha_numerator, fused.alpha_denominator, fused.threshold);
+        let mut verifier = Verifier::new(&mut transcript);
+        
+        let (current_vars, reference_vars) =
+            Self::commit_public_embeddings(&mut verifier, public_commitments, self.embedding_size)
+                .ok_or_else(|| CircuitError::ProofVerificationFailed(
+                    format!(
+                        "Expected {} commitments, got {}",
+                        self.embedding_size * 2,
+                        public_commitments.len()
+                    )
+                ))?;
+        Self::bound_coordinates(&mut verifier, &current_vars, &reference_vars, None)?;
+        
+        let distance_lc = BiometricGadgets::squared_distance_gadget(&mut verifier, &current_vars, &reference_vars)?;
+        let score_lc = BiometricGadgets::cosine_score_gadget(
+            &mut verifier,
+            &current_vars,
+            &reference_vars,
+            COSINE_SCALE,
+            None,
+        )?;
+        BiometricGadgets::range_gadget(
+            &mut verifier,
+            Self::fused_slack(distance_lc, score_lc, fused.alpha_numerator, fused.alpha_denominator, fused.threshold),
+            None,
+            64,
+        )?;
+        
+        let bulletproof_gens = self.gens_for(Self::fused_multipliers(self.embedding_size))?;
+        Ok(verifier.verify(&proof, &self.pedersen_gens, &bulletproof_gens).is_ok())
+    }
+    
+    /// Verify a proof against a float reference embedding
+    ///
+    /// The reference is quantized with the scale factor recorded in the proof
+    /// and committed here, so only the proof's probe commitments (the first
+    /// `embedding_size` entries) are taken from the proof itself.
+    pub fn verify_proof_from_floats(&self, proof: &BiometricProof, reference: &[f64]) -> CircuitResult<bool> {
+        let scale_factor = proof.public_inputs.scale_factor.ok_or_else(|| {
+            CircuitError::InvalidParameter("Proof does not record a scale factor".to_string())
+        })?;
+        
+        if reference.len() != self.embedding_size {
+            return Err(CircuitError::InvalidParameter("Invalid embedding size".to_string()));
+        }
+        
+        if proof.commitments.len() < self.embedding_size {
+            return Err(CircuitError::InvalidCommitment(
+                format!("Expected {} probe commitments, got {}", self.embedding_size, proof.commitments.len())
+            ));
+        }
+        
+        let mut commitments = Self::decode_commitments(&proof.commitments[..self.embedding_size]);
+        commitments.extend(
+            Self::quantize(reference, scale_factor)?
+                .iter()
+                .map(|v| self.pedersen_gens.commit(*v, ScalarUtils::zero()).compress()),
+        );
+        
+        self.verify_distance(&proof.proof, &commitments, None, &[], None, Some(ProofStamp::of(proof)))
+    }
+    
+    /// Verify a biometric proof, refusing to start when its declared size
+    /// exceeds `max_constraints`
+    ///
+    /// The work estimate is the number of committed coordinates plus the
+    /// multiplier count implied by the proof's inner-product rounds, both of
+    /// which are known before any group operations are performed.
+    pub fn verify_proof_with_budget(
+        &self,
+        proof_bytes: &[u8],
+        public_commitments: &[CompressedRistretto],
+        max_constraints: usize,
+    ) -> CircuitResult<bool> {
+        let work = Self::declared_work(proof_bytes, public_commitments)?;
+        if work > max_constraints {
+            return Err(CircuitError::ProofVerificationFailed("exceeds work budget".to_string()));
+        }
+        
+        self.verify_proof(proof_bytes, public_commitments)
+    }
+    
+    /// Describe the constraints `generate_proof` builds for this circuit
+    ///
+    /// The description matches the gadgets used by `prove_distance` and
+    /// `verify_distance` gate for gate, for audit and re-implementation.
+    pub fn describe_constraints(&self) -> ConstraintDescription {
+        let n = self.embedding_size;
+        let all: Vec<usize> = (0..2 * n).collect();
+        let group = |kind, count, multipliers_per_gate, bit_length, bound, variables| GateGroup {
+            kind,
+            count,
+            multipliers_per_gate,
+            bit_length,
+            bound,
+            variables,
+        };
+        
+        let mut gates = vec![group(
+            GateKind::Range,
+            2 * n,
+            2 * COORDINATE_RANGE_BITS,
+            Some(COORDINATE_RANGE_BITS),
+            Some(MAX_EMBEDDING_VALUE as u64),
+            all.clone(),
+        )];
+        
+        if self.bind_commitment_hash {
+            // One permutation per two absorbed coordinates
+            gates.push(group(
+                GateKind::Hash,
+                n,
+                Self::binding_multipliers(1, true),
+                None,
+                None,
+                (0..n).map(|i| 2 * i).chain((0..n).map(|i| 2 * i + 1)).collect(),
+            ));
+        }
+        
+        match self.metric {
+            SimilarityMetric::SquaredDistance => {
+                gates.push(group(GateKind::SquaredDifference, n, 1, None, None, all));
+                gates.push(group(GateKind::Sum, 1, 1, None, None, Vec::new()));
+                gates.push(group(GateKind::Threshold, 1, self.range_bits, Some(self.range_bits), Some(self.threshold), Vec::new()));
+            }
+            SimilarityMetric::CosineSimilarity => {
+                // Dot product and both squared norms, then dot ≥ 1
+                gates.push(group(GateKind::Product, 3 * n, 1, None, None, all));
+                gates.push(group(GateKind::Range, 1, 64, Some(64), None, Vec::new()));
+                // dot² and ‖current‖² · ‖reference‖², then the scaled comparison
+                gates.push(group(GateKind::Product, 2, 1, None, None, Vec::new()));
+                gates.push(group(
+                    GateKind::Threshold,
+                    1,
+                    COSINE_RANGE_BITS,
+                    Some(COSINE_RANGE_BITS),
+                    Some(self.threshold),
+                    Vec::new(),
+                ));
+            }
+        }
+        
+        ConstraintDescription {
+            embedding_size: n,
+            threshold: self.threshold,
+            committed_variables: 2 * n,
+            gates,
+        }
+    }
+    
+    /// Estimate the size of the proof `prove` would produce, without proving
+    ///
+    /// Derived from `embedding_size`, the metric and any commitment binding,
+    /// so it is cheap enough to size buffers or a progress indicator before
+    /// starting. See `ProofEstimate` for the margin.
+    pub fn estimate(&self) -> ProofEstimate {
+        let num_multipliers = self.metric_multipliers();
+        // The inner product argument halves the padded gate count each round,
+        // sending two points per round and two final scalars
+        let rounds = num_multipliers.next_power_of_two().trailing_zeros() as usize;
+        // Version byte, then A_I, A_O, S, T_1, T_3..T_6, t_x, t_x_blinding and e_blinding
+        let approx_proof_bytes = 1 + 11 * 32 + (2 * rounds + 2) * 32;
+        
+        ProofEstimate {
+            num_multipliers,
+            approx_proof_bytes,
+            num_commitments: 2 * self.embedding_size,
+        }
+    }
+    
+    /// Transcript for continuity proofs, bound to the continuity threshold
+    fn continuity_transcript(&self, continuity_threshold: u64) -> Transcript {
+        let mut transcript = self.proof_transcript(None);
+        transcript.append_u64(b"continuity_threshold", continuity_threshold);
+        transcript
+    }
+    
+    /// Transcript for aggregate match proofs, bound to the pair count and
+    /// every pair's threshold
+    fn aggregate_transcript(&self, thresholds: &[u64]) -> Transcript {
+        let mut transcript = self.proof_transcript(None);
+        transcript.append_u64(b"aggregate_pairs", thresholds.len() as u64);
+        for threshold in thresholds {
+            transcript.append_u64(b"aggregate_threshold", *threshold);
+        }
+        transcript
+    }
+    
+    /// Transcript for range proofs, bound to both distance bounds
+    fn range_transcript(
+        &self,
+        device_binding: Option<&[u8]>,
+        challenge: &[u8],
+        min_distance: u64,
+        max_distance: u64,
+    ) -> Transcript {
+        let mut transcript = self.bound_transcript(device_binding, challenge);
+        transcript.append_u64(b"min_distance", min_distance);
+        transcript.append_u64(b"max_distance", max_distance);
+        transcript
+    }
+    
+    /// Transcript for bucketed proofs, bound to the matched bucket's bounds
+    ///
+    /// Zero stands in for the circuit threshold, which a bucketed proof does
+    /// not reveal, so it verifies under any circuit of the same size and domain.
+    fn bucket_transcript(&self, lower: u64, upper: u64) -> Transcript {
+        let mut transcript = build_transcript(TRANSCRIPT_LABEL, 0, self.embedding_size, &self.domain, &[]);
+        transcript.append_message(b"dom-sep", b"bucketed_proof");
+        transcript.append_u64(b"bucket_lower", lower);
+        transcript.append_u64(b"bucket_upper", upper);
+        transcript
+    }
+    
+    /// Transcript for linkable proofs, bound to the linking context and tag
+    #[cfg(feature = "poseidon")]
+    fn linkable_transcript(&self, context: &[u8], tag: &Scalar) -> Transcript {
+        let mut transcript = self.proof_transcript(None);
+        transcript.append_message(b"linking_context", context);
+        transcript.append_message(b"linking_tag", tag.as_bytes());
+        transcript
+    }
+    
+    /// Transcript for interval proofs, bound to both exclusive bounds
+    fn interval_transcript(&self, low: u64, high: u64) -> Transcript {
+        let mut transcript = self.proof_transcript(None);
+        transcript.append_u64(b"interval_low", low);
+        transcript.append_u64(b"interval_high", high);
+        transcript
+    }
+    
+    /// Transcript for chunked proofs, bound to the chunk size
+    fn chunked_transcript(&self, chunk_size: usize) -> Transcript {
+        let mut transcript = self.proof_transcript(None);
+        transcript.append_u64(b"chunk_size", chunk_size as u64);
+        transcript
+    }
+    
+    /// Multiplication gates used by `prove` under the circuit's metric,
+    /// including any commitment hash binding
+    fn metric_multipliers(&self) -> usize {
+        Self::multipliers_for(self.embedding_size, self.metric, self.range_bits)
+            + Self::binding_multipliers(self.embedding_size, self.bind_commitment_hash)
+    }
+    
+    /// Multiplication gates `generate_proof` uses for the given configuration
+    pub(crate) fn multipliers_for(embedding_size: usize, metric: SimilarityMetric, range_bits: usize) -> usize {
+        let distance = match metric {
+            // One per coordinate, one for the distance, one per threshold bit
+            SimilarityMetric::SquaredDistance => embedding_size + 1 + range_bits,
+            // Three per coordinate, two for the squared sides, one per bit of
+            // the dot product and of the comparison gap
+            SimilarityMetric::CosineSimilarity => 3 * embedding_size + 2 + 64 + COSINE_RANGE_BITS,
+        };
+        Self::coordinate_multipliers(embedding_size) + distance
+    }
+    
+    /// Bulletproof generator capacity `generate_proof` needs
+    ///
+    /// The multiplier count rounded up to the next power of two.
+    pub fn generator_capacity(embedding_size: usize, metric: SimilarityMetric, range_bits: usize) -> usize {
+        Self::multipliers_for(embedding_size, metric, range_bits).next_power_of_two()
+    }
+    
+    /// Generators for `generate_proof`, capped at `MAX_GENERATOR_CAPACITY`
+    fn sized_gens(embedding_size: usize, metric: SimilarityMetric) -> Arc<BulletproofGens> {
+        Self::shared_gens(Self::generator_capacity(embedding_size, metric, RANGE_BITS).min(MAX_GENERATOR_CAPACITY))
+    }
+    
+    /// Process-wide single-party generators of the given capacity
+    ///
+    /// Built on first use and then shared by every caller asking for the same
+    /// capacity, from any thread, so constructing circuits repeatedly does not
+    /// regenerate them. Capacities above `MAX_GENERATOR_CAPACITY` are built
+    /// fresh each time rather than kept alive, as is every capacity without
+    /// `std`.
+    pub fn shared_gens(capacity: usize) -> Arc<BulletproofGens> {
+        #[cfg(feature = "std")]
+        if capacity <= MAX_GENERATOR_CAPACITY {
+            static CACHE: OnceLock<Mutex<HashMap<usize, Arc<BulletproofGens>>>> = OnceLock::new();
+            
+            // Building under the lock keeps concurrent first uses from each
+            // generating the same capacity; a panic elsewhere cannot corrupt the map
+            let mut cache = CACHE.get_or_init(Default::default).lock().unwrap_or_else(PoisonError::into_inner);
+            return Arc::clone(cache.entry(capacity).or_insert_with(|| Arc::new(BulletproofGens::new(capacity, 1))));
+        }
+        Arc::new(BulletproofGens::new(capacity, 1))
+    }
+    
+    /// Multiplication gates used by `generate_linkable_proof`
+    #[cfg(feature = "poseidon")]
+    fn linkable_multipliers(&self) -> usize {
+        Self::multipliers_for(self.embedding_size, SimilarityMetric::SquaredDistance, self.range_bits)
+            + BiometricGadgets::poseidon_multipliers(self.embedding_size + 1)
+    }
+    
+    /// Multiplication gates used by `prove_in_range`
+    fn range_multipliers(embedding_size: usize) -> usize {
+        // One per coordinate, one for the distance, one holding both gaps and
+        // one per bit of each gap
+        Self::coordinate_multipliers(embedding_size) + embedding_size + 2 + 2 * RANGE_BITS
+    }
+    
+    /// Reject a distance range `prove_in_range` cannot prove under this circuit
+    fn check_distance_range(&self, min_distance: u64, max_distance: u64) -> CircuitResult<()> {
+        if min_distance > max_distance {
+            return Err(CircuitError::InvalidParameter(
+                format!("Distance range [{}, {}] is empty", min_distance, max_distance)
+            ));
+        }
+        if max_distance > self.threshold {
+            return Err(CircuitError::InvalidParameter(
+                format!("Range maximum {} exceeds circuit threshold {}", max_distance, self.threshold)
+            ));
+        }
+        Ok(())
+    }
+    
+    /// Multiplication gates used by `generate_continuity_proof`
+    fn continuity_multipliers(embedding_size: usize) -> usize {
+        // Range checks on the previous probe too, then two distance and
+        // threshold constraints
+        Self::coordinate_multipliers(embedding_size)
+            + 2 * embedding_size * COORDINATE_RANGE_BITS
+            + 2 * (embedding_size + 1 + RANGE_BITS)
+    }
+    
+    /// Multiplication gates used by `generate_aggregate_match_proof`
+    fn aggregate_multipliers(embedding_size: usize, pairs: usize) -> usize {
+        pairs * (Self::coordinate_multipliers(embedding_size) + embedding_size + 1 + RANGE_BITS)
+    }
+    
+    /// An aggregate proof covers between one and `AGGREGATION_SIZE` pairs
+    fn check_aggregation_size(pairs: usize) -> CircuitResult<()> {
+        if !(1..=AGGREGATION_SIZE).contains(&pairs) {
+            return Err(CircuitError::InvalidParameter(
+                format!("Aggregate proofs cover 1 to {} pairs, got {}", AGGREGATION_SIZE, pairs)
+            ));
+        }
+        Ok(())
+    }
+    
+    /// Multiplication gates used by `bound_coordinates`
+    fn coordinate_multipliers(embedding_size: usize) -> usize {
+        // Two decompositions for each of the 2 · embedding_size coordinates
+        4 * embedding_size * COORDINATE_RANGE_BITS
+    }
+    
+    /// Multiplication gates used by `generate_fused_proof`
+    fn fused_multipliers(embedding_size: usize) -> usize {
+        // Squared distance: one per coordinate. Cosine score: three per
+        // coordinate, five for the squared sides and score, 64 + 64 range bits
+        // and two gaps of COSINE_RANGE_BITS. Fused slack: 64 range bits.
+        4 * embedding_size + 5 + 64 + 64 + 2 * COSINE_RANGE_BITS + 64 + Self::coordinate_multipliers(embedding_size)
+    }
+    
+    /// Reject proof kinds that are only defined for squared distance
+    fn require_squared_distance(&self) -> CircuitResult<()> {
+        if self.metric != SimilarityMetric::SquaredDistance {
+            return Err(CircuitError::InvalidParameter(
+                format!("{:?} circuits only support plain proofs", self.metric)
+            ));
+        }
+        Ok(())
+    }
+    
+    /// Prover witness `(dot, gap)` for `cosine_similarity_gadget`
+    ///
+    /// Fails with `ThresholdExceeded` when the similarity is below the
+    /// threshold, reporting the actual similarity in `COSINE_SCALE` units.
+    fn cosine_witness(&self, current_embedding: &[Scalar], reference_embedding: &[Scalar]) -> CircuitResult<(u64, u128)> {
+        if self.threshold > COSINE_SCALE {
+            return Err(CircuitError::InvalidParameter(
+                format!("Cosine threshold {} exceeds scale {}", self.threshold, COSINE_SCALE)
+            ));
+        }
+        
+        let too_large = Self::cosine_overflow;
+        let (dot, current_norm, reference_norm) = Self::cosine_terms(current_embedding, reference_embedding)?;
+        
+        let actual = if dot > 0 {
+            (dot as f64 * COSINE_SCALE as f64 / libm::sqrt((current_norm as f64) * (reference_norm as f64))) as u64
+        } else {
+            0
+        };
+        let below_threshold = CircuitError::ThresholdExceeded { expected: self.threshold, actual };
+        
+        if dot <= 0 {
+            return Err(below_threshold);
+        }
+        let dot = u64::try_from(dot).map_err(|_| too_large())?;
+        
+        let threshold_sq = (self.threshold as u128).pow(2);
+        let lhs = (dot as u128)
+            .checked_mul(dot as u128)
+            .and_then(|d| d.checked_mul((COSINE_SCALE as u128).pow(2)))
+            .ok_or_else(too_large)?;
+        let rhs = current_norm
+            .checked_mul(reference_norm)
+            .and_then(|n| n.checked_mul(threshold_sq))
+            .ok_or_else(too_large)?;
+        
+        lhs.checked_sub(rhs).map(|gap| (dot, gap)).ok_or(below_threshold)
+    }
+    
+    /// Prover witness for `cosine_score_gadget`
+    fn cosine_score_witness(current_embedding: &[Scalar], reference_embedding: &[Scalar]) -> CircuitResult<CosineScoreWitness> {
+        let (dot, current_norm, reference_norm) = Self::cosine_terms(current_embedding, reference_embedding)?;
+        
+        if current_norm == 0 || reference_norm == 0 {
+            return Err(CircuitError::InvalidParameter(
+                "Cosine similarity is undefined for a zero embedding".to_string()
+            ));
+        }
+        if dot < 0 {
+            return Err(CircuitError::ProofGenerationFailed(
+                "Cosine score requires a non-negative dot product".to_string()
+            ));
+        }
+        let dot = u64::try_from(dot).map_err(|_| Self::cosine_overflow())?;
+        
+        let norm_product = current_norm.checked_mul(reference_norm).ok_or_else(Self::cosine_overflow)?;
+        let scaled_dot_sq = (dot as u128)
+            .checked_mul(dot as u128)
+            .and_then(|d| d.checked_mul((COSINE_SCALE as u128).pow(2)))
+            .ok_or_else(Self::cosine_overflow)?;
+        let bound = |score: u64| (score as u128).checked_mul(score as u128).and_then(|s| s.checked_mul(norm_product));
+        
+        // Start from the floating-point estimate and correct it exactly
+        let mut score = (dot as f64 * COSINE_SCALE as f64 / libm::sqrt(norm_product as f64)) as u64;
+        while bound(score).is_none_or(|b| b > scaled_dot_sq) {
+            score -= 1;
+        }
+        while bound(score + 1).is_some_and(|b| b <= scaled_dot_sq) {
+            score += 1;
+        }
+        
+        let lower = bound(score).ok_or_else(Self::cosine_overflow)?;
+        let upper = bound(score + 1).ok_or_else(Self::cosine_overflow)?;
+        Ok(CosineScoreWitness {
+            dot,
+            score,
+            lower_gap: scaled_dot_sq - lower,
+            upper_gap: upper - scaled_dot_sq - 1,
+        })
+    }
+    
+    /// Dot product and squared norms of two embeddings, as integers
+    fn cosine_terms(current_embedding: &[Scalar], reference_embedding: &[Scalar]) -> CircuitResult<(i128, u128, u128)> {
+        let (mut dot, mut current_norm, mut reference_norm) = (0i128, 0u128, 0u128);
+        for (curr, refer) in current_embedding.iter().zip(reference_embedding) {
+            let curr = FieldUtils::scalar_to_i64(curr)? as i128;
+            let refer = FieldUtils::scalar_to_i64(refer)? as i128;
+            dot = dot.checked_add(curr * refer).ok_or_else(Self::cosine_overflow)?;
+            current_norm = current_norm.checked_add((curr * curr) as u128).ok_or_else(Self::cosine_overflow)?;
+            reference_norm = reference_norm.checked_add((refer * refer) as u128).ok_or_else(Self::cosine_overflow)?;
+        }
+        Ok((dot, current_norm, reference_norm))
+    }
+    
+    fn cosine_overflow() -> CircuitError {
+        CircuitError::InvalidParameter("Embedding values too large for cosine similarity".to_string())
+    }
+    
+    /// Check fusion weights: `alpha = numerator / denominator` must lie in `[0, 1]`
+    fn check_fusion_weights(alpha_numerator: u64, alpha_denominator: u64) -> CircuitResult<()> {
+        if alpha_denominator == 0 || alpha_numerator > alpha_denominator {
+            return Err(CircuitError::InvalidParameter(
+                format!("Fusion weight {}/{} is not in [0, 1]", alpha_numerator, alpha_denominator)
+            ));
+        }
+        Ok(())
+    }
+    
+    /// `threshold · d - (n · distance + (d - n) · (COSINE_SCALE - score))`
+    /// for fusion weight `n / d`
+    fn fused_slack(
+        distance: LinearCombination,
+        score: LinearCombination,
+        alpha_numerator: u64,
+        alpha_denominator: u64,
+        threshold: u64,
+    ) -> LinearCombination {
+        let cosine_distance = LinearCombination::from(Scalar::from(COSINE_SCALE)) - score;
+        LinearCombination::from(Scalar::from(threshold) * Scalar::from(alpha_denominator))
+            - distance * Scalar::from(alpha_numerator)
+            - cosine_distance * Scalar::from(alpha_denominator - alpha_numerator)
+    }
+    
+    /// Transcript for fused proofs, bound to the public weights and threshold
+    fn fused_transcript(&self, alpha_numerator: u64, alpha_denominator: u64, threshold: u64) -> Transcript {
+        let mut transcript = self.proof_transcript(None);
+        transcript.append_u64(b"fusion_alpha_numerator", alpha_numerator);
+        transcript.append_u64(b"fusion_alpha_denominator", alpha_denominator);
+        transcript.append_u64(b"fusion_threshold", threshold);
+        transcript
+    }
+    
+    /// Bulletproof generators with room for `multipliers` multiplication gates
+    ///
+    /// Uses the circuit's own generators when they are large enough. Otherwise
+    /// caller-supplied generators (see `fixed_gens`) fail with
+    /// `CapacityExceeded`, and sized ones grow to the shared generators of the
+    /// next power of two.
+    fn gens_for(&self, multipliers: usize) -> CircuitResult<Arc<BulletproofGens>> {
+        let capacity = multipliers.next_power_of_two();
+        if self.bulletproof_gens.gens_capacity >= capacity {
+            Ok(Arc::clone(&self.bulletproof_gens))
+        } else if self.fixed_gens {
+            Err(CircuitError::CapacityExceeded {
+                needed: capacity,
+                available: self.bulletproof_gens.gens_capacity,
+            })
+        } else {
+            Ok(Self::shared_gens(capacity))
+        }
+    }
+    
+    /// Commit to both embeddings coordinate by coordinate, with zero blinding
+    fn commit_embeddings<T>(
+        prover: &mut Prover<T>,
+        current_embedding: &[Scalar],
+        reference_embedding: &[Scalar],
+    ) -> (Vec<Variable>, Vec<Variable>)
+    where
+        T: core::borrow::BorrowMut<Transcript>,
+    {
+        let zero = vec![ScalarUtils::zero(); current_embedding.len() + reference_embedding.len()];
+        let (current_vars, reference_vars, _) =
+            Self::commit_blinded_embeddings(prover, current_embedding, reference_embedding, &zero)
+                .expect("one zero blinding per coordinate");
+        (current_vars, reference_vars)
+    }
+    
+    /// Commit to both embeddings coordinate by coordinate under `blindings`
+    ///
+    /// `blindings` holds the current embedding's blindings, then the
+    /// reference's. Each coordinate index is absorbed into the transcript
+    /// ahead of its current/reference commitment pair, so the proof is bound
+    /// to which point belongs to which coordinate. `commit_public_embeddings`
+    /// replays the same order on the verifier side. The commitments are
+    /// returned current embedding first.
+    fn commit_blinded_embeddings<T>(
+        prover: &mut Prover<T>,
+        current_embedding: &[Scalar],
+        reference_embedding: &[Scalar],
+        blindings: &[Scalar],
+    ) -> CircuitResult<(Vec<Variable>, Vec<Variable>, Vec<SerializableCommitment>)>
+    where
+        T: core::borrow::BorrowMut<Transcript>,
+    {
+        let n = current_embedding.len();
+        if reference_embedding.len() != n || blindings.len() != 2 * n {
+            return Err(CircuitError::InvalidParameter(
+                format!("Expected {} blindings, got {}", 2 * n, blindings.len())
+            ));
+        }
+        let (current_blindings, reference_blindings) = blindings.split_at(n);
+        
+        prover.transcript().append_u64(b"embedding_size", n as u64);
+        
+        let mut current_commitments = Vec::with_capacity(2 * n);
+        let mut reference_commitments = Vec::with_capacity(n);
+        let (current_vars, reference_vars) = current_embedding
+            .iter()
+            .zip(reference_embedding)
+            .zip(current_blindings.iter().zip(reference_blindings))
+            .enumerate()
+            .map(|(i, ((curr, refer), (curr_blinding, ref_blinding)))| {
+                prover.transcript().append_u64(b"coordinate", i as u64);
+                let (curr_commitment, curr_var) = prover.commit(*curr, *curr_blinding);
+                let (ref_commitment, ref_var) = prover.commit(*refer, *ref_blinding);
+                current_commitments.push(SerializableCommitment(curr_commitment));
+                reference_commitments.push(SerializableCommitment(ref_commitment));
+                (curr_var, ref_var)
+            })
+            .unzip();
+        
+        current_commitments.extend(reference_commitments);
+        Ok((current_vars, reference_vars, current_commitments))
+    }
+    
+    /// One zero blinding per committed coordinate of both embeddings
+    fn zero_blindings(&self) -> Vec<Scalar> {
+        vec![ScalarUtils::zero(); 2 * self.embedding_size]
+    }
+    
+    /// `count` fresh random blindings, wiped when dropped
+    fn random_blindings<R: RngCore + CryptoRng>(count: usize, rng: &mut R) -> Zeroizing<Vec<Scalar>> {
+        Zeroizing::new((0..count).map(|_| ScalarUtils::random(rng)).collect())
+    }
+    
+    /// Bound every committed coordinate to `[-MAX_EMBEDDING_VALUE, MAX_EMBEDDING_VALUE]`
+    ///
+    /// Called right after the embeddings are committed, before any distance
+    /// gadget. `assignments` holds the prover's embeddings and is `None` on
+    /// the verifier side; the prover rejects out-of-range values up front.
+    fn bound_coordinates<CS: ConstraintSystem>(
+        cs: &mut CS,
+        current_vars: &[Variable],
+        reference_vars: &[Variable],
+        assignments: Option<(&[Scalar], &[Scalar])>,
+    ) -> CircuitResult<()> {
+        Self::bound_coordinates_before(cs, current_vars, reference_vars, assignments, None)
+    }
+    
+    /// `bound_coordinates`, checking `deadline` before each coordinate
+    fn bound_coordinates_before<CS: ConstraintSystem>(
+        cs: &mut CS,
+        current_vars: &[Variable],
+        reference_vars: &[Variable],
+        assignments: Option<(&[Scalar], &[Scalar])>,
+        deadline: Option<Deadline>,
+    ) -> CircuitResult<()> {
+        let values = match assignments {
+            Some((current, reference)) => current
+                .iter()
+                .chain(reference)
+                .map(|v| {
+                    let value = FieldUtils::scalar_to_i64(v)?;
+                    if value.unsigned_abs() > MAX_EMBEDDING_VALUE as u64 {
+                        return Err(CircuitError::InvalidEmbedding(
+                            format!("Coordinate value {} exceeds ±{}", value, MAX_EMBEDDING_VALUE)
+                        ));
+                    }
+                    Ok(Some(value))
+                })
+                .collect::<CircuitResult<Vec<_>>>()?,
+            None => vec![None; current_vars.len() + reference_vars.len()],
+        };
+        
+        for (var, value) in current_vars.iter().chain(reference_vars).zip(values) {
+            Self::check_deadline(deadline)?;
+            BiometricGadgets::coordinate_range_gadget(
+                cs,
+                *var,
+                MAX_EMBEDDING_VALUE as u64,
+                value,
+                COORDINATE_RANGE_BITS,
+            )?;
+        }
+        Ok(())
+    }
+    
+    /// Fail once `deadline`, if any, has passed
+    ///
+    /// One clock read, negligible next to the bit decomposition it precedes.
+    #[cfg(feature = "std")]
+    fn check_deadline(deadline: Option<Deadline>) -> CircuitResult<()> {
+        match deadline {
+            Some(deadline) if Instant::now() >= deadline => {
+                Err(CircuitError::ProofGenerationFailed("deadline exceeded".to_string()))
+            }
+            _ => Ok(()),
+        }
+    }
+    
+    #[cfg(not(feature = "std"))]
+    fn check_deadline(deadline: Option<Deadline>) -> CircuitResult<()> {
+        match deadline {
+            Some(never) => match never {},
+            None => Ok(()),
+        }
+    }
+    
+    /// Constrain the committed embeddings to hash to `commitment_hash`, when set
+    #[cfg(feature = "poseidon")]
+    fn bind_commitment_hash<CS: ConstraintSystem>(
+        cs: &mut CS,
+        current_vars: &[Variable],
+        reference_vars: &[Variable],
+        commitment_hash: Option<Scalar>,
+    ) -> CircuitResult<()> {
+        match commitment_hash {
+            Some(hash) => BiometricGadgets::commitment_hash_gadget(cs, current_vars, reference_vars, hash),
+            None => Ok(()),
+        }
+    }
+    
+    #[cfg(not(feature = "poseidon"))]
+    fn bind_commitment_hash<CS: ConstraintSystem>(
+        _cs: &mut CS,
+        _current_vars: &[Variable],
+        _reference_vars: &[Variable],
+        commitment_hash: Option<Scalar>,
+    ) -> CircuitResult<()> {
+        match commitment_hash {
+            Some(_) => Err(Self::binding_unavailable()),
+            None => Ok(()),
+        }
+    }
+    
+    /// Commitment hash `prove` binds in-circuit, when the circuit binds one:
+    /// the Poseidon hash of the current then reference coordinates
+    #[cfg(feature = "poseidon")]
+    fn commitment_binding(&self, current_embedding: &[Scalar], reference_embedding: &[Scalar]) -> CircuitResult<Option<Scalar>> {
+        Ok(self
+            .bind_commitment_hash
+            .then(|| CircuitHash::poseidon(&[current_embedding, reference_embedding].concat())))
+    }
+    
+    #[cfg(not(feature = "poseidon"))]
+    fn commitment_binding(&self, _current_embedding: &[Scalar], _reference_embedding: &[Scalar]) -> CircuitResult<Option<Scalar>> {
+        if self.bind_commitment_hash {
+            return Err(Self::binding_unavailable());
+        }
+        Ok(None)
+    }
+    
+    #[cfg(not(feature = "poseidon"))]
+    fn binding_unavailable() -> CircuitError {
+        CircuitError::InvalidParameter("Binding the commitment hash needs the `poseidon` feature".to_string())
+    }
+    
+    /// Commitment hash a packaged proof binds in-circuit
+    ///
+    /// When `bind_commitment_hash` is set, `prove`'s threshold proofs record
+    /// the Poseidon hash of their embeddings, which must be a canonical scalar
+    /// encoding. `None` when the hash is instead checked natively against the
+    /// commitments.
+    pub(crate) fn bound_commitment_hash(proof: &BiometricProof, bind_commitment_hash: bool) -> CircuitResult<Option<Scalar>> {
+        if !bind_commitment_hash || proof.metadata.chunk_size.is_some() || proof.public_inputs.distance_range.is_some() {
+            return Ok(None);
+        }
+        
+        let recorded = &proof.public_inputs.commitment_hash;
+        let bytes: [u8; 32] = recorded.as_slice().try_into().map_err(|_| {
+            CircuitError::InvalidCommitment(format!("Commitment hash has length {}", recorded.len()))
+        })?;
+        let hash = Scalar::from_bytes_mod_order(bytes);
+        if hash.to_bytes() != bytes {
+            return Err(CircuitError::InvalidCommitment("Commitment hash is not a canonical scalar".to_string()));
+        }
+        Ok(Some(hash))
+    }
+    
+    /// Multipliers binding the commitment hash, when `bind_commitment_hash` is set
+    #[cfg(feature = "poseidon")]
+    pub(crate) fn binding_multipliers(embedding_size: usize, bind_commitment_hash: bool) -> usize {
+        if bind_commitment_hash {
+            BiometricGadgets::poseidon_multipliers(2 * embedding_size)
+        } else {
+            0
+        }
+    }
+    
+    #[cfg(not(feature = "poseidon"))]
+    pub(crate) fn binding_multipliers(_embedding_size: usize, _bind_commitment_hash: bool) -> usize {
+        0
+    }
+    
+    /// Verifier counterpart of `commit_embeddings`
+    ///
+    /// `public_commitments` holds the current embedding's commitments followed
+    /// by the reference embedding's. Returns `None` when the count does not
+    /// match the circuit's embedding size.
+    fn commit_public_embeddings<T>(
+        verifier: &mut Verifier<T>,
+        public_commitments: &[CompressedRistretto],
+        embedding_size: usize,
+    ) -> Option<(Vec<Variable>, Vec<Variable>)>
+    where
+        T: core::borrow::BorrowMut<Transcript>,
+    {
+        if public_commitments.len() != embedding_size * 2 {
+            return None;
+        }
+        let (current, reference) = public_commitments.split_at(embedding_size);
+        
+        verifier.transcript().append_u64(b"embedding_size", embedding_size as u64);
+        
+        Some(
+            current
+                .iter()
+                .zip(reference)
+                .enumerate()
+                .map(|(i, (curr, refer))| {
+                    verifier.transcript().append_u64(b"coordinate", i as u64);
+                    (verifier.commit(*curr), verifier.commit(*refer))
+                })
+                .unzip()
+        )
+    }
+    
+    /// Wrap proof bytes and commitments with this circuit's public inputs
+    ///
+    /// Records `bound_hash` as the commitment hash when the proof binds one
+    /// in-circuit, and the hash of the commitments otherwise, and the
+    /// `stamp` the transcript was bound to.
+    fn package(
+        &self,
+        proof: Vec<u8>,
+        commitments: Vec<SerializableCommitment>,
+        bound_hash: Option<Scalar>,
+        stamp: ProofStamp,
+    ) -> BiometricProof {
+        let commitment_hash = match bound_hash {
+            Some(hash) => hash.to_bytes().to_vec(),
+            None => BiometricProof::hash_commitments(&commitments),
+        };
+        let mut proof = BiometricProof::new_at(
+            proof,
+            commitments,
+            self.threshold,
+            self.embedding_size,
+            commitment_hash,
+            stamp.timestamp,
+        );
+        proof.public_inputs.liveness_score = stamp.liveness_score;
+        proof.public_inputs.generator_epoch = self.commitment_scheme.epoch;
+        proof.metadata.circuit_params.range_bits = self.range_bits;
+        proof
+    }
+    
+    /// Quantize a float embedding to field elements
+    fn quantize(embedding: &[f64], scale_factor: i64) -> CircuitResult<Vec<Scalar>> {
+        FieldUtils::embedding_to_scalars(&BiometricEmbedding::from_floats(embedding.to_vec(), scale_factor)?.data)
+    }
+    
+    /// Unwrap proof commitments, already validated when the proof was decoded
+    pub(crate) fn decode_commitments(commitments: &[SerializableCommitment]) -> Vec<CompressedRistretto> {
+        commitments.iter().map(|c| c.0).collect()
+    }
+    
+    /// Squared distance between the witness embeddings
+    fn witness_distance(current_embedding: &[Scalar], reference_embedding: &[Scalar]) -> CircuitResult<u64> {
+        FieldUtils::scalar_to_u64(&FieldUtils::scalar_distance_squared(current_embedding, reference_embedding)?)
+    }
+    
+    /// Transcript shared by the prover and verifier, optionally bound to a device
+    fn proof_transcript(&self, device_binding: Option<&[u8]>) -> Transcript {
+        self.bound_transcript(device_binding, &[])
+    }
+    
+    /// `proof_transcript`, also bound to a freshness challenge
+    fn bound_transcript(&self, device_binding: Option<&[u8]>, challenge: &[u8]) -> Transcript {
+        Self::distance_transcript(self.threshold, self.embedding_size, &self.domain, device_binding, challenge)
+    }
+    
+    /// `bound_transcript` for explicit circuit parameters
+    pub(crate) fn distance_transcript(
+        threshold: u64,
+        embedding_size: usize,
+        domain: &[u8],
+        device_binding: Option<&[u8]>,
+        challenge: &[u8],
+    ) -> Transcript {
+        let mut transcript = build_transcript(TRANSCRIPT_LABEL, threshold, embedding_size, domain, challenge);
+        if let Some(binding) = device_binding {
+            transcript.append_message(b"device_binding", binding);
+        }
+        transcript
+    }
+    
+    /// Work implied by a serialized proof and its public commitments
+    fn declared_work(proof_bytes: &[u8], public_commitments: &[CompressedRistretto]) -> CircuitResult<usize> {
+        // Layout: version byte, 11 or 14 points, IPP (2k points + 2 scalars)
+        let elements = proof_bytes.len().saturating_sub(1);
+        if proof_bytes.is_empty() || !elements.is_multiple_of(32) {
+            return Err(CircuitError::ProofVerificationFailed(
+                format!("Malformed proof length {}", proof_bytes.len())
+            ));
+        }
+        
+        let elements = elements / 32;
+        let fixed = if elements % 2 == 1 { 13 } else { 16 };
+        if elements < fixed {
+            return Err(CircuitError::ProofVerificationFailed(
+                format!("Malformed proof length {}", proof_bytes.len())
+            ));
+        }
+        
+        let rounds = (elements - fixed) / 2;
+        let multipliers = 1usize.checked_shl(rounds as u32).unwrap_or(usize::MAX);
+        
+        Ok(multipliers.saturating_add(public_commitments.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+    use alloc::vec;
+    
+    #[test]
+    fn test_circuit_creation() {
+        let circuit = BiometricCircuit::new(128, 1000);
+        assert_eq!(circuit.embedding_size, 128);
+        assert_eq!(circuit.threshold, 1000);
+    }
+    
+    #[test]
+    fn test_proof_generation() {
+        let circuit = BiometricCircuit::new(4, 100);
+        let current = vec![Scalar::from(1u64), Scalar::from(2u64), Scalar::from(3u64), Scalar::from(4u64)];
+        let reference = vec![Scalar::from(2u64), Scalar::from(3u64), Scalar::from(4u64), Scalar::from(5u64)];
+        
+        let result = circuit.generate_proof(&current, &reference);
+        assert!(result.is_ok());
+    }
+    
+    fn committed(values: &[Scalar]) -> Vec<CompressedRistretto> {
+        let pc_gens = PedersenGens::default();
+        values.iter().map(|v| pc_gens.commit(*v, ScalarUtils::zero()).compress()).collect()
+    }
+    
+    #[test]
+    fn test_verify_within_budget() {
+        let circuit = BiometricCircuit::new(4, 100);
+        let current = vec![Scalar::from(1u64), Scalar::from(2u64), Scalar::from(3u64), Scalar::from(4u64)];
+        let reference = vec![Scalar::from(2u64), Scalar::from(3u64), Scalar::from(4u64), Scalar::from(5u64)];
+        
+        let proof = circuit.generate_proof(&current, &reference).unwrap();
+        let commitments = committed(&[current, reference].concat());
+        
+        assert!(circuit.verify_proof_with_budget(&proof, &commitments, 1024).unwrap());
+    }
+    
+    #[test]
+    fn test_verify_rejects_oversized_declaration() {
+        let circuit = BiometricCircuit::new(4, 100);
+        let current = vec![Scalar::from(1u64), Scalar::from(2u64), Scalar::from(3u64), Scalar::from(4u64)];
+        let reference = current.clone();
+        
+        let proof = circuit.generate_proof(&current, &reference).unwrap();
+        
+        // Declares a 2048-dimensional embedding pair
+        let oversized = vec![CompressedRistretto::default(); 4096];
+        match circuit.verify_proof_with_budget(&proof, &oversized, 1024) {
+            Err(CircuitError::ProofVerificationFailed(msg)) => assert_eq!(msg, "exceeds work budget"),
+            other => panic!("expected budget rejection, got {:?}", other),
+        }
+        
+        // A proof claiming an enormous number of inner-product rounds
+        let mut inflated = proof.clone();
+        inflated.extend(vec![0u8; 64 * 40]);
+        assert!(circuit.verify_proof_with_budget(&inflated, &[], 1024).is_err());
+        
+        // Lengths that cannot be a serialized proof are rejected outright
+        assert!(circuit.verify_proof_with_budget(&proof[..proof.len() - 1], &[], 1024).is_err());
+    }
+    
+    #[test]
+    fn test_bucketed_proof_beyond_last_bucket() {
+        let circuit = BiometricCircuit::new(4, 1000);
+        let buckets = ThresholdBuckets::new(vec![500], vec!["low".to_string()]).unwrap();
+        
+        let current = vec![Scalar::from(20u64), Scalar::from(20u64), Scalar::from(20u64), ScalarUtils::zero()];
+        let reference = vec![ScalarUtils::zero(); 4];
+        
+        let result = circuit.generate_bucketed_proof(&current, &reference, &buckets);
+        assert!(matches!(result, Err(CircuitError::ThresholdExceeded { expected: 500, actual: 1200 })));
+    }
+    
+    #[test]
+    fn test_bucketed_proof_round_trip() {
+        let circuit = BiometricCircuit::new(4, 1000);
+        let buckets = ThresholdBuckets::new(
+            vec![500, 1500, 3000],
+            vec!["low".to_string(), "medium".to_string(), "high".to_string()],
+        ).unwrap();
+        
+        // Squared distance 1200, in the "medium" bucket
+        let current = vec![Scalar::from(20u64), Scalar::from(20u64), Scalar::from(20u64), ScalarUtils::zero()];
+        let reference = vec![ScalarUtils::zero(); 4];
+        let commitments = committed(&[current.clone(), reference.clone()].concat());
+        
+        let bucketed = circuit.generate_bucketed_proof(&current, &reference, &buckets).unwrap();
+        assert_eq!((bucketed.bucket_index, bucketed.bucket_label.as_str()), (1, "medium"));
+        assert!(circuit.verify_bucketed_proof(&bucketed, &buckets, &commitments).unwrap());
+        
+        // The public part carries the label, not the distance or the circuit threshold
+        let public = serde_json::to_string(&(bucketed.bucket_index, &bucketed.bucket_label)).unwrap();
+        assert!(!public.contains("1200"));
+        assert!(!public.contains("1000"));
+        
+        // The threshold is not part of the statement
+        assert!(BiometricCircuit::new(4, 2000).verify_bucketed_proof(&bucketed, &buckets, &commitments).unwrap());
+        
+        // Claiming a neighbouring bucket fails
+        for (index, label) in [(0, "low"), (2, "high")] {
+            let claimed = BucketedProof { bucket_index: index, bucket_label: label.to_string(), ..bucketed.clone() };
+            assert!(!circuit.verify_bucketed_proof(&claimed, &buckets, &commitments).unwrap());
+        }
+        
+        // As does checking it against a table whose bucket 1 has other bounds
+        let shifted = ThresholdBuckets::new(
+            vec![1000, 1500, 3000],
+            vec!["low".to_string(), "medium".to_string(), "high".to_string()],
+        ).unwrap();
+        assert!(!circuit.verify_bucketed_proof(&bucketed, &shifted, &commitments).unwrap());
+        
+        // Or against commitments to another probe
+        let other = committed(&[vec![ScalarUtils::zero(); 4], reference].concat());
+        assert!(!circuit.verify_bucketed_proof(&bucketed, &buckets, &other).unwrap());
+        
+        let relabelled = BucketedProof { bucket_label: "low".to_string(), ..bucketed.clone() };
+        assert!(circuit.verify_bucketed_proof(&relabelled, &buckets, &commitments).is_err());
+        let unknown = BucketedProof { bucket_index: 3, ..bucketed };
+        assert!(circuit.verify_bucketed_proof(&unknown, &buckets, &commitments).is_err());
+    }
+    
+    #[test]
+    fn test_device_bound_proof_rejected_for_other_device() {
+        let circuit = BiometricCircuit::new(4, 100);
+        let current = vec![Scalar::from(1u64), Scalar::from(2u64), Scalar::from(3u64), Scalar::from(4u64)];
+        let reference = vec![Scalar::from(2u64), Scalar::from(3u64), Scalar::from(4u64), Scalar::from(5u64)];
+        let commitments = committed(&[current.clone(), reference.clone()].concat());
+        
+        let device_a = ProofPublicInputs::device_binding_for(b"device-a-attestation");
+        let device_b = ProofPublicInputs::device_binding_for(b"device-b-attestation");
+        
+        let proof = circuit.generate_proof_with_device_binding(&current, &reference, &device_a).unwrap();
+        let public_inputs = ProofPublicInputs {
+            threshold: circuit.threshold,
+            embedding_size: circuit.embedding_size,
+            commitment_hash: vec![0; 32],
+            device_binding: Some(device_a.clone()),
+            generator_epoch: None,
+            scale_factor: None,
+            liveness_score: None,
+            distance_range: None,
+            challenge: Vec::new(),
+        };
+        
+        assert!(circuit
+            .verify_proof_with_device_binding(&proof, &commitments, &public_inputs, Some(&device_a))
+            .unwrap());
+        
+        let result = circuit.verify_proof_with_device_binding(&proof, &commitments, &public_inputs, Some(&device_b));
+        assert!(matches!(result, Err(CircuitError::ProofVerificationFailed(_))));
+        
+        // Relabelling the public inputs with device B's binding breaks the transcript
+        let relabelled = ProofPublicInputs {
+            device_binding: Some(device_b.clone()),
+            ..public_inputs
+        };
+        assert!(!circuit
+            .verify_proof_with_device_binding(&proof, &commitments, &relabelled, Some(&device_b))
+            .unwrap());
+    }
+    
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_prove_blinds_commitments() {
+        let circuit = BiometricCircuit::new(4, 100);
+        let current = BiometricEmbedding::new(vec![10, -20, 30, 40]).unwrap();
+        let reference = BiometricEmbedding::new(vec![12, -20, 31, 40]).unwrap();
+        
+        let first = circuit.prove(&current, &reference).unwrap();
+        let second = circuit.prove(&current, &reference).unwrap();
+        assert!(circuit.verify(&first).unwrap());
+        assert!(circuit.verify(&second).unwrap());
+        
+        // Fresh blindings per proof: nothing to brute-force or link on
+        let unblinded = committed(&FieldUtils::embedding_to_scalars(&[current.data, reference.data].concat()).unwrap());
+        assert_ne!(BiometricCircuit::decode_commitments(&first.commitments), unblinded);
+        assert!(first.commitments.iter().zip(&second.commitments).all(|(a, b)| a != b));
+    }
+    
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_prove_scalars_records_device_binding() {
+        let circuit = BiometricCircuit::new(4, 100);
+        let current = vec![Scalar::from(1u64), Scalar::from(2u64), Scalar::from(3u64), Scalar::from(4u64)];
+        let reference = vec![Scalar::from(2u64), Scalar::from(3u64), Scalar::from(4u64), Scalar::from(5u64)];
+        let device = ProofPublicInputs::device_binding_for(b"device-attestation");
+        
+        let proof = circuit.prove_scalars(&current, &reference, Some(&device)).unwrap();
+        assert_eq!(proof.public_inputs.device_binding, Some(device));
+        assert!(circuit.verify(&proof).unwrap());
+        
+        // Stripping the binding changes the transcript the verifier replays
+        let mut stripped = proof.clone();
+        stripped.public_inputs.device_binding = None;
+        assert!(!circuit.verify(&stripped).unwrap());
+    }
+    
+    #[test]
+    fn test_interval_proof_bounds() {
+        let circuit = BiometricCircuit::new(4, 1000);
+        
+        // Squared distance 10² + 10² = 200
+        let current = vec![Scalar::from(10u64), Scalar::from(10u64), ScalarUtils::zero(), ScalarUtils::zero()];
+        let reference = vec![ScalarUtils::zero(); 4];
+        
+        let interval = circuit.generate_interval_proof(&current, &reference, 100, 300).unwrap();
+        assert_eq!((interval.low, interval.high), (100, 300));
+        assert!(!interval.proof.is_empty());
+        
+        // Too close to the reference
+        assert!(circuit.generate_interval_proof(&current, &reference, 250, 300).is_err());
+        
+        // Too far from the reference
+        assert!(matches!(
+            circuit.generate_interval_proof(&current, &reference, 100, 150),
+            Err(CircuitError::ThresholdExceeded { expected: 150, actual: 200 })
+        ));
+        
+        // Bounds are exclusive
+        assert!(circuit.generate_interval_proof(&current, &reference, 200, 300).is_err());
+        assert!(circuit.generate_interval_proof(&current, &reference, 100, 200).is_err());
+        assert!(circuit.generate_interval_proof(&current, &reference, 199, 200).is_err());
+    }
+    
+    #[test]
+    fn test_interval_proof_round_trip() {
+        let circuit = BiometricCircuit::new(4, 1000);
+        
+        // Squared distance 200
+        let current = vec![Scalar::from(10u64), Scalar::from(10u64), ScalarUtils::zero(), ScalarUtils::zero()];
+        let reference = vec![ScalarUtils::zero(); 4];
+        let commitments = committed(&[current.clone(), reference.clone()].concat());
+        
+        let interval = circuit.generate_interval_proof(&current, &reference, 100, 300).unwrap();
+        assert!(circuit.verify_interval_proof(&interval, &commitments).unwrap());
+        
+        // Presenting the proof with a different interval, even one that
+        // still contains the distance, fails
+        for (low, high) in [(150, 300), (100, 250), (0, 1000), (199, 201)] {
+            let claimed = IntervalProof { low, high, ..interval.clone() };
+            assert!(!circuit.verify_interval_proof(&claimed, &commitments).unwrap());
+        }
+        
+        // As does checking it against commitments to another probe
+        let other = committed(&[vec![ScalarUtils::zero(); 4], reference].concat());
+        assert!(!circuit.verify_interval_proof(&interval, &other).unwrap());
+        
+        let empty = IntervalProof { low: 200, high: 201, ..interval };
+        assert!(matches!(circuit.verify_interval_proof(&empty, &commitments), Err(CircuitError::InvalidParameter(_))));
+    }
+    
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_prove_in_range() {
+        let circuit = BiometricCircuit::new(4, 300);
+        let current = BiometricEmbedding::new(vec![10, -20, 30, 40]).unwrap();
+        let reference = BiometricEmbedding::new(vec![20, -20, 30, 40]).unwrap();
+        
+        // Distance 100 lies within [50, 200], bounds included
+        let proof = circuit.prove_in_range(&current, &reference, 50, 200).unwrap();
+        assert_eq!(proof.public_inputs.distance_range, Some((50, 200)));
+        assert!(circuit.verify(&proof).unwrap());
+        assert!(circuit.verify(&circuit.prove_in_range(&current, &reference, 100, 100).unwrap()).unwrap());
+        
+        let json = crate::utils::SerializationUtils::serialize_proof(&proof).unwrap();
+        assert!(circuit.verify(&crate::utils::SerializationUtils::deserialize_proof(&json).unwrap()).unwrap());
+        
+        // Too close to the reference, e.g. a replayed template
+        assert!(matches!(
+            circuit.prove_in_range(&current, &reference, 150, 200),
+            Err(CircuitError::ProofGenerationFailed(_))
+        ));
+        
+        // Too far from the reference
+        assert!(matches!(
+            circuit.prove_in_range(&current, &reference, 10, 80),
+            Err(CircuitError::ThresholdExceeded { expected: 80, actual: 100 })
+        ));
+        
+        // Relabelling the range breaks the proof, and ranges past the threshold are refused
+        let mut narrowed = proof.clone();
+        narrowed.public_inputs.distance_range = Some((150, 200));
+        assert!(!circuit.verify(&narrowed).unwrap());
+        let mut widened = proof.clone();
+        widened.public_inputs.distance_range = Some((50, 400));
+        assert!(matches!(circuit.verify(&widened), Err(CircuitError::InvalidParameter(_))));
+        assert!(circuit.prove_in_range(&current, &reference, 50, 400).is_err());
+        assert!(circuit.prove_in_range(&current, &reference, 200, 50).is_err());
+        
+        // A plain threshold proof is not a range proof
+        let mut relabelled = circuit.prove(&current, &reference).unwrap();
+        relabelled.public_inputs.distance_range = Some((50, 200));
+        assert!(!circuit.verify(&relabelled).unwrap());
+    }
+    
+    /// Challenge the verifier would derive after replaying `public_commitments`
+    fn verifier_challenge(public_commitments: &[CompressedRistretto], embedding_size: usize) -> Option<[u8; 32]> {
+        let mut transcript = BiometricCircuit::new(4, 100).proof_transcript(None);
+        let mut verifier = Verifier::new(&mut transcript);
+        BiometricCircuit::commit_public_embeddings(&mut verifier, public_commitments, embedding_size)?;
+        drop(verifier);
+        
+        let mut challenge = [0u8; 32];
+        transcript.challenge_bytes(b"test", &mut challenge);
+        Some(challenge)
+    }
+    
+    #[test]
+    fn test_commitment_order_bound_to_transcript() {
+        let pc_gens = PedersenGens::default();
+        let current = vec![Scalar::from(1u64), Scalar::from(2u64), Scalar::from(3u64), Scalar::from(4u64)];
+        let reference = vec![Scalar::from(2u64), Scalar::from(3u64), Scalar::from(4u64), Scalar::from(5u64)];
+        
+        let mut transcript = BiometricCircuit::new(4, 100).proof_transcript(None);
+        let mut prover = Prover::new(&pc_gens, &mut transcript);
+        BiometricCircuit::commit_embeddings(&mut prover, &current, &reference);
+        drop(prover);
+        let mut prover_challenge = [0u8; 32];
+        transcript.challenge_bytes(b"test", &mut prover_challenge);
+        
+        // The verifier replaying the published order derives the prover's challenges
+        let mut commitments = committed(&[current, reference].concat());
+        assert_eq!(verifier_challenge(&commitments, 4), Some(prover_challenge));
+        
+        // Swapping two coordinates' commitments changes every later challenge,
+        // so a proof cannot be replayed against the reordered points
+        commitments.swap(0, 1);
+        assert_ne!(verifier_challenge(&commitments, 4), Some(prover_challenge));
+        
+        // Moving a reference commitment into the current half is also detected
+        commitments.swap(0, 1);
+        commitments.swap(1, 5);
+        assert_ne!(verifier_challenge(&commitments, 4), Some(prover_challenge));
+        
+        // Missing commitments cannot be replayed at all
+        assert_eq!(verifier_challenge(&commitments[..7], 4), None);
+    }
+    
+    #[test]
+    fn test_threshold_enforced() {
+        let current = vec![Scalar::from(1u64), Scalar::from(2u64), Scalar::from(3u64), Scalar::from(4u64)];
+        
+        // Squared distance 10² + 0 + 0 + 0 = 100
+        let reference = vec![Scalar::from(11u64), Scalar::from(2u64), Scalar::from(3u64), Scalar::from(4u64)];
+        let commitments = committed(&[current.clone(), reference.clone()].concat());
+        
+        let at_threshold = BiometricCircuit::new(4, 100);
+        let proof = at_threshold.generate_proof(&current, &reference).unwrap();
+        assert!(at_threshold.verify_proof(&proof, &commitments).unwrap());
+        
+        let strict = BiometricCircuit::new(4, 99);
+        assert!(matches!(
+            strict.generate_proof(&current, &reference),
+            Err(CircuitError::ThresholdExceeded { expected: 99, actual: 100 })
+        ));
+        
+        // A proof made under a looser threshold does not satisfy a stricter verifier
+        assert!(!strict.verify_proof(&proof, &commitments).unwrap());
+    }
+    
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_proof_bound_to_challenge() {
+        let circuit = BiometricCircuit::new(4, 100);
+        let current = BiometricEmbedding::new(vec![10, -20, 30, 40]).unwrap();
+        let reference = BiometricEmbedding::new(vec![12, -20, 31, 40]).unwrap();
+        
+        let proof = circuit.prove_with_challenge(&current, &reference, b"challenge-a").unwrap();
+        assert!(circuit.verify_with_challenge(&proof, b"challenge-a").unwrap());
+        assert!(circuit.verify_with_challenge(&proof, b"challenge-b").is_err());
+        
+        // Relabelling the proof with the expected challenge breaks the transcript
+        let mut relabelled = proof.clone();
+        relabelled.public_inputs.challenge = b"challenge-b".to_vec();
+        assert!(!circuit.verify_with_challenge(&relabelled, b"challenge-b").unwrap());
+        
+        // A proof without a challenge cannot answer one
+        let unbound = circuit.prove(&current, &reference).unwrap();
+        assert!(circuit.verify_with_challenge(&unbound, b"challenge-a").is_err());
+    }
+    
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_verify_rejects_unsupported_version() {
+        let circuit = BiometricCircuit::new(4, 100);
+        let current = BiometricEmbedding::new(vec![10, -20, 30, 40]).unwrap();
+        let reference = BiometricEmbedding::new(vec![12, -20, 31, 40]).unwrap();
+        
+        let mut proof = circuit.prove(&current, &reference).unwrap();
+        assert!(circuit.verify(&proof).unwrap());
+        
+        proof.metadata.version = "7.3.0".to_string();
+        match circuit.verify(&proof) {
+            Err(CircuitError::ProofVerificationFailed(msg)) => assert!(msg.contains("7.3.0")),
+            other => panic!("expected version rejection, got {:?}", other),
+        }
+    }
+    
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_prove_rejects_mixed_scales() {
+        let circuit = BiometricCircuit::new(4, 1000);
+        let floats = [0.01, -0.02, 0.03, 0.04];
+        let current = BiometricEmbedding::from_floats(floats.to_vec(), 1000).unwrap();
+        let reference = BiometricEmbedding::from_floats(floats.to_vec(), 100).unwrap();
+        
+        assert!(matches!(circuit.prove(&current, &reference), Err(CircuitError::InvalidEmbedding(_))));
+    }
+    
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_prove_reports_non_match() {
+        let circuit = BiometricCircuit::new(4, 100);
+        let current = BiometricEmbedding::new(vec![0, 0, 0, 0]).unwrap();
+        let reference = BiometricEmbedding::new(vec![100, 0, 0, 0]).unwrap();
+        
+        assert!(matches!(
+            circuit.prove(&current, &reference),
+            Err(CircuitError::ThresholdExceeded { expected: 100, actual: 10_000 })
+        ));
+    }
+    
+    #[test]
+    fn test_explicit_gens_interoperate() {
+        let current = vec![Scalar::from(1u64), Scalar::from(2u64), Scalar::from(3u64), Scalar::from(4u64)];
+        let reference = vec![Scalar::from(2u64), Scalar::from(3u64), Scalar::from(4u64), Scalar::from(5u64)];
+        
+        // Prover and verifier each build their own generators from the same inputs
+        let build = || {
+            let scheme = CommitmentScheme::new();
+            BiometricCircuit::with_gens(4, 100, scheme.pedersen_gens(), BulletproofGens::new(512, 1))
+        };
+        let prover_side = build();
+        let verifier_side = build();
+        
+        let commitments: Vec<CompressedRistretto> = [current.clone(), reference.clone()]
+            .concat()
+            .iter()
+            .map(|v| prover_side.commitment_scheme.commit(v, &ScalarUtils::zero()).compress())
+            .collect();
+        let proof = prover_side.generate_proof(&current, &reference).unwrap();
+        
+        assert!(verifier_side.verify_proof(&proof, &commitments).unwrap());
+    }
+    
+    #[test]
+    fn test_explicit_gens_report_capacity_exceeded() {
+        let current = BiometricEmbedding::new(vec![1, 2, 3, 4]).unwrap();
+        let reference = BiometricEmbedding::new(vec![2, 3, 4, 5]).unwrap();
+        let mut rng = rand::rngs::OsRng;
+        
+        // 4 dimensions need 373 multipliers, padded to 512 generators
+        let undersized = BiometricCircuit::with_gens(4, 100, PedersenGens::default(), BulletproofGens::new(64, 1));
+        assert!(matches!(
+            undersized.prove_with_rng(&current, &reference, &mut rng),
+            Err(CircuitError::CapacityExceeded { needed: 512, available: 64 })
+        ));
+        assert!(matches!(
+            undersized.prove_in_range_with_rng(&current, &reference, 0, 100, &mut rng),
+            Err(CircuitError::CapacityExceeded { needed: 512, available: 64 })
+        ));
+        
+        let proof = BiometricCircuit::new(4, 100).prove_with_rng(&current, &reference, &mut rng).unwrap();
+        assert!(matches!(
+            undersized.verify(&proof),
+            Err(CircuitError::CapacityExceeded { needed: 512, available: 64 })
+        ));
+        assert!(matches!(
+            undersized.verify_batch(&[proof]),
+            Err(CircuitError::CapacityExceeded { needed: 512, available: 64 })
+        ));
+    }
+    
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_rotated_generators_bind_proofs_to_epoch() {
+        let current = vec![Scalar::from(1u64), Scalar::from(2u64), Scalar::from(3u64), Scalar::from(4u64)];
+        let reference = vec![Scalar::from(2u64), Scalar::from(3u64), Scalar::from(4u64), Scalar::from(5u64)];
+        
+        let epoch_1 = BiometricCircuit::with_generator_epoch(4, 100, 1);
+        let epoch_2 = BiometricCircuit::with_generator_epoch(4, 100, 2);
+        assert_ne!(epoch_1.pedersen_gens.B, epoch_2.pedersen_gens.B);
+        assert_ne!(epoch_1.pedersen_gens.B_blinding, epoch_2.pedersen_gens.B_blinding);
+        
+        let commitments: Vec<CompressedRistretto> = [current.clone(), reference.clone()]
+            .concat()
+            .iter()
+            .map(|v| epoch_1.pedersen_gens.commit(*v, ScalarUtils::zero()).compress())
+            .collect();
+        let proof = epoch_1.generate_proof(&current, &reference).unwrap();
+        
+        // The verifier picks the generators from the recorded epoch
+        let public_inputs = BiometricProof::new(proof.clone(), vec![], 100, 4, vec![0; 32])
+            .with_generator_epoch(1)
+            .public_inputs;
+        let verifier = BiometricCircuit::from_public_inputs(&public_inputs);
+        assert!(verifier.verify_proof(&proof, &commitments).unwrap());
+        
+        assert!(!epoch_2.verify_proof(&proof, &commitments).unwrap());
+        assert!(!BiometricCircuit::new(4, 100).verify_proof(&proof, &commitments).unwrap());
+    }
+    
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_verify_proof_from_floats() {
+        let circuit = BiometricCircuit::new(4, 100);
+        let current = [0.101, 0.202, -0.303, 0.404];
+        let reference = [0.100, 0.200, -0.300, 0.400];
+        
+        let proof = circuit.generate_proof_from_floats(&current, &reference, 1000).unwrap();
+        assert_eq!(proof.public_inputs.scale_factor, Some(1000));
+        assert!(circuit.verify_proof_from_floats(&proof, &reference).unwrap());
+        
+        // A different enrolled reference does not verify
+        assert!(!circuit.verify_proof_from_floats(&proof, &[0.1, 0.2, -0.3, 0.5]).unwrap());
+        
+        // Missing scale factor is an error rather than a guess
+        let mut unscaled = proof.clone();
+        unscaled.public_inputs.scale_factor = None;
+        assert!(matches!(
+            circuit.verify_proof_from_floats(&unscaled, &reference),
+            Err(CircuitError::InvalidParameter(_))
+        ));
+    }
+    
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_verify_proof_from_floats_wrong_scale() {
+        let circuit = BiometricCircuit::new(4, 100);
+        let current = [0.101, 0.202, -0.303, 0.404];
+        let reference = [0.100, 0.200, -0.300, 0.400];
+        let proof = circuit.generate_proof_from_floats(&current, &reference, 1000).unwrap();
+        
+        // Quantizing the reference by hand with a different scale breaks verification
+        let mut commitments = BiometricCircuit::decode_commitments(&proof.commitments[..4]);
+        commitments.extend(committed(&BiometricCircuit::quantize(&reference, 100).unwrap()));
+        assert!(!circuit.verify_proof(&proof.proof, &commitments).unwrap());
+        
+        // So does a proof whose recorded scale was altered
+        let mut rescaled = proof.clone();
+        rescaled.public_inputs.scale_factor = Some(100);
+        assert!(!circuit.verify_proof_from_floats(&rescaled, &reference).unwrap());
+    }
+    
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_256_dim_circuit_has_enough_generators() {
+        let circuit = BiometricCircuit::new(256, 1000);
+        let required = BiometricCircuit::generator_capacity(256, SimilarityMetric::SquaredDistance, RANGE_BITS);
+        assert!(circuit.bulletproof_gens.gens_capacity >= required);
+        assert!(Arc::ptr_eq(&circuit.gens_for(circuit.metric_multipliers()).unwrap(), &circuit.bulletproof_gens));
+        
+        let current = BiometricEmbedding::new((0..256).map(|i| i % 50).collect()).unwrap();
+        let reference = BiometricEmbedding::new((0..256).map(|i| i % 50 + (i % 2)).collect()).unwrap();
+        let proof = circuit.prove(&current, &reference).unwrap();
+        assert!(circuit.verify(&proof).unwrap());
+    }
+    
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_circuits_share_generators() {
+        let first = BiometricCircuit::new(16, 1000);
+        let second = BiometricCircuit::new(16, 1000);
+        assert!(Arc::ptr_eq(&first.bulletproof_gens, &second.bulletproof_gens));
+        
+        // Constructions racing on other threads get the same generators
+        let from_threads: Vec<Arc<BulletproofGens>> = (0..4)
+            .map(|_| std::thread::spawn(|| BiometricCircuit::new(16, 1000).bulletproof_gens))
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect();
+        assert!(from_threads.iter().all(|gens| Arc::ptr_eq(gens, &first.bulletproof_gens)));
+        
+        // A circuit needing more generators gets its own
+        let larger = BiometricCircuit::new(64, 1000);
+        assert!(larger.bulletproof_gens.gens_capacity > first.bulletproof_gens.gens_capacity);
+        assert!(!Arc::ptr_eq(&larger.bulletproof_gens, &first.bulletproof_gens));
+        
+        let current = BiometricEmbedding::new((0..16).collect()).unwrap();
+        let reference = BiometricEmbedding::new((1..=16).collect()).unwrap();
+        let proof = first.prove(&current, &reference).unwrap();
+        assert!(second.verify(&proof).unwrap());
+    }
+    
+    #[test]
+    fn test_proof_bound_to_domain() {
+        let current = vec![Scalar::from(1u64), Scalar::from(2u64), Scalar::from(3u64), Scalar::from(4u64)];
+        let reference = vec![Scalar::from(2u64), Scalar::from(3u64), Scalar::from(4u64), Scalar::from(5u64)];
+        let commitments = committed(&[current.clone(), reference.clone()].concat());
+        
+        let domain_a = BiometricCircuit::new(4, 100).with_domain(b"A");
+        let domain_b = BiometricCircuit::new(4, 100).with_domain(b"B");
+        let proof = domain_a.generate_proof(&current, &reference).unwrap();
+        
+        assert!(domain_a.verify_proof(&proof, &commitments).unwrap());
+        assert!(!domain_b.verify_proof(&proof, &commitments).unwrap());
+        
+        // The threshold is bound too, even when the proof would satisfy both
+        assert!(!BiometricCircuit::new(4, 200).with_domain(b"A").verify_proof(&proof, &commitments).unwrap());
+    }
+    
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_prove_and_verify_round_trip() {
+        let circuit = BiometricCircuit::new(4, 100);
+        let current = BiometricEmbedding::new(vec![10, -20, 30, 40]).unwrap();
+        let reference = BiometricEmbedding::new(vec![12, -20, 31, 40]).unwrap();
+        
+        let proof = circuit.prove(&current, &reference).unwrap();
+        assert_eq!(proof.commitments.len(), 8);
+        assert_eq!(proof.public_inputs.commitment_hash.len(), 32);
+        assert!(crate::utils::ValidationUtils::validate_proof(&proof).is_ok());
+        assert!(circuit.verify(&proof).unwrap());
+        
+        // Survives serialization
+        let json = crate::utils::SerializationUtils::serialize_proof(&proof).unwrap();
+        let restored = crate::utils::SerializationUtils::deserialize_proof(&json).unwrap();
+        assert!(circuit.verify(&restored).unwrap());
+        
+        // Swapped commitments no longer match the recorded hash
+        let mut swapped = proof.clone();
+        swapped.commitments.swap(0, 4);
+        assert!(matches!(circuit.verify(&swapped), Err(CircuitError::InvalidCommitment(_))));
+        
+        // Rehashing the swapped commitments still fails the proof itself
+        swapped.public_inputs.commitment_hash = BiometricProof::hash_commitments(&swapped.commitments);
+        assert!(!circuit.verify(&swapped).unwrap());
+        
+        // A circuit with other parameters refuses the proof
+        assert!(BiometricCircuit::new(4, 200).verify(&proof).is_err());
+        
+        // So does one whose recorded range bits cannot cover the threshold
+        let mut narrow = proof.clone();
+        narrow.metadata.circuit_params.range_bits = 6;
+        assert!(matches!(circuit.verify(&narrow), Err(CircuitError::ParameterMismatch(_))));
+        
+        // Over-threshold pairs cannot be packaged at all
+        let far = BiometricEmbedding::new(vec![30, -20, 30, 40]).unwrap();
+        assert!(matches!(circuit.prove(&current, &far), Err(CircuitError::ThresholdExceeded { .. })));
+    }
+    
+    #[cfg(feature = "poseidon")]
+    #[test]
+    fn test_commitment_hash_bound_in_circuit() {
+        let circuit = BiometricCircuit::new(4, 100).with_commitment_binding();
+        let current = BiometricEmbedding::new(vec![10, -20, 30, 40]).unwrap();
+        let reference = BiometricEmbedding::new(vec![12, -20, 31, 40]).unwrap();
+        
+        let proof = circuit.prove(&current, &reference).unwrap();
+        let witness = FieldUtils::embedding_to_scalars(&[current.data.clone(), reference.data.clone()].concat()).unwrap();
+        assert_eq!(proof.public_inputs.commitment_hash, CircuitHash::poseidon(&witness).to_bytes().to_vec());
+        assert!(circuit.verify(&proof).unwrap());
+        
+        // The hash of other embeddings does not match the committed witness
+        let mut mismatched = proof.clone();
+        let other = FieldUtils::embedding_to_scalars(&[10, -20, 30, 41, 12, -20, 31, 40]).unwrap();
+        mismatched.public_inputs.commitment_hash = CircuitHash::poseidon(&other).to_bytes().to_vec();
+        assert!(!circuit.verify(&mismatched).unwrap());
+        
+        // Bytes that are not a scalar cannot be bound at all
+        let mut malformed = proof.clone();
+        malformed.public_inputs.commitment_hash = vec![0xff; 32];
+        assert!(matches!(circuit.verify(&malformed), Err(CircuitError::InvalidCommitment(_))));
+        
+        // Without the binding the recorded hash is checked against the commitments
+        assert!(matches!(
+            BiometricCircuit::new(4, 100).verify(&proof),
+            Err(CircuitError::InvalidCommitment(_))
+        ));
+        
+        let description = circuit.describe_constraints();
+        assert_eq!(description.count(GateKind::Hash), 4);
+        assert_eq!(description.multipliers(), circuit.metric_multipliers());
+    }
+    
+    #[cfg(not(feature = "poseidon"))]
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_commitment_binding_needs_poseidon() {
+        let mut circuit = BiometricCircuit::new(4, 100);
+        circuit.bind_commitment_hash = true;
+        let current = BiometricEmbedding::new(vec![10, -20, 30, 40]).unwrap();
+        
+        assert!(matches!(circuit.prove(&current, &current), Err(CircuitError::InvalidParameter(_))));
+    }
+    
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_verify_with_policy() {
+        let circuit = BiometricCircuit::new(4, 100);
+        let current = BiometricEmbedding::new(vec![10, -20, 30, 40]).unwrap();
+        let reference = BiometricEmbedding::new(vec![12, -20, 31, 40]).unwrap();
+        let (enrollment, opening) = circuit.enroll(&reference).unwrap();
+        let now = crate::types::proof_data::current_timestamp().unwrap();
+        let proof = circuit
+            .prove_enrolled_at(&current, &reference, &opening, now, Some(900), &mut rand::rngs::OsRng)
+            .unwrap();
+        assert_eq!(proof.public_inputs.liveness_score, Some(900));
+        let enrolled = enrollment.to_commitments();
+        
+        let policy = VerificationPolicy::new(100, SimilarityMetric::SquaredDistance)
+            .with_max_proof_age(300)
+            .with_min_liveness(800);
+        assert!(circuit.verify_with_policy(&proof, &enrolled, &policy).unwrap());
+        
+        let looser = VerificationPolicy { threshold: 200, ..policy.clone() };
+        assert!(matches!(
+            circuit.verify_with_policy(&proof, &enrolled, &looser),
+            Err(CircuitError::ParameterMismatch(_))
+        ));
+        
+        let cosine = VerificationPolicy { metric: SimilarityMetric::CosineSimilarity, ..policy.clone() };
+        assert!(matches!(
+            circuit.verify_with_policy(&proof, &enrolled, &cosine),
+            Err(CircuitError::ParameterMismatch(_))
+        ));
+        
+        let mut stale = proof.clone();
+        stale.metadata.timestamp -= 301;
+        assert!(matches!(
+            circuit.verify_with_policy(&stale, &enrolled, &policy),
+            Err(CircuitError::ProofVerificationFailed(_))
+        ));
+        
+        let unlive = circuit
+            .prove_enrolled_at(&current, &reference, &opening, now, Some(799), &mut rand::rngs::OsRng)
+            .unwrap();
+        assert!(matches!(
+            circuit.verify_with_policy(&unlive, &enrolled, &policy),
+            Err(CircuitError::ProofVerificationFailed(_))
+        ));
+        let unscored = circuit.prove_enrolled(&current, &reference, &opening).unwrap();
+        assert!(circuit.verify_with_policy(&unscored, &enrolled, &policy).is_err());
+        
+        // A genuine proof over someone else's reference is refused
+        let (other, _) = circuit.enroll(&BiometricEmbedding::new(vec![12, -20, 31, 41]).unwrap()).unwrap();
+        assert!(!circuit.verify_with_policy(&proof, &other.to_commitments(), &policy).unwrap());
+        
+        // So is a proof that was not made against the enrollment's opening
+        let unenrolled = circuit.prove(&current, &reference).unwrap();
+        let unscored_policy = VerificationPolicy { min_liveness: None, ..policy };
+        assert!(!circuit.verify_with_policy(&unenrolled, &enrolled, &unscored_policy).unwrap());
+    }
+    
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_policy_fields_are_bound_to_the_proof() {
+        let circuit = BiometricCircuit::new(4, 100);
+        let current = BiometricEmbedding::new(vec![10, -20, 30, 40]).unwrap();
+        let reference = BiometricEmbedding::new(vec![12, -20, 31, 40]).unwrap();
+        let (enrollment, opening) = circuit.enroll(&reference).unwrap();
+        let enrolled = enrollment.to_commitments();
+        let now = crate::types::proof_data::current_timestamp().unwrap();
+        let proof = circuit
+            .prove_enrolled_at(&current, &reference, &opening, now - 600, Some(700), &mut rand::rngs::OsRng)
+            .unwrap();
+        assert!(circuit.verify(&proof).unwrap());
+        
+        let policy = VerificationPolicy::new(100, SimilarityMetric::SquaredDistance)
+            .with_max_proof_age(300)
+            .with_min_liveness(800);
+        
+        // Refreshing a stale proof's timestamp no longer gets it past the age check
+        let mut refreshed = proof.clone();
+        refreshed.metadata.timestamp = now;
+        refreshed.public_inputs.liveness_score = Some(900);
+        assert!(!circuit.verify(&refreshed).unwrap());
+        assert!(!circuit.verify_with_policy(&refreshed, &enrolled, &policy).unwrap());
+        
+        let mut restamped = proof.clone();
+        restamped.metadata.timestamp = now;
+        assert!(!circuit.verify(&restamped).unwrap());
+        
+        // Nor does raising, adding or stripping the liveness score
+        let raised = proof.clone().with_liveness_score(900);
+        assert!(!circuit.verify(&raised).unwrap());
+        let mut stripped = proof.clone();
+        stripped.public_inputs.liveness_score = None;
+        assert!(!circuit.verify(&stripped).unwrap());
+        let added = circuit.prove_enrolled(&current, &reference, &opening).unwrap().with_liveness_score(900);
+        assert!(!circuit.verify(&added).unwrap());
+    }
+    
+    #[test]
+    fn test_cosine_similarity_metric() {
+        let circuit = BiometricCircuit::with_metric(2, 950, SimilarityMetric::CosineSimilarity);
+        
+        // cos([3, 4], [4, 3]) = 24 / 25 = 0.96
+        let current = vec![Scalar::from(3u64), Scalar::from(4u64)];
+        let reference = vec![Scalar::from(4u64), Scalar::from(3u64)];
+        let commitments = committed(&[current.clone(), reference.clone()].concat());
+        
+        let proof = circuit.generate_proof(&current, &reference).unwrap();
+        assert!(circuit.verify_proof(&proof, &commitments).unwrap());
+        
+        // Scaling one side leaves the cosine unchanged
+        let scaled: Vec<Scalar> = reference.iter().map(|v| v * Scalar::from(10u64)).collect();
+        assert!(circuit.generate_proof(&current, &scaled).is_ok());
+        
+        let strict = BiometricCircuit::with_metric(2, 970, SimilarityMetric::CosineSimilarity);
+        assert!(matches!(
+            strict.generate_proof(&current, &reference),
+            Err(CircuitError::ThresholdExceeded { expected: 970, actual: 960 })
+        ));
+        assert!(!strict.verify_proof(&proof, &commitments).unwrap());
+        
+        // The squared distance circuit does not accept a cosine proof
+        let euclidean = BiometricCircuit::new(2, 950);
+        assert!(!euclidean.verify_proof(&proof, &commitments).unwrap());
+    }
+    
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_cosine_similarity_rejects_opposed_and_zero_embeddings() {
+        let circuit = BiometricCircuit::with_metric(2, 0, SimilarityMetric::CosineSimilarity);
+        let current = vec![Scalar::from(3u64), Scalar::from(4u64)];
+        
+        let opposed: Vec<Scalar> = current.iter().map(|v| -v).collect();
+        assert!(matches!(
+            circuit.generate_proof(&current, &opposed),
+            Err(CircuitError::ThresholdExceeded { actual: 0, .. })
+        ));
+        
+        let zero = vec![ScalarUtils::zero(), ScalarUtils::zero()];
+        assert!(circuit.generate_proof(&zero, &current).is_err());
+        
+        // Only plain proofs are defined for cosine circuits
+        assert!(matches!(
+            circuit.generate_chunked_proof(&current, &current, 1),
+            Err(CircuitError::InvalidParameter(_))
+        ));
+        
+        let above_one = BiometricCircuit::with_metric(2, COSINE_SCALE + 1, SimilarityMetric::CosineSimilarity);
+        assert!(matches!(
+            above_one.generate_proof(&current, &current),
+            Err(CircuitError::InvalidParameter(_))
+        ));
+    }
+    
+    #[test]
+    fn test_fused_proof_blends_metrics() {
+        // Squared distance 610, cosine similarity 0.5547 (score 554)
+        let probe = vec![ScalarUtils::zero(), Scalar::from(1u64)];
+        let reference = vec![Scalar::from(21u64), Scalar::from(14u64)];
+        let commitments = committed(&[probe.clone(), reference.clone()].concat());
+        let circuit = BiometricCircuit::new(2, 600);
+        
+        // Neither metric alone meets 600
+        assert!(matches!(
+            circuit.generate_proof(&probe, &reference),
+            Err(CircuitError::ThresholdExceeded { expected: 600, actual: 610 })
+        ));
+        let cosine = BiometricCircuit::with_metric(2, 600, SimilarityMetric::CosineSimilarity);
+        assert!(matches!(
+            cosine.generate_proof(&probe, &reference),
+            Err(CircuitError::ThresholdExceeded { expected: 600, actual: 554 })
+        ));
+        
+        // (610 + (1000 - 554)) / 2 = 528 does
+        let fused = circuit.generate_fused_proof(&probe, &reference, 1, 2, 600).unwrap();
+        assert_eq!((fused.alpha_numerator, fused.alpha_denominator, fused.threshold), (1, 2, 600));
+        assert!(circuit.verify_fused_proof(&fused, &commitments).unwrap());
+        
+        // The public weights and threshold are bound to the proof
+        for (numerator, denominator, threshold) in [(1, 3, 600), (1, 2, 527)] {
+            let relabelled = FusedProof {
+                alpha_numerator: numerator,
+                alpha_denominator: denominator,
+                threshold,
+                ..fused.clone()
+            };
+            assert!(!circuit.verify_fused_proof(&relabelled, &commitments).unwrap());
+        }
+        
+        // The blend is checked exactly at the boundary
+        assert!(circuit.generate_fused_proof(&probe, &reference, 1, 2, 528).is_ok());
+        assert!(matches!(
+            circuit.generate_fused_proof(&probe, &reference, 1, 2, 527),
+            Err(CircuitError::ThresholdExceeded { expected: 527, actual: 528 })
+        ));
+        assert!(circuit.generate_fused_proof(&probe, &reference, 3, 2, 600).is_err());
+    }
+    
+    #[test]
+    fn test_out_of_range_coordinates_are_unprovable() {
+        let circuit = BiometricCircuit::new(2, 1000);
+        let max = Scalar::from(MAX_EMBEDDING_VALUE as u64);
+        
+        // Values at the bound are accepted
+        let edge = vec![max, -max];
+        let proof = circuit.generate_proof(&edge, &edge).unwrap();
+        assert!(circuit.verify_proof(&proof, &committed(&[edge.clone(), edge].concat())).unwrap());
+        
+        // The honest prover refuses values beyond it
+        let current = vec![max + Scalar::from(1u64), ScalarUtils::zero()];
+        let reference = current.clone();
+        assert!(matches!(
+            circuit.generate_proof(&current, &reference),
+            Err(CircuitError::InvalidEmbedding(_))
+        ));
+        
+        // A prover forging the range witnesses cannot produce a verifying proof
+        let mut transcript = BiometricCircuit::new(4, 100).proof_transcript(None);
+        let mut prover = Prover::new(&circuit.pedersen_gens, &mut transcript);
+        let (current_vars, reference_vars) = BiometricCircuit::commit_embeddings(&mut prover, &current, &reference);
+        for var in current_vars.iter().chain(&reference_vars) {
+            BiometricGadgets::coordinate_range_gadget(
+                &mut prover,
+                *var,
+                MAX_EMBEDDING_VALUE as u64,
+                Some(MAX_EMBEDDING_VALUE),
+                COORDINATE_RANGE_BITS,
+            ).unwrap();
+        }
+        let distance = BiometricGadgets::distance_gadget(&mut prover, &current_vars, &reference_vars).unwrap();
+        BiometricConstraints::add_threshold_constraint(&mut prover, distance, 1000).unwrap();
+        let forged = prover.prove(&circuit.gens_for(circuit.metric_multipliers()).unwrap()).unwrap().to_bytes();
+        
+        let commitments = committed(&[current, reference].concat());
+        assert!(!circuit.verify_proof(&forged, &commitments).unwrap());
+    }
+    
+    #[test]
+    fn test_describe_constraints() {
+        let circuit = BiometricCircuit::new(2, 100);
+        let description = circuit.describe_constraints();
+        
+        let kinds: Vec<GateKind> = description.gates.iter().map(|g| g.kind).collect();
+        assert_eq!(kinds, vec![GateKind::Range, GateKind::SquaredDifference, GateKind::Sum, GateKind::Threshold]);
+        assert_eq!(description.committed_variables, 4);
+        assert_eq!(description.count(GateKind::Range), 4);
+        assert_eq!(description.count(GateKind::SquaredDifference), 2);
+        assert_eq!(description.count(GateKind::Sum), 1);
+        assert_eq!(description.count(GateKind::Threshold), 1);
+        assert_eq!(description.gates[1].variables, vec![0, 1, 2, 3]);
+        assert_eq!(description.gates[3].bound, Some(100));
+        assert_eq!(description.multipliers(), circuit.metric_multipliers());
+        
+        let json = serde_json::to_string(&description).unwrap();
+        assert!(json.contains("\"SquaredDifference\""));
+        assert_eq!(serde_json::from_str::<ConstraintDescription>(&json).unwrap(), description);
+        
+        let cosine = BiometricCircuit::with_metric(2, 950, SimilarityMetric::CosineSimilarity);
+        assert_eq!(cosine.describe_constraints().multipliers(), cosine.metric_multipliers());
+    }
+    
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_prove_with_deadline() {
+        use std::time::Duration;
+        
+        let size = crate::config::MAX_EMBEDDING_SIZE;
+        let circuit = BiometricCircuit::new(size, 1000);
+        let embedding = BiometricEmbedding::new((1..=size as i64).collect()).unwrap();
+        
+        let started = Instant::now();
+        let expired = circuit.prove_with_deadline(&embedding, &embedding, started);
+        assert!(matches!(expired, Err(CircuitError::ProofGenerationFailed(ref message)) if message == "deadline exceeded"));
+        assert!(started.elapsed() < Duration::from_millis(100), "took {:?}", started.elapsed());
+        
+        let small = BiometricCircuit::new(4, 1000);
+        let embedding = BiometricEmbedding::new(vec![1, 2, 3, 4]).unwrap();
+        let deadline = Instant::now() + Duration::from_secs(600);
+        let proof = small.prove_with_deadline(&embedding, &embedding, deadline).unwrap();
+        assert!(small.verify(&proof).unwrap());
+    }
+    
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_verify_enrolled() {
+        let circuit = BiometricCircuit::new(4, 100);
+        let reference = BiometricEmbedding::new(vec![10, 20, 30, 40]).unwrap();
+        
+        let (enrollment, opening) = circuit.enroll(&reference).unwrap();
+        let stored = serde_json::to_vec(&enrollment).unwrap();
+        let enrollment: EnrollmentCommitment = serde_json::from_slice(&stored).unwrap();
+        assert_eq!(enrollment.embedding_size(), 4);
+        
+        // The stored commitments are blinded, not recomputable from the template
+        let unblinded = committed(&FieldUtils::embedding_to_scalars(&reference.data).unwrap());
+        assert_ne!(enrollment.to_commitments(), unblinded);
+        
+        let probe = BiometricEmbedding::new(vec![11, 19, 30, 42]).unwrap();
+        let proof = circuit.prove_enrolled(&probe, &reference, &opening).unwrap();
+        assert!(circuit.verify_enrolled(&proof, &enrollment).unwrap());
+        assert_eq!(&proof.commitments[4..], enrollment.reference_commitments.as_slice());
+        
+        // A proof against another reference does not match the enrollment
+        let impostor = BiometricEmbedding::new(vec![11, 19, 30, 41]).unwrap();
+        let other = circuit.prove_enrolled(&probe, &impostor, &opening).unwrap();
+        assert!(!circuit.verify_enrolled(&other, &enrollment).unwrap());
+        
+        // Nor does the right reference under another enrollment's opening
+        let (_, reenrolled) = circuit.enroll(&reference).unwrap();
+        let unopened = circuit.prove_enrolled(&probe, &reference, &reenrolled).unwrap();
+        assert!(!circuit.verify_enrolled(&unopened, &enrollment).unwrap());
+        
+        // A stored bundle that was altered is rejected outright
+        let mut tampered = enrollment.clone();
+        tampered.reference_commitments.swap(0, 1);
+        assert!(matches!(circuit.verify_enrolled(&proof, &tampered), Err(CircuitError::InvalidCommitment(_))));
+        
+        let rotated = BiometricCircuit::with_generator_epoch(4, 100, 3);
+        assert!(matches!(rotated.verify_enrolled(&proof, &enrollment), Err(CircuitError::ParameterMismatch(_))));
+        assert!(circuit.enroll(&BiometricEmbedding::new(vec![1, 2]).unwrap()).is_err());
+    }
+    
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_estimate_matches_real_proof() {
+        for (size, metric) in [
+            (4, SimilarityMetric::SquaredDistance),
+            (16, SimilarityMetric::SquaredDistance),
+            (64, SimilarityMetric::SquaredDistance),
+            (8, SimilarityMetric::CosineSimilarity),
+        ] {
+            let threshold = if metric == SimilarityMetric::CosineSimilarity { 950 } else { 1000 };
+            let circuit = BiometricCircuit::with_metric(size, threshold, metric);
+            let embedding = BiometricEmbedding::new((1..=size as i64).collect()).unwrap();
+            let proof = circuit.prove(&embedding, &embedding).unwrap();
+            
+            let estimate = circuit.estimate();
+            assert_eq!(estimate.num_multipliers, circuit.describe_constraints().multipliers());
+            assert_eq!(estimate.num_commitments, proof.commitments.len());
+            assert!(estimate.approx_proof_bytes <= proof.proof.len(), "size {}", size);
+            assert!(proof.proof.len() - estimate.approx_proof_bytes <= 96, "size {}", size);
+        }
+    }
+    
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_verify_batch_matches_sequential() {
+        let circuit = BiometricCircuit::new(4, 100);
+        let mut proofs: Vec<BiometricProof> = (0..100i64)
+            .map(|i| {
+                let current = BiometricEmbedding::new(vec![i, 2 * i, 3, 4]).unwrap();
+                let reference = BiometricEmbedding::new(vec![i + 1, 2 * i, 3, 4 + i % 3]).unwrap();
+                circuit.prove(&current, &reference).unwrap()
+            })
+            .collect();
+        
+        // Corrupt a few proofs in different ways
+        proofs[7].proof[40] ^= 1;
+        proofs[23].commitments.swap(0, 4);
+        proofs[51].public_inputs.threshold = 200;
+        proofs[88].public_inputs.commitment_hash.clear();
+        
+        let batch = circuit.verify_batch(&proofs).unwrap();
+        let sequential: Vec<bool> = proofs.iter().map(|p| circuit.verify(p).unwrap_or(false)).collect();
+        assert_eq!(batch, sequential);
+        
+        let failed: Vec<usize> = (0..batch.len()).filter(|&i| !batch[i]).collect();
+        assert_eq!(failed, vec![7, 23, 51, 88]);
+        assert!(circuit.verify_batch(&[]).unwrap().is_empty());
+    }
+    
+    #[test]
+    fn test_aggregate_match_proof_requires_every_pair() {
+        let circuit = BiometricCircuit::new(4, 100);
+        let scalars = |values: [u64; 4]| values.map(Scalar::from).to_vec();
+        let (first_probe, first_reference) = (scalars([1, 2, 3, 4]), scalars([2, 3, 4, 5]));
+        let (second_probe, second_reference) = (scalars([10, 10, 10, 10]), scalars([13, 10, 10, 10]));
+        
+        // Distances 4 and 9
+        let proof = circuit
+            .generate_aggregate_match_proof(&[(&first_probe, &first_reference, 4), (&second_probe, &second_reference, 9)])
+            .unwrap();
+        assert_eq!(proof.thresholds, vec![4, 9]);
+        
+        let commitments = committed(&[
+            first_probe.clone(), first_reference.clone(), second_probe.clone(), second_reference.clone(),
+        ].concat());
+        assert!(circuit.verify_aggregate_match_proof(&proof, &commitments).unwrap());
+        
+        // Swapping the pairs or tightening a threshold breaks the proof
+        let swapped = committed(&[
+            second_probe.clone(), second_reference.clone(), first_probe.clone(), first_reference.clone(),
+        ].concat());
+        assert!(!circuit.verify_aggregate_match_proof(&proof, &swapped).unwrap());
+        let tightened = AggregateMatchProof { thresholds: vec![4, 8], ..proof.clone() };
+        assert!(!circuit.verify_aggregate_match_proof(&tightened, &commitments).unwrap());
+        
+        // Either pair over its threshold fails the whole aggregate
+        assert!(matches!(
+            circuit.generate_aggregate_match_proof(&[(&first_probe, &first_reference, 3), (&second_probe, &second_reference, 9)]),
+            Err(CircuitError::ThresholdExceeded { expected: 3, actual: 4 })
+        ));
+        assert!(matches!(
+            circuit.generate_aggregate_match_proof(&[(&first_probe, &first_reference, 4), (&second_probe, &second_reference, 8)]),
+            Err(CircuitError::ThresholdExceeded { expected: 8, actual: 9 })
+        ));
+        
+        assert!(matches!(
+            circuit.generate_aggregate_match_proof(&[]),
+            Err(CircuitError::InvalidParameter(_))
+        ));
+    }
+    
+    #[test]
+    fn test_continuity_proof_detects_subject_swap() {
+        let circuit = BiometricCircuit::new(4, 100);
+        let scalars = |values: [u64; 4]| values.map(Scalar::from).to_vec();
+        let reference = scalars([10, 10, 10, 10]);
+        let frames = [
+            scalars([10, 10, 10, 11]),
+            scalars([10, 10, 11, 11]),
+            scalars([10, 11, 11, 11]),
+        ];
+        
+        // Each frame drifts by one unit from the last
+        for pair in frames.windows(2) {
+            let (previous, current) = (&pair[0], &pair[1]);
+            let proof = circuit.generate_continuity_proof(current, previous, &reference, 5).unwrap();
+            assert_eq!(proof.continuity_threshold, 5);
+            
+            let commitments = committed(&[current.clone(), reference.clone()].concat());
+            assert!(circuit.verify_continuity_proof(&proof, &commitments, &committed(previous)).unwrap());
+            
+            // The proof is bound to its previous frame and continuity threshold
+            assert!(!circuit.verify_continuity_proof(&proof, &commitments, &committed(&reference)).unwrap());
+            let relaxed = ContinuityProof { continuity_threshold: 50, ..proof };
+            assert!(!circuit.verify_continuity_proof(&relaxed, &commitments, &committed(previous)).unwrap());
+        }
+        
+        // A different subject still within the enrollment threshold (distance 72)
+        let swapped = scalars([16, 4, 10, 10]);
+        assert!(circuit.generate_proof(&swapped, &reference).is_ok());
+        assert!(matches!(
+            circuit.generate_continuity_proof(&swapped, &frames[2], &reference, 5),
+            Err(CircuitError::ThresholdExceeded { expected: 5, actual: 87 })
+        ));
+    }
+    
+    #[test]
+    fn test_reordered_commitments_fail_verification() {
+        let circuit = BiometricCircuit::new(4, 100);
+        let current = vec![Scalar::from(1u64), Scalar::from(2u64), Scalar::from(3u64), Scalar::from(4u64)];
+        let reference = vec![Scalar::from(2u64), Scalar::from(3u64), Scalar::from(4u64), Scalar::from(5u64)];
+        
+        let proof = circuit.generate_proof(&current, &reference).unwrap();
+        let mut commitments = committed(&[current, reference].concat());
+        assert!(circuit.verify_proof(&proof, &commitments).unwrap());
+        
+        commitments.swap(0, 1);
+        assert!(!circuit.verify_proof(&proof, &commitments).unwrap());
+    }
+    
+    #[test]
+    fn test_tampered_proof_fails_verification() {
+        let circuit = BiometricCircuit::new(4, 100);
+        let current = vec![Scalar::from(1u64), Scalar::from(2u64), Scalar::from(3u64), Scalar::from(4u64)];
+        let reference = vec![Scalar::from(2u64), Scalar::from(3u64), Scalar::from(4u64), Scalar::from(5u64)];
+        let commitments = committed(&[current.clone(), reference.clone()].concat());
+        
+        let proof = circuit.generate_proof(&current, &reference).unwrap();
+        assert!(circuit.verify_proof(&proof, &commitments).unwrap());
+        
+        // Flip a byte inside the first committed point
+        let mut tampered = proof.clone();
+        tampered[10] ^= 0x01;
+        assert!(!circuit.verify_proof(&tampered, &commitments).unwrap());
+        
+        // Malformed input is an error rather than a failed verification
+        assert!(matches!(
+            circuit.verify_proof(&[0u8; 32], &commitments),
+            Err(CircuitError::ProofVerificationFailed(_))
+        ));
+        assert!(matches!(
+            circuit.verify_proof(&proof, &commitments[..7]),
+            Err(CircuitError::ProofVerificationFailed(_))
+        ));
+    }
+    
+    #[cfg(feature = "poseidon")]
+    #[test]
+    fn test_linkable_proofs_share_tag() {
+        let circuit = BiometricCircuit::new(4, 1000);
+        let reference = vec![Scalar::from(10u64), Scalar::from(20u64), Scalar::from(30u64), Scalar::from(40u64)];
+        let probe_a = vec![Scalar::from(11u64), Scalar::from(20u64), Scalar::from(30u64), Scalar::from(40u64)];
+        let probe_b = vec![Scalar::from(10u64), Scalar::from(21u64), Scalar::from(29u64), Scalar::from(40u64)];
+        
+        // Different probes against the same reference and context link
+        let first = circuit.generate_linkable_proof(&probe_a, &reference, b"merchant-42").unwrap();
+        let second = circuit.generate_linkable_proof(&probe_b, &reference, b"merchant-42").unwrap();
+        assert_eq!(first.linking_tag(), second.linking_tag());
+        assert!(first.is_linked_to(&second));
+        assert_ne!(first.proof, second.proof);
+        
+        // A different reference does not
+        let other_reference = vec![Scalar::from(10u64), Scalar::from(20u64), Scalar::from(30u64), Scalar::from(41u64)];
+        let other = circuit.generate_linkable_proof(&probe_a, &other_reference, b"merchant-42").unwrap();
+        assert_ne!(first.linking_tag(), other.linking_tag());
+        assert!(!first.is_linked_to(&other));
+        
+        // Nor does the same reference under another context
+        let elsewhere = circuit.generate_linkable_proof(&probe_a, &reference, b"merchant-7").unwrap();
+        assert_ne!(first.linking_tag(), elsewhere.linking_tag());
+        assert!(!first.is_linked_to(&elsewhere));
+    }
+    
+    #[cfg(feature = "poseidon")]
+    #[test]
+    fn test_linkable_proof_constrains_tag() {
+        let circuit = BiometricCircuit::new(4, 1000);
+        let reference = vec![Scalar::from(10u64), Scalar::from(20u64), Scalar::from(30u64), Scalar::from(40u64)];
+        let probe = vec![Scalar::from(11u64), Scalar::from(20u64), Scalar::from(30u64), Scalar::from(40u64)];
+        let commitments = committed(&[probe.clone(), reference.clone()].concat());
+        
+        let linkable = circuit.generate_linkable_proof(&probe, &reference, b"merchant-42").unwrap();
+        assert!(circuit.verify_linkable_proof(&linkable, &commitments).unwrap());
+        
+        // A tag lifted from a proof over another reference is refused
+        let other_reference = vec![Scalar::from(10u64), Scalar::from(20u64), Scalar::from(30u64), Scalar::from(41u64)];
+        let other = circuit.generate_linkable_proof(&probe, &other_reference, b"merchant-42").unwrap();
+        let forged = LinkableProof { tag: other.tag.clone(), ..linkable.clone() };
+        assert!(!circuit.verify_linkable_proof(&forged, &commitments).unwrap());
+        
+        // As is a prover that picks its own tag and transcript for the reference
+        let chosen = LinkingTag::derive(&other_reference, b"merchant-42");
+        let mut transcript = circuit.linkable_transcript(b"merchant-42", &chosen);
+        let mut prover = Prover::new(&circuit.pedersen_gens, &mut transcript);
+        let (current_vars, reference_vars) = BiometricCircuit::commit_embeddings(&mut prover, &probe, &reference);
+        BiometricCircuit::bound_coordinates(&mut prover, &current_vars, &reference_vars, Some((&probe, &reference))).unwrap();
+        BiometricGadgets::linking_tag_gadget(&mut prover, &reference_vars, LinkingTag::context_scalar(b"merchant-42"), chosen).unwrap();
+        let distance_var = BiometricGadgets::distance_gadget(&mut prover, &current_vars, &reference_vars).unwrap();
+        BiometricConstraints::add_threshold_constraint_with_bits(&mut prover, distance_var, 1000, circuit.range_bits).unwrap();
+        let proof = prover.prove(&circuit.gens_for(circuit.linkable_multipliers()).unwrap()).unwrap();
+        let dishonest = LinkableProof { proof: proof.to_bytes(), context: b"merchant-42".to_vec(), tag: chosen.to_bytes().to_vec() };
+        assert!(!circuit.verify_linkable_proof(&dishonest, &commitments).unwrap());
+        
+        // Moving the proof to another context fails too
+        let moved = LinkableProof { context: b"merchant-7".to_vec(), ..linkable.clone() };
+        assert!(!circuit.verify_linkable_proof(&moved, &commitments).unwrap());
+        
+        let malformed = LinkableProof { tag: vec![0xff; 32], ..linkable };
+        assert!(matches!(
+            circuit.verify_linkable_proof(&malformed, &commitments),
+            Err(CircuitError::ProofVerificationFailed(_))
+        ));
+    }
+    
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_chunked_proof_matches_monolithic() {
+        let circuit = BiometricCircuit::new(512, 1000);
+        let current: Vec<Scalar> = (0..512u64).map(|i| Scalar::from(10 + i % 7)).collect();
+        
+        // One unit of deviation in every eighth coordinate: distance 64
+        let reference: Vec<Scalar> = (0..512u64)
+            .map(|i| Scalar::from(10 + i % 7 + u64::from(i % 8 == 0)))
+            .collect();
+        let commitments = committed(&[current.clone(), reference.clone()].concat());
+        
+        let chunked = circuit.generate_chunked_proof(&current, &reference, 128).unwrap();
+        assert_eq!(chunked.partial_commitments.len(), 4);
+        assert!(circuit.verify_chunked_proof(&chunked, &commitments).unwrap());
+        
+        // The monolithic proof reaches the same outcome for the same pair
+        let monolithic = circuit.generate_proof(&current, &reference).unwrap();
+        assert_eq!(
+            circuit.verify_proof(&monolithic, &commitments).unwrap(),
+            circuit.verify_chunked_proof(&chunked, &commitments).unwrap()
+        );
+        
+        // Swapping a partial commitment breaks the sum
+        let mut tampered = chunked.clone();
+        tampered.partial_commitments.swap(0, 1);
+        assert!(!circuit.verify_chunked_proof(&tampered, &commitments).unwrap());
+        
+        // The proof is bound to the chunking it was made with
+        let mut rechunked = chunked.clone();
+        rechunked.chunk_size = 256;
+        assert!(circuit.verify_chunked_proof(&rechunked, &commitments).is_err());
+        
+        // Over-threshold pairs cannot be proven at all, chunked or not
+        let far: Vec<Scalar> = (0..512u64).map(|i| Scalar::from(20 + i % 7)).collect();
+        assert!(matches!(
+            circuit.generate_chunked_proof(&current, &far, 128),
+            Err(CircuitError::ThresholdExceeded { expected: 1000, .. })
+        ));
+        assert!(matches!(
+            circuit.generate_proof(&current, &far),
+            Err(CircuitError::ThresholdExceeded { expected: 1000, .. })
+        ));
+    }
+    
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_prove_chunked_beyond_max_embedding_size() {
+        let size = 2 * crate::config::MAX_EMBEDDING_SIZE;
+        let circuit = BiometricCircuit::new(size, 1000);
+        let current: Vec<Scalar> = (0..size as u64).map(|i| Scalar::from(10 + i % 7)).collect();
+        
+        // One unit of deviation in every fourth coordinate: distance 256
+        let reference: Vec<Scalar> = (0..size as u64)
+            .map(|i| Scalar::from(10 + i % 7 + u64::from(i % 4 == 0)))
+            .collect();
+        
+        let proof = circuit.prove_chunked(&current, &reference, 256).unwrap();
+        assert_eq!(proof.metadata.chunk_size, Some(256));
+        assert_eq!(proof.metadata.chunk_count, Some(4));
+        assert_eq!(proof.commitments.len(), 2 * size + 4);
+        assert!(circuit.verify(&proof).unwrap());
+        
+        // The recorded layout must match the carried commitments
+        let mut miscounted = proof.clone();
+        miscounted.metadata.chunk_count = Some(3);
+        assert!(circuit.verify(&miscounted).is_err());
+        
+        let mut rechunked = proof;
+        rechunked.metadata.chunk_size = Some(512);
+        assert!(circuit.verify(&rechunked).is_err());
+    }
+    
+    #[test]
+    fn test_seeded_chunked_commitments_are_reproducible() {
+        use rand_chacha::rand_core::SeedableRng;
+        use rand_chacha::ChaCha20Rng;
+        
+        let circuit = BiometricCircuit::new(6, 100);
+        let current: Vec<Scalar> = (1..=6u64).map(Scalar::from).collect();
+        let reference: Vec<Scalar> = (1..=6u64).map(|v| Scalar::from(v + 1)).collect();
+        let prove = |seed: u64| {
+            let mut rng = ChaCha20Rng::seed_from_u64(seed);
+            circuit.prove_chunked_with_rng(&current, &reference, 4, &mut rng).unwrap()
+        };
+        
+        let first = prove(42);
+        let second = prove(42);
+        assert_eq!(first.commitments, second.commitments);
+        assert_eq!(first.public_inputs.commitment_hash, second.public_inputs.commitment_hash);
+        assert!(circuit.verify(&first).unwrap());
+        
+        assert_ne!(prove(43).commitments, first.commitments);
+    }
+    
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_chunked_proof_uneven_chunks() {
+        let circuit = BiometricCircuit::new(6, 100);
+        let current: Vec<Scalar> = (1..=6u64).map(Scalar::from).collect();
+        let reference: Vec<Scalar> = (1..=6u64).map(|v| Scalar::from(v + 1)).collect();
+        let commitments = committed(&[current.clone(), reference.clone()].concat());
+        
+        // Chunks of 4 and 2
+        let chunked = circuit.generate_chunked_proof(&current, &reference, 4).unwrap();
+        assert_eq!(chunked.partial_commitments.len(), 2);
+        assert!(circuit.verify_chunked_proof(&chunked, &commitments).unwrap());
+        
+        // Commitments to a different probe do not verify
+        let other = committed(&[reference.clone(), reference].concat());
+        assert!(!circuit.verify_chunked_proof(&chunked, &other).unwrap());
     }
 }
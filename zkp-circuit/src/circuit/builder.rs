@@ -0,0 +1,228 @@
+use alloc::vec::Vec;
+use alloc::format;
+use crate::backend::bulletproofs::PedersenGens;
+
+use crate::circuit::BiometricCircuit;
+use crate::config::{SimilarityMetric, DEFAULT_EMBEDDING_SIZE, DEFAULT_THRESHOLD, MAX_EMBEDDING_SIZE, MAX_GENERATOR_CAPACITY, MAX_THRESHOLD, MIN_THRESHOLD, RANGE_BITS};
+use crate::crypto::CommitmentScheme;
+use crate::types::{CircuitError, CircuitResult};
+
+/// Fluent configuration for `BiometricCircuit`
+///
+/// Starts from the same defaults as `BiometricCircuit::new` with
+/// `DEFAULT_EMBEDDING_SIZE` and `DEFAULT_THRESHOLD`; `build` checks the
+/// result against the limits in `config`.
+#[derive(Clone)]
+pub struct BiometricCircuitBuilder {
+    embedding_size: usize,
+    threshold: u64,
+    range_bits: usize,
+    metric: SimilarityMetric,
+    pedersen_gens: Option<PedersenGens>,
+    gens_capacity: Option<usize>,
+    domain: Vec<u8>,
+}
+
+impl Default for BiometricCircuitBuilder {
+    fn default() -> Self {
+        Self {
+            embedding_size: DEFAULT_EMBEDDING_SIZE,
+            threshold: DEFAULT_THRESHOLD,
+            range_bits: RANGE_BITS,
+            metric: SimilarityMetric::default(),
+            pedersen_gens: None,
+            gens_capacity: None,
+            domain: Vec::new(),
+        }
+    }
+}
+
+impl BiometricCircuitBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    pub fn embedding_size(mut self, embedding_size: usize) -> Self {
+        self.embedding_size = embedding_size;
+        self
+    }
+    
+    pub fn threshold(mut self, threshold: u64) -> Self {
+        self.threshold = threshold;
+        self
+    }
+    
+    /// Bits the threshold gate decomposes `threshold - distance` over
+    pub fn range_bits(mut self, range_bits: usize) -> Self {
+        self.range_bits = range_bits;
+        self
+    }
+    
+    pub fn metric(mut self, metric: SimilarityMetric) -> Self {
+        self.metric = metric;
+        self
+    }
+    
+    /// Use explicit Pedersen generators instead of the default ones
+    pub fn pedersen_gens(mut self, pedersen_gens: PedersenGens) -> Self {
+        self.pedersen_gens = Some(pedersen_gens);
+        self
+    }
+    
+    /// Allocate this many Bulletproof generators instead of the computed capacity
+    ///
+    /// The circuit then never grows them: proofs needing more fail with
+    /// `CapacityExceeded`.
+    pub fn gens_capacity(mut self, capacity: usize) -> Self {
+        self.gens_capacity = Some(capacity);
+        self
+    }
+    
+    /// Bind proofs to a deployment `domain`
+    pub fn domain(mut self, domain: &[u8]) -> Self {
+        self.domain = domain.to_vec();
+        self
+    }
+    
+    /// Validate the configuration and create the circuit
+    ///
+    /// The embedding size must be in `1..=MAX_EMBEDDING_SIZE`, the threshold
+    /// in `MIN_THRESHOLD..=MAX_THRESHOLD`, and `range_bits` at most 64 and
+    /// wide enough to represent the threshold. The generator capacity
+    /// defaults to `BiometricCircuit::generator_capacity`; an explicit one
+    /// must cover it (otherwise `CapacityExceeded`), and neither may exceed
+    /// `MAX_GENERATOR_CAPACITY`.
+    pub fn build(self) -> CircuitResult<BiometricCircuit> {
+        if self.embedding_size == 0 || self.embedding_size > MAX_EMBEDDING_SIZE {
+            return Err(CircuitError::InvalidParameter(
+                format!("Embedding size {} must be between 1 and {}", self.embedding_size, MAX_EMBEDDING_SIZE)
+            ));
+        }
+        
+        if !(MIN_THRESHOLD..=MAX_THRESHOLD).contains(&self.threshold) {
+            return Err(CircuitError::InvalidParameter(
+                format!("Threshold {} must be between {} and {}", self.threshold, MIN_THRESHOLD, MAX_THRESHOLD)
+            ));
+        }
+        
+        let required_bits = (u64::BITS - self.threshold.leading_zeros()) as usize;
+        if self.range_bits < required_bits || self.range_bits > 64 {
+            return Err(CircuitError::InvalidParameter(
+                format!(
+                    "Range bits {} must be between {} and 64 for threshold {}",
+                    self.range_bits, required_bits, self.threshold
+                )
+            ));
+        }
+        
+        let required_capacity = BiometricCircuit::generator_capacity(self.embedding_size, self.metric, self.range_bits);
+        let capacity = self.gens_capacity.unwrap_or(required_capacity);
+        if capacity < required_capacity {
+            return Err(CircuitError::CapacityExceeded { needed: required_capacity, available: capacity });
+        }
+        if capacity > MAX_GENERATOR_CAPACITY {
+            return Err(CircuitError::InvalidParameter(
+                format!("Generator capacity {} exceeds maximum {}", capacity, MAX_GENERATOR_CAPACITY)
+            ));
+        }
+        
+        Ok(BiometricCircuit {
+            embedding_size: self.embedding_size,
+            threshold: self.threshold,
+            pedersen_gens: self.pedersen_gens.unwrap_or_default(),
+            bulletproof_gens: BiometricCircuit::shared_gens(capacity),
+            commitment_scheme: CommitmentScheme::new(),
+            metric: self.metric,
+            range_bits: self.range_bits,
+            domain: self.domain,
+            bind_commitment_hash: false,
+            fixed_gens: self.gens_capacity.is_some(),
+        })
+    }
+}
+
+impl BiometricCircuit {
+    /// Start configuring a circuit; see `BiometricCircuitBuilder`
+    pub fn builder() -> BiometricCircuitBuilder {
+        BiometricCircuitBuilder::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "std")]
+    use crate::types::BiometricEmbedding;
+    
+    #[test]
+    fn test_builder_defaults_match_new() {
+        let built = BiometricCircuit::builder().build().unwrap();
+        let direct = BiometricCircuit::new(DEFAULT_EMBEDDING_SIZE, DEFAULT_THRESHOLD);
+        
+        assert_eq!(built.embedding_size, direct.embedding_size);
+        assert_eq!(built.threshold, direct.threshold);
+        assert_eq!(built.metric, direct.metric);
+        assert_eq!(built.range_bits, direct.range_bits);
+        assert_eq!(built.pedersen_gens.B, direct.pedersen_gens.B);
+        assert_eq!(built.pedersen_gens.B_blinding, direct.pedersen_gens.B_blinding);
+        assert_eq!(built.bulletproof_gens.gens_capacity, direct.bulletproof_gens.gens_capacity);
+        assert_eq!(built.commitment_scheme.epoch, direct.commitment_scheme.epoch);
+    }
+    
+    #[test]
+    fn test_builder_rejects_out_of_range_parameters() {
+        for threshold in [MIN_THRESHOLD - 1, MAX_THRESHOLD + 1] {
+            assert!(matches!(
+                BiometricCircuit::builder().threshold(threshold).build(),
+                Err(CircuitError::InvalidParameter(_))
+            ));
+        }
+        
+        assert!(BiometricCircuit::builder().embedding_size(0).build().is_err());
+        assert!(BiometricCircuit::builder().embedding_size(MAX_EMBEDDING_SIZE + 1).build().is_err());
+        
+        // 1000 needs 10 bits
+        assert!(BiometricCircuit::builder().range_bits(9).build().is_err());
+        assert!(BiometricCircuit::builder().range_bits(65).build().is_err());
+        assert!(BiometricCircuit::builder().range_bits(10).build().is_ok());
+        
+        // 4 dimensions need 373 multipliers, padded to 512 generators
+        assert!(matches!(
+            BiometricCircuit::builder().embedding_size(4).gens_capacity(64).build(),
+            Err(CircuitError::CapacityExceeded { needed: 512, available: 64 })
+        ));
+        assert!(BiometricCircuit::builder().gens_capacity(MAX_GENERATOR_CAPACITY * 2).build().is_err());
+        let explicit = BiometricCircuit::builder().embedding_size(4).gens_capacity(1024).build().unwrap();
+        assert_eq!(explicit.bulletproof_gens.gens_capacity, 1024);
+    }
+    
+    #[test]
+    fn test_max_embedding_size_fits_generator_cap() {
+        for metric in [SimilarityMetric::SquaredDistance, SimilarityMetric::CosineSimilarity] {
+            assert!(BiometricCircuit::generator_capacity(MAX_EMBEDDING_SIZE, metric, 64) <= MAX_GENERATOR_CAPACITY);
+        }
+    }
+    
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_builder_range_bits_reach_the_proof() {
+        let circuit = BiometricCircuit::builder()
+            .embedding_size(4)
+            .threshold(100)
+            .range_bits(16)
+            .build()
+            .unwrap();
+        let current = BiometricEmbedding::new(vec![10, -20, 30, 40]).unwrap();
+        let reference = BiometricEmbedding::new(vec![12, -20, 31, 40]).unwrap();
+        
+        let proof = circuit.prove(&current, &reference).unwrap();
+        assert_eq!(proof.metadata.circuit_params.range_bits, 16);
+        assert!(circuit.verify(&proof).unwrap());
+        
+        // A verifier expecting the default width refuses it
+        assert!(matches!(
+            BiometricCircuit::new(4, 100).verify(&proof),
+            Err(CircuitError::ParameterMismatch(_))
+        ));
+    }
+}
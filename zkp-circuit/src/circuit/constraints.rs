@@ -1,10 +1,15 @@
-use bulletproofs::r1cs::{Prover, Verifier, Variable, LinearCombination, ConstraintSystem as R1CSConstraintSystem};
-use curve25519_dalek_ng::scalar::Scalar;
-use std::borrow::BorrowMut;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use alloc::format;
+use crate::backend::bulletproofs::r1cs::{Prover, Verifier, Variable, LinearCombination, ConstraintSystem as R1CSConstraintSystem};
+use crate::backend::curve25519::scalar::Scalar;
+use core::borrow::BorrowMut;
 use merlin::Transcript;
 use crate::types::{CircuitError, CircuitResult};
-use crate::crypto::FieldUtils;
 use crate::utils::scalar_utils::ScalarUtils;
+use crate::config::RANGE_BITS;
+use crate::circuit::gadgets::BiometricGadgets;
+use crate::crypto::FieldUtils;
 
 /// Biometric constraint system for R1CS
 pub struct BiometricConstraints;
@@ -19,7 +24,7 @@ impl BiometricConstraints {
         c: Variable,
     )
     where
-        T: std::borrow::BorrowMut<merlin::Transcript>,
+        T: core::borrow::BorrowMut<merlin::Transcript>,
     {
         // Create linear combinations
         let a_lc: LinearCombination = a.into();
@@ -40,9 +45,9 @@ impl BiometricConstraints {
         total: Variable,
     )
     where
-        T: std::borrow::BorrowMut<merlin::Transcript>,
+        T: core::borrow::BorrowMut<merlin::Transcript>,
     {
-        let zero_scalar = Scalar::zero();
+        let zero_scalar = ScalarUtils::zero();
         let (_, zero_var) = prover.commit(zero_scalar, zero_scalar);
         let zero_lc: LinearCombination = zero_var.into();
         
@@ -57,6 +62,10 @@ impl BiometricConstraints {
     
     /// Add range constraint
     /// Constrains: var < max_value
+    ///
+    /// `var` is decomposed into `bit_length` boolean variables. Unless
+    /// `max_value` is exactly `2^bit_length`, `max_value - 1 - var` is
+    /// decomposed as well. `max_value` must fit in `bit_length` bits.
     pub fn add_range_constraint<T>(
         prover: &mut Prover<T>,
         var: Variable,
@@ -64,32 +73,99 @@ impl BiometricConstraints {
         bit_length: usize,
     ) -> CircuitResult<()>
     where
-        T: std::borrow::BorrowMut<merlin::Transcript>,
+        T: core::borrow::BorrowMut<merlin::Transcript>,
     {
-        // range constraint - ensure it's within bounds
-        // In a full implementation, this would do bit decomposition
-        let max_scalar = Scalar::from(max_value);
-        let (_, max_var) = prover.commit(max_scalar, Scalar::zero());
+        let needs_upper_bound = range_needs_upper_bound(max_value, bit_length)?;
+        
+        let var_lc: LinearCombination = var.into();
+        let value = FieldUtils::scalar_to_u64(&prover.eval(&var_lc)).unwrap_or(u64::MAX);
+        BiometricGadgets::range_gadget(prover, var_lc.clone(), Some(value), bit_length)?;
+        
+        if needs_upper_bound {
+            BiometricGadgets::range_gadget(
+                prover,
+                LinearCombination::from(Scalar::from(max_value - 1)) - var_lc,
+                Some((max_value - 1).wrapping_sub(value)),
+                bit_length,
+            )?;
+        }
         
-        // Basic constraint: var should be less than max_value
         Ok(())
     }
     
     /// Add threshold comparison constraint
     /// Constrains: value ≤ threshold
+    ///
+    /// `threshold - value` is bit-decomposed over `RANGE_BITS`, so no proof
+    /// exists for a value above the threshold. Fails early with
+    /// `ThresholdExceeded` when the prover's own assignment is too large.
     pub fn add_threshold_constraint<T>(
         prover: &mut Prover<T>,
         value: Variable,
         threshold: u64,
     ) -> CircuitResult<()>
     where
-        T: std::borrow::BorrowMut<merlin::Transcript>,
+        T: core::borrow::BorrowMut<merlin::Transcript>,
+    {
+        Self::add_threshold_constraint_with_bits(prover, value, threshold, RANGE_BITS)
+    }
+    
+    /// `add_threshold_constraint` decomposing the gap over `range_bits`
+    pub fn add_threshold_constraint_with_bits<T>(
+        prover: &mut Prover<T>,
+        value: Variable,
+        threshold: u64,
+        range_bits: usize,
+    ) -> CircuitResult<()>
+    where
+        T: core::borrow::BorrowMut<merlin::Transcript>,
+    {
+        let value_lc: LinearCombination = value.into();
+        let actual = FieldUtils::scalar_to_u64(&prover.eval(&value_lc)).unwrap_or(u64::MAX);
+        if actual > threshold {
+            return Err(CircuitError::ThresholdExceeded { expected: threshold, actual });
+        }
+        
+        BiometricGadgets::range_gadget(
+            prover,
+            LinearCombination::from(Scalar::from(threshold)) - value_lc,
+            Some(threshold - actual),
+            range_bits,
+        )
+    }
+    
+    /// Add interval constraint
+    /// Constrains: lower ≤ value ≤ upper
+    pub fn add_interval_constraint<T>(
+        prover: &mut Prover<T>,
+        value: Variable,
+        lower: u64,
+        upper: u64,
+    ) -> CircuitResult<()>
+    where
+        T: core::borrow::BorrowMut<merlin::Transcript>,
     {
-        // Simplified threshold constraint
-        let threshold_scalar = Scalar::from(threshold);
-        let (_, threshold_var) = prover.commit(threshold_scalar, Scalar::zero());
+        if lower > upper {
+            return Err(CircuitError::InvalidParameter(
+                format!("Interval lower bound {} exceeds upper bound {}", lower, upper)
+            ));
+        }
+        
+        let value_lc: LinearCombination = value.into();
+        let max_value = 1u64 << RANGE_BITS;
+        
+        // value - lower ≥ 0 and upper - value ≥ 0, each as a committed gap
+        for gap_lc in [
+            value_lc.clone() - Scalar::from(lower),
+            LinearCombination::from(Scalar::from(upper)) - value_lc,
+        ] {
+            let gap_var = prover.allocate(Some(prover.eval(&gap_lc))).map_err(|_| {
+                CircuitError::ProofGenerationFailed("Failed to allocate interval gap".to_string())
+            })?;
+            prover.constrain(gap_lc - gap_var);
+            Self::add_range_constraint(prover, gap_var, max_value, RANGE_BITS)?;
+        }
         
-        // record both values
         Ok(())
     }
     
@@ -102,39 +178,79 @@ impl BiometricConstraints {
         threshold: u64,
     ) -> CircuitResult<Variable>
     where
-        T: std::borrow::BorrowMut<merlin::Transcript>,
+        T: core::borrow::BorrowMut<merlin::Transcript>,
     {
-        if current_embedding.len() != reference_embedding.len() {
-            return Err(CircuitError::InvalidParameter(
-                "Embedding size mismatch".to_string()
-            ));
-        }
-        
-        let mut squared_diff_vars = Vec::new();
-        
-        // For each dimension, compute (current[i] - reference[i])²
+        // Evaluate the committed coordinates for the threshold witness
+        let mut distance = ScalarUtils::zero();
         for (curr, refer) in current_embedding.iter().zip(reference_embedding.iter()) {
-            // Create variable for squared difference
-            let zero_scalar = Scalar::zero();
-            let (_, diff_sq_var) = prover.commit(zero_scalar, zero_scalar);
-            
-            // Add constraint: (current - reference)² = diff_sq_var
-            Self::add_squared_difference_constraint(prover, *curr, *refer, diff_sq_var);
-            
-            squared_diff_vars.push(diff_sq_var);
+            let diff = prover.eval(&(*curr - *refer));
+            distance += diff * diff;
         }
+        let distance = FieldUtils::scalar_to_u64(&distance).unwrap_or(u64::MAX);
         
-        // Sum all squared differences
-        let (_, total_distance_var) = prover.commit(Scalar::zero(), Scalar::zero());
-        Self::add_sum_constraint(prover, &squared_diff_vars, total_distance_var);
-        
-        // Add threshold constraint
-        Self::add_threshold_constraint(prover, total_distance_var, threshold)?;
-        
-        Ok(total_distance_var)
+        similarity_constraints(prover, current_embedding, reference_embedding, threshold, Some(distance))
     }
 }
 
+/// Squared-difference and threshold constraints shared by prover and verifier
+///
+/// Builds `distance = Σ (current_i - reference_i)²` with `distance_gadget`
+/// and bit-decomposes `threshold - distance` over `RANGE_BITS`. `distance`
+/// is the prover's assignment and `None` on the verifier side; the prover
+/// fails early with `ThresholdExceeded` when it is above the threshold.
+fn similarity_constraints<CS: R1CSConstraintSystem>(
+    cs: &mut CS,
+    current_embedding: &[Variable],
+    reference_embedding: &[Variable],
+    threshold: u64,
+    distance: Option<u64>,
+) -> CircuitResult<Variable> {
+    if current_embedding.len() != reference_embedding.len() {
+        return Err(CircuitError::InvalidParameter(
+            "Embedding size mismatch".to_string()
+        ));
+    }
+    
+    if let Some(actual) = distance.filter(|&d| d > threshold) {
+        return Err(CircuitError::ThresholdExceeded { expected: threshold, actual });
+    }
+    
+    let distance_var = BiometricGadgets::distance_gadget(cs, current_embedding, reference_embedding)?;
+    BiometricGadgets::range_gadget(
+        cs,
+        LinearCombination::from(Scalar::from(threshold)) - distance_var,
+        distance.map(|d| threshold - d),
+        RANGE_BITS,
+    )?;
+    
+    Ok(distance_var)
+}
+
+/// Check that `[0, max_value)` fits in `bit_length` bits
+///
+/// Returns whether the range is narrower than `[0, 2^bit_length)`, in which
+/// case the upper bound needs its own decomposition.
+fn range_needs_upper_bound(max_value: u64, bit_length: usize) -> CircuitResult<bool> {
+    if bit_length == 0 || bit_length > 64 {
+        return Err(CircuitError::InvalidParameter(
+            format!("Unsupported range bit length {}", bit_length)
+        ));
+    }
+    
+    if max_value == 0 {
+        return Err(CircuitError::InvalidParameter("Range upper bound must be positive".to_string()));
+    }
+    
+    let needed_bits = (64 - (max_value - 1).leading_zeros()) as usize;
+    if needed_bits > bit_length {
+        return Err(CircuitError::InvalidParameter(
+            format!("Range bound {} needs {} bits but only {} are allowed", max_value, needed_bits, bit_length)
+        ));
+    }
+    
+    Ok(bit_length == 64 || max_value != 1u64 << bit_length)
+}
+
 /// Verification constraint system (mirrors the proving constraints)
 pub struct VerificationConstraints;
 
@@ -142,60 +258,142 @@ impl VerificationConstraints {
     /// Add verification constraints for biometric similarity
     pub fn add_biometric_verification_constraints<T>(
         verifier: &mut Verifier<T>,
-        public_commitments: &[curve25519_dalek_ng::ristretto::CompressedRistretto],
+        public_commitments: &[crate::backend::curve25519::ristretto::CompressedRistretto],
         embedding_size: usize,
         threshold: u64,
     ) -> CircuitResult<()>
     where
         T: BorrowMut<Transcript>
     {
-        // Commit to public values
-        let mut commitment_vars = Vec::new();
-        for commitment in public_commitments {
-            let var = verifier.commit(*commitment);
-            commitment_vars.push(var);
+        if public_commitments.len() != embedding_size * 2 {
+            return Err(CircuitError::ProofVerificationFailed(
+                format!("Expected {} commitments, got {}", embedding_size * 2, public_commitments.len())
+            ));
         }
         
-        // The verification constraints should mirror the proving constraints
-        // This is a simplified version - in practice, you need to carefully
-        // reconstruct the exact same constraint system
+        // Commit to public values: current embedding first, then reference
+        let commitment_vars: Vec<Variable> = public_commitments
+            .iter()
+            .map(|commitment| verifier.commit(*commitment))
+            .collect();
+        let (current_vars, reference_vars) = commitment_vars.split_at(embedding_size);
         
-        if commitment_vars.len() >= embedding_size * 2 {
-            let current_vars = &commitment_vars[0..embedding_size];
-            let reference_vars = &commitment_vars[embedding_size..embedding_size * 2];
-            
-            // Add the same biometric similarity constraints
-            Self::verify_biometric_similarity(verifier, current_vars, reference_vars, threshold)?;
+        // Add the same biometric similarity constraints
+        Self::verify_biometric_similarity(verifier, current_vars, reference_vars, threshold)
+    }
+    
+    /// Mirror of `BiometricConstraints::add_range_constraint`
+    /// Constrains: var < max_value
+    pub fn add_range_constraint<T>(
+        verifier: &mut Verifier<T>,
+        var: Variable,
+        max_value: u64,
+        bit_length: usize,
+    ) -> CircuitResult<()>
+    where
+        T: BorrowMut<Transcript>
+    {
+        let needs_upper_bound = range_needs_upper_bound(max_value, bit_length)?;
+        
+        BiometricGadgets::range_gadget(verifier, var.into(), None, bit_length)?;
+        
+        if needs_upper_bound {
+            BiometricGadgets::range_gadget(
+                verifier,
+                LinearCombination::from(Scalar::from(max_value - 1)) - var,
+                None,
+                bit_length,
+            )?;
         }
         
         Ok(())
     }
     
-    /// Verify biometric similarity constraints (simplified)
-    fn verify_biometric_similarity<T>(
+    /// Mirror of `BiometricConstraints::add_threshold_constraint`
+    /// Constrains: value ≤ threshold
+    pub fn add_threshold_constraint<T>(
         verifier: &mut Verifier<T>,
-        current_embedding: &[Variable],
-        reference_embedding: &[Variable],
+        value: Variable,
         threshold: u64,
     ) -> CircuitResult<()>
     where
         T: BorrowMut<Transcript>
     {
-        // Simplified verification - validate structure
-        // In full implementation, this would recreate exact same constraints
+        Self::add_threshold_constraint_with_bits(verifier, value, threshold, RANGE_BITS)
+    }
+    
+    /// Mirror of `BiometricConstraints::add_threshold_constraint_with_bits`
+    pub fn add_threshold_constraint_with_bits<T>(
+        verifier: &mut Verifier<T>,
+        value: Variable,
+        threshold: u64,
+        range_bits: usize,
+    ) -> CircuitResult<()>
+    where
+        T: BorrowMut<Transcript>
+    {
+        BiometricGadgets::range_gadget(
+            verifier,
+            LinearCombination::from(Scalar::from(threshold)) - value,
+            None,
+            range_bits,
+        )
+    }
+    
+    /// Mirror of `BiometricConstraints::add_interval_constraint`
+    /// Constrains: lower ≤ value ≤ upper
+    pub fn add_interval_constraint<T>(
+        verifier: &mut Verifier<T>,
+        value: Variable,
+        lower: u64,
+        upper: u64,
+    ) -> CircuitResult<()>
+    where
+        T: BorrowMut<Transcript>
+    {
+        if lower > upper {
+            return Err(CircuitError::InvalidParameter(
+                format!("Interval lower bound {} exceeds upper bound {}", lower, upper)
+            ));
+        }
+        
+        let value_lc: LinearCombination = value.into();
+        let max_value = 1u64 << RANGE_BITS;
         
-        if current_embedding.len() != reference_embedding.len() {
-            return Err(CircuitError::InvalidParameter("Size mismatch".to_string()));
+        for gap_lc in [
+            value_lc.clone() - Scalar::from(lower),
+            LinearCombination::from(Scalar::from(upper)) - value_lc,
+        ] {
+            let gap_var = verifier.allocate(None).map_err(|_| {
+                CircuitError::ProofVerificationFailed("Failed to allocate interval gap".to_string())
+            })?;
+            verifier.constrain(gap_lc - gap_var);
+            Self::add_range_constraint(verifier, gap_var, max_value, RANGE_BITS)?;
         }
         
         Ok(())
     }
+    
+    /// Mirror of `BiometricConstraints::add_biometric_similarity_constraint`
+    fn verify_biometric_similarity<T>(
+        verifier: &mut Verifier<T>,
+        current_embedding: &[Variable],
+        reference_embedding: &[Variable],
+        threshold: u64,
+    ) -> CircuitResult<()>
+    where
+        T: BorrowMut<Transcript>
+    {
+        similarity_constraints(verifier, current_embedding, reference_embedding, threshold, None).map(|_| ())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use bulletproofs::PedersenGens;
+    use alloc::vec::Vec;
+    use alloc::vec;
+    use crate::backend::bulletproofs::{BulletproofGens, PedersenGens};
     use merlin::Transcript;
     
     #[test]
@@ -205,13 +403,108 @@ mod tests {
         let mut prover = Prover::new(&pc_gens, &mut transcript);
         
         // Test basic constraint addition
-        let (_, var_a) = prover.commit(Scalar::from(5u64), Scalar::zero());
-        let (_, var_b) = prover.commit(Scalar::from(3u64), Scalar::zero());
-        let (_, var_c) = prover.commit(Scalar::from(4u64), Scalar::zero()); // (5-3)² = 4
+        let (_, var_a) = prover.commit(Scalar::from(5u64), ScalarUtils::zero());
+        let (_, var_b) = prover.commit(Scalar::from(3u64), ScalarUtils::zero());
+        let (_, var_c) = prover.commit(Scalar::from(4u64), ScalarUtils::zero()); // (5-3)² = 4
         
         BiometricConstraints::add_squared_difference_constraint(&mut prover, var_a, var_b, var_c);
         
-        // If we reach here without panic, the constraint was added successfully
-        assert!(true);
+        // The squared difference relation is recorded as a single linear constraint
+        assert_eq!(prover.metrics().constraints, 1);
+    }
+    
+    fn prove_range(value: u64, max_value: u64, bit_length: usize) -> bool {
+        let pc_gens = PedersenGens::default();
+        let bp_gens = BulletproofGens::new(256, 1);
+        
+        let mut transcript = Transcript::new(b"range_test");
+        let mut prover = Prover::new(&pc_gens, &mut transcript);
+        let (commitment, var) = prover.commit(Scalar::from(value), ScalarUtils::zero());
+        BiometricConstraints::add_range_constraint(&mut prover, var, max_value, bit_length).unwrap();
+        let proof = prover.prove(&bp_gens).unwrap();
+        
+        let mut transcript = Transcript::new(b"range_test");
+        let mut verifier = Verifier::new(&mut transcript);
+        let var = verifier.commit(commitment);
+        VerificationConstraints::add_range_constraint(&mut verifier, var, max_value, bit_length).unwrap();
+        verifier.verify(&proof, &pc_gens, &bp_gens).is_ok()
+    }
+    
+    #[test]
+    fn test_range_constraint_bit_decomposition() {
+        assert!(prove_range(255, 256, 8));
+        assert!(!prove_range(256, 256, 8));
+        assert!(prove_range(0, 256, 8));
+        
+        // Bounds narrower than 2^bit_length are enforced too
+        assert!(prove_range(99, 100, 8));
+        assert!(!prove_range(100, 100, 8));
+    }
+    
+    /// Prove similarity with `BiometricConstraints` and check it with `VerificationConstraints`
+    fn verify_similarity(current: &[u64], reference: &[u64], threshold: u64, verifier_threshold: u64) -> CircuitResult<bool> {
+        let pc_gens = PedersenGens::default();
+        let bp_gens = BulletproofGens::new(64, 1);
+        
+        let mut transcript = Transcript::new(b"similarity_test");
+        let mut prover = Prover::new(&pc_gens, &mut transcript);
+        let mut commit = |values: &[u64]| -> (Vec<_>, Vec<Variable>) {
+            values.iter().map(|&v| prover.commit(Scalar::from(v), ScalarUtils::zero())).unzip()
+        };
+        let (mut commitments, current_vars) = commit(current);
+        let (reference_commitments, reference_vars) = commit(reference);
+        commitments.extend(reference_commitments);
+        BiometricConstraints::add_biometric_similarity_constraint(&mut prover, &current_vars, &reference_vars, threshold)?;
+        let proof = prover.prove(&bp_gens).unwrap();
+        
+        let mut transcript = Transcript::new(b"similarity_test");
+        let mut verifier = Verifier::new(&mut transcript);
+        VerificationConstraints::add_biometric_verification_constraints(
+            &mut verifier,
+            &commitments,
+            current.len(),
+            verifier_threshold,
+        )?;
+        Ok(verifier.verify(&proof, &pc_gens, &bp_gens).is_ok())
+    }
+    
+    #[test]
+    fn test_similarity_constraints_mirror_prover() {
+        // Distance (4-1)² + 0 + (5-7)² = 13
+        assert!(verify_similarity(&[1, 2, 7], &[4, 2, 5], 20, 20).unwrap());
+        assert!(verify_similarity(&[1, 2, 7], &[4, 2, 5], 13, 13).unwrap());
+        
+        // A verifier rebuilding different constraints rejects the proof
+        assert!(!verify_similarity(&[1, 2, 7], &[4, 2, 5], 20, 19).unwrap());
+        
+        // The prover cannot claim a distance above its threshold
+        assert!(matches!(
+            verify_similarity(&[1, 2, 7], &[4, 2, 5], 12, 12),
+            Err(CircuitError::ThresholdExceeded { expected: 12, actual: 13 })
+        ));
+    }
+    
+    #[test]
+    fn test_verification_constraints_require_all_commitments() {
+        let mut transcript = Transcript::new(b"similarity_test");
+        let mut verifier = Verifier::new(&mut transcript);
+        let commitments = vec![PedersenGens::default().commit(Scalar::from(1u64), ScalarUtils::zero()).compress(); 3];
+        
+        assert!(VerificationConstraints::add_biometric_verification_constraints(&mut verifier, &commitments, 2, 100).is_err());
+    }
+    
+    #[test]
+    fn test_range_constraint_rejects_oversized_bound() {
+        let pc_gens = PedersenGens::default();
+        let mut transcript = Transcript::new(b"range_test");
+        let mut prover = Prover::new(&pc_gens, &mut transcript);
+        let (_, var) = prover.commit(Scalar::from(1u64), ScalarUtils::zero());
+        
+        for (max_value, bit_length) in [(257, 8), (0, 8), (16, 0), (16, 65)] {
+            assert!(matches!(
+                BiometricConstraints::add_range_constraint(&mut prover, var, max_value, bit_length),
+                Err(CircuitError::InvalidParameter(_))
+            ));
+        }
     }
 }
@@ -1,43 +1,433 @@
-use bulletproofs::r1cs::{Prover, Verifier, Variable, LinearCombination, ConstraintSystem};
-use curve25519_dalek_ng::scalar::Scalar;
-use std::borrow::BorrowMut;
+use alloc::string::ToString;
+#[cfg(feature = "poseidon")]
+use alloc::vec::Vec;
+use alloc::format;
+use crate::backend::bulletproofs::r1cs::{Verifier, Variable, LinearCombination, ConstraintSystem};
+use crate::backend::curve25519::scalar::Scalar;
+use core::borrow::BorrowMut;
 use merlin::Transcript;
 
+use crate::config::COSINE_RANGE_BITS;
+#[cfg(feature = "poseidon")]
+use crate::crypto::poseidon::{
+    Poseidon, POSEIDON_FULL_ROUNDS, POSEIDON_PARTIAL_ROUNDS, POSEIDON_RATE, POSEIDON_WIDTH,
+};
 use crate::types::{CircuitError, CircuitResult};
+use crate::utils::scalar_utils::ScalarUtils;
+
+/// Prover witness for `BiometricGadgets::cosine_score_gadget`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CosineScoreWitness {
+    /// Dot product of the two embeddings
+    pub dot: u64,
+    /// `floor(cos · scale)`
+    pub score: u64,
+    /// `dot² · scale² - score² · ‖current‖² · ‖reference‖²`
+    pub lower_gap: u128,
+    /// `(score + 1)² · ‖current‖² · ‖reference‖² - dot² · scale² - 1`
+    pub upper_gap: u128,
+}
 
 /// Biometric-specific gadgets for ZKP circuits
 pub struct BiometricGadgets;
 
 impl BiometricGadgets {
-    /// Distance computation gadget (simplified)
-    pub fn distance_gadget<T>(
-        prover: &mut Prover<T>,
+    /// Distance computation gadget
+    ///
+    /// Returns a variable equal to the squared Euclidean distance, using one
+    /// multiplier per coordinate plus one to materialize the sum. Generic over
+    /// the constraint system so the verifier can rebuild exactly the
+    /// constraints the prover used.
+    pub fn distance_gadget<CS: ConstraintSystem>(
+        cs: &mut CS,
         current_vars: &[Variable],
         reference_vars: &[Variable],
-    ) -> CircuitResult<Variable>
-    where
-        T: BorrowMut<Transcript>,
-    {
+    ) -> CircuitResult<Variable> {
+        let distance_lc = Self::squared_distance_gadget(cs, current_vars, reference_vars)?;
+        
+        // Materialize the distance as a variable: distance · 1
+        let (distance_var, _, _) = cs.multiply(distance_lc, ScalarUtils::one().into());
+        
+        Ok(distance_var)
+    }
+    
+    /// Squared Euclidean distance gadget
+    ///
+    /// Returns `Σ (current_i - reference_i)²` as a linear combination, using one
+    /// multiplier per coordinate. Works on both the prover and the verifier side.
+    pub fn squared_distance_gadget<CS: ConstraintSystem>(
+        cs: &mut CS,
+        current_vars: &[Variable],
+        reference_vars: &[Variable],
+    ) -> CircuitResult<LinearCombination> {
         if current_vars.len() != reference_vars.len() {
             return Err(CircuitError::InvalidParameter("Mismatched variable lengths".to_string()));
         }
         
-        // Sum of squared differences
         let mut distance_lc = LinearCombination::default();
         
         for (curr, ref_v) in current_vars.iter().zip(reference_vars.iter()) {
-            // Compute difference: diff = current - reference
             let diff = LinearCombination::from(*curr) - LinearCombination::from(*ref_v);
+            let (_, _, square) = cs.multiply(diff.clone(), diff);
+            distance_lc = distance_lc + square;
+        }
+        
+        Ok(distance_lc)
+    }
+    
+    /// Range gadget: constrains `value` to `[0, 2^bit_length)` by bit decomposition
+    ///
+    /// `assignment` is the prover's value of `value` and `None` on the verifier
+    /// side. Uses one multiplier per bit.
+    pub fn range_gadget<CS: ConstraintSystem>(
+        cs: &mut CS,
+        value: LinearCombination,
+        assignment: Option<u64>,
+        bit_length: usize,
+    ) -> CircuitResult<()> {
+        if bit_length == 0 || bit_length > 64 {
+            return Err(CircuitError::InvalidParameter(
+                format!("Unsupported range bit length {}", bit_length)
+            ));
+        }
+        
+        Self::bit_decomposition(cs, value, assignment.map(u128::from), bit_length)
+    }
+    
+    /// Coordinate range gadget: constrains `value` to `[-max_abs, max_abs]`
+    ///
+    /// Both `max_abs + value` and `max_abs - value` are decomposed over
+    /// `bit_length` bits, so `2 · max_abs` must be below `2^bit_length`. Keeps
+    /// committed coordinates in the small-integer band the distance gadgets
+    /// assume. Uses `2 · bit_length` multipliers.
+    pub fn coordinate_range_gadget<CS: ConstraintSystem>(
+        cs: &mut CS,
+        value: Variable,
+        max_abs: u64,
+        assignment: Option<i64>,
+        bit_length: usize,
+    ) -> CircuitResult<()> {
+        if bit_length == 0 || bit_length >= 64 || max_abs >= 1 << (bit_length - 1) {
+            return Err(CircuitError::InvalidParameter(
+                format!("Bound {} does not fit a {}-bit range", max_abs, bit_length)
+            ));
+        }
+        
+        // Out-of-band assignments wrap and leave the decomposition unsatisfied
+        let bound = Scalar::from(max_abs);
+        Self::range_gadget(
+            cs,
+            LinearCombination::from(bound) + value,
+            assignment.map(|v| (max_abs as i128 + v as i128) as u64),
+            bit_length,
+        )?;
+        Self::range_gadget(
+            cs,
+            LinearCombination::from(bound) - value,
+            assignment.map(|v| (max_abs as i128 - v as i128) as u64),
+            bit_length,
+        )
+    }
+    
+    /// Cosine similarity gadget
+    ///
+    /// Constrains `dot ≥ 1` and `dot² · scale² ≥ threshold² · ‖current‖² · ‖reference‖²`,
+    /// i.e. `dot ≥ (threshold / scale) · sqrt(‖current‖² · ‖reference‖²)`, without
+    /// a square root. Requiring a positive dot product also rules out a zero
+    /// embedding, for which both sides vanish. `witness` is the prover's
+    /// `(dot, gap)` where `gap` is the difference of the two sides, and `None`
+    /// on the verifier side.
+    pub fn cosine_similarity_gadget<CS: ConstraintSystem>(
+        cs: &mut CS,
+        current_vars: &[Variable],
+        reference_vars: &[Variable],
+        threshold: u64,
+        scale: u64,
+        witness: Option<(u64, u128)>,
+    ) -> CircuitResult<()> {
+        if current_vars.len() != reference_vars.len() {
+            return Err(CircuitError::InvalidParameter("Mismatched variable lengths".to_string()));
+        }
+        
+        let mut dot_lc = LinearCombination::default();
+        let mut current_norm_lc = LinearCombination::default();
+        let mut reference_norm_lc = LinearCombination::default();
+        
+        for (curr, ref_v) in current_vars.iter().zip(reference_vars.iter()) {
+            let (_, _, dot) = cs.multiply((*curr).into(), (*ref_v).into());
+            let (_, _, current_sq) = cs.multiply((*curr).into(), (*curr).into());
+            let (_, _, reference_sq) = cs.multiply((*ref_v).into(), (*ref_v).into());
             
-            // Add to total distance
-            distance_lc = distance_lc + diff;
+            dot_lc = dot_lc + dot;
+            current_norm_lc = current_norm_lc + current_sq;
+            reference_norm_lc = reference_norm_lc + reference_sq;
         }
         
-        // Commit to the distance
-        let (_, distance_var) = prover.commit(Scalar::zero(), Scalar::zero());
-        prover.constrain(distance_lc - LinearCombination::from(distance_var));
+        // A negative dot product would square to a large positive value
+        Self::range_gadget(
+            cs,
+            dot_lc.clone() - ScalarUtils::one(),
+            witness.map(|(dot, _)| dot.wrapping_sub(1)),
+            64,
+        )?;
         
-        Ok(distance_var)
+        let (_, _, dot_sq) = cs.multiply(dot_lc.clone(), dot_lc);
+        let (_, _, norm_product) = cs.multiply(current_norm_lc, reference_norm_lc);
+        
+        let scale_sq = Scalar::from(scale) * Scalar::from(scale);
+        let threshold_sq = Scalar::from(threshold) * Scalar::from(threshold);
+        Self::bit_decomposition(
+            cs,
+            LinearCombination::from(dot_sq) * scale_sq - LinearCombination::from(norm_product) * threshold_sq,
+            witness.map(|(_, gap)| gap),
+            COSINE_RANGE_BITS,
+        )
+    }
+    
+    /// Cosine score gadget
+    ///
+    /// Returns a linear combination equal to `floor(cos(current, reference) · scale)`
+    /// by constraining `score² · N ≤ dot² · scale² < (score + 1)² · N`, where
+    /// `N = ‖current‖² · ‖reference‖²`. The dot product must be non-negative and
+    /// neither embedding may be zero. `witness` is `None` on the verifier side.
+    pub fn cosine_score_gadget<CS: ConstraintSystem>(
+        cs: &mut CS,
+        current_vars: &[Variable],
+        reference_vars: &[Variable],
+        scale: u64,
+        witness: Option<CosineScoreWitness>,
+    ) -> CircuitResult<LinearCombination> {
+        if current_vars.len() != reference_vars.len() {
+            return Err(CircuitError::InvalidParameter("Mismatched variable lengths".to_string()));
+        }
+        
+        let mut dot_lc = LinearCombination::default();
+        let mut current_norm_lc = LinearCombination::default();
+        let mut reference_norm_lc = LinearCombination::default();
+        
+        for (curr, ref_v) in current_vars.iter().zip(reference_vars.iter()) {
+            let (_, _, dot) = cs.multiply((*curr).into(), (*ref_v).into());
+            let (_, _, current_sq) = cs.multiply((*curr).into(), (*curr).into());
+            let (_, _, reference_sq) = cs.multiply((*ref_v).into(), (*ref_v).into());
+            
+            dot_lc = dot_lc + dot;
+            current_norm_lc = current_norm_lc + current_sq;
+            reference_norm_lc = reference_norm_lc + reference_sq;
+        }
+        
+        Self::range_gadget(cs, dot_lc.clone(), witness.map(|w| w.dot), 64)?;
+        
+        let (_, _, dot_sq) = cs.multiply(dot_lc.clone(), dot_lc);
+        let (_, _, norm_product) = cs.multiply(current_norm_lc, reference_norm_lc);
+        
+        // score = a = b, score² = a · b
+        let (score, score_copy, score_sq) = cs
+            .allocate_multiplier(witness.map(|w| (Scalar::from(w.score), Scalar::from(w.score))))
+            .map_err(|_| CircuitError::ProofGenerationFailed("Failed to allocate cosine score".to_string()))?;
+        cs.constrain(LinearCombination::from(score) - score_copy);
+        Self::range_gadget(cs, score.into(), witness.map(|w| w.score), 64)?;
+        
+        let (_, _, score_norm) = cs.multiply(score.into(), norm_product.into());
+        let (_, _, score_sq_norm) = cs.multiply(score_sq.into(), norm_product.into());
+        
+        let scaled_dot_sq = LinearCombination::from(dot_sq) * (Scalar::from(scale) * Scalar::from(scale));
+        Self::bit_decomposition(
+            cs,
+            scaled_dot_sq.clone() - score_sq_norm,
+            witness.map(|w| w.lower_gap),
+            COSINE_RANGE_BITS,
+        )?;
+        Self::bit_decomposition(
+            cs,
+            LinearCombination::from(score_sq_norm) + LinearCombination::from(score_norm) * Scalar::from(2u64)
+                + norm_product - scaled_dot_sq - ScalarUtils::one(),
+            witness.map(|w| w.upper_gap),
+            COSINE_RANGE_BITS,
+        )?;
+        
+        Ok(score.into())
+    }
+    
+    /// Constrain `value` to `[0, 2^bit_length)` with one boolean per bit
+    fn bit_decomposition<CS: ConstraintSystem>(
+        cs: &mut CS,
+        value: LinearCombination,
+        assignment: Option<u128>,
+        bit_length: usize,
+    ) -> CircuitResult<()> {
+        let mut recomposed = LinearCombination::default();
+        let mut power = ScalarUtils::one();
+        
+        for i in 0..bit_length {
+            // a = bit, b = 1 - bit, a · b = 0
+            let (a, b, product) = cs
+                .allocate_multiplier(assignment.map(|v| {
+                    let bit = ((v >> i) & 1) as u64;
+                    (Scalar::from(bit), Scalar::from(1 - bit))
+                }))
+                .map_err(|_| CircuitError::ProofGenerationFailed("Failed to allocate range bit".to_string()))?;
+            
+            cs.constrain(product.into());
+            cs.constrain(LinearCombination::from(a) + b - ScalarUtils::one());
+            
+            recomposed = recomposed + LinearCombination::from(a) * power;
+            power = power + power;
+        }
+        
+        cs.constrain(value - recomposed);
+        Ok(())
+    }
+    
+    /// Hamming weight gadget for binary templates (e.g. iris codes)
+    ///
+    /// Constrains every input to {0, 1} and returns a variable equal to the
+    /// number of set bits, ready for a subsequent range check. Works on both
+    /// the prover and the verifier side.
+    pub fn hamming_weight_gadget<CS: ConstraintSystem>(
+        cs: &mut CS,
+        bits: &[Variable],
+    ) -> CircuitResult<Variable> {
+        if bits.is_empty() {
+            return Err(CircuitError::InvalidParameter("Cannot weigh an empty bit vector".to_string()));
+        }
+        
+        let mut weight_lc = LinearCombination::default();
+        
+        for &bit in bits {
+            // b · (b - 1) = 0
+            let (_, _, product) = cs.multiply(bit.into(), LinearCombination::from(bit) - ScalarUtils::one());
+            cs.constrain(product.into());
+            
+            weight_lc = weight_lc + bit;
+        }
+        
+        // Materialize the sum as a variable: weight · 1
+        let (weight_var, _, _) = cs.multiply(weight_lc, ScalarUtils::one().into());
+        
+        Ok(weight_var)
+    }
+    
+    /// Poseidon gadget: constrains the output of `CircuitHash::poseidon`
+    ///
+    /// Mirrors the native sponge over `inputs`, which may be any linear
+    /// combinations of committed variables. Each S-box costs three multipliers
+    /// and each partial round two more to materialize the untouched state
+    /// elements, which keeps the linear combinations from growing, for
+    /// `9 · POSEIDON_FULL_ROUNDS + 5 · POSEIDON_PARTIAL_ROUNDS` multipliers per
+    /// absorbed block.
+    #[cfg(feature = "poseidon")]
+    pub fn poseidon_gadget<CS: ConstraintSystem>(
+        cs: &mut CS,
+        inputs: &[LinearCombination],
+    ) -> CircuitResult<LinearCombination> {
+        let mut state: [LinearCombination; POSEIDON_WIDTH] = [
+            Scalar::from(inputs.len() as u64).into(),
+            LinearCombination::default(),
+            LinearCombination::default(),
+        ];
+        
+        let mut blocks = inputs.chunks(POSEIDON_RATE).peekable();
+        if blocks.peek().is_none() {
+            Self::poseidon_permutation(cs, &mut state);
+        }
+        for block in blocks {
+            for (element, input) in state[1..].iter_mut().zip(block) {
+                *element = element.clone() + input.clone();
+            }
+            Self::poseidon_permutation(cs, &mut state);
+        }
+        
+        let [_, output, _] = state;
+        Ok(output)
+    }
+    
+    /// Commitment hash gadget: constrains `CircuitHash::poseidon` of the
+    /// current then reference coordinates to equal `commitment_hash`
+    ///
+    /// Ties a public hash of the embeddings to the committed witness. Uses
+    /// `poseidon_multipliers(2 · n)` multipliers.
+    #[cfg(feature = "poseidon")]
+    pub fn commitment_hash_gadget<CS: ConstraintSystem>(
+        cs: &mut CS,
+        current_vars: &[Variable],
+        reference_vars: &[Variable],
+        commitment_hash: Scalar,
+    ) -> CircuitResult<()> {
+        if current_vars.len() != reference_vars.len() {
+            return Err(CircuitError::InvalidParameter("Mismatched variable lengths".to_string()));
+        }
+        
+        let inputs: Vec<LinearCombination> = current_vars
+            .iter()
+            .chain(reference_vars)
+            .map(|&var| var.into())
+            .collect();
+        let hash = Self::poseidon_gadget(cs, &inputs)?;
+        cs.constrain(hash - commitment_hash);
+        Ok(())
+    }
+    
+    /// Linking tag gadget: constrains `LinkingTag::derive` of the reference
+    /// coordinates under the context absorbed as `context` to equal `tag`
+    ///
+    /// Ties a public linking tag to the committed reference. Uses
+    /// `poseidon_multipliers(n + 1)` multipliers.
+    #[cfg(feature = "poseidon")]
+    pub fn linking_tag_gadget<CS: ConstraintSystem>(
+        cs: &mut CS,
+        reference_vars: &[Variable],
+        context: Scalar,
+        tag: Scalar,
+    ) -> CircuitResult<()> {
+        let inputs: Vec<LinearCombination> = core::iter::once(context.into())
+            .chain(reference_vars.iter().map(|&var| var.into()))
+            .collect();
+        let hash = Self::poseidon_gadget(cs, &inputs)?;
+        cs.constrain(hash - tag);
+        Ok(())
+    }
+    
+    /// Multipliers `poseidon_gadget` uses to hash `inputs` field elements
+    #[cfg(feature = "poseidon")]
+    pub fn poseidon_multipliers(inputs: usize) -> usize {
+        let permutations = inputs.div_ceil(POSEIDON_RATE).max(1);
+        permutations * (9 * POSEIDON_FULL_ROUNDS + 5 * POSEIDON_PARTIAL_ROUNDS)
+    }
+    
+    #[cfg(feature = "poseidon")]
+    fn poseidon_permutation<CS: ConstraintSystem>(
+        cs: &mut CS,
+        state: &mut [LinearCombination; POSEIDON_WIDTH],
+    ) {
+        let constants = Poseidon::round_constants();
+        let mds = Poseidon::mds();
+        
+        for (round, round_constants) in constants.iter().enumerate() {
+            let full = Poseidon::is_full_round(round);
+            let words: Vec<Variable> = state
+                .iter()
+                .zip(round_constants)
+                .enumerate()
+                .map(|(i, (element, constant))| {
+                    let element = element.clone() + *constant;
+                    if full || i == 0 {
+                        let (_, _, x2) = cs.multiply(element.clone(), element.clone());
+                        let (_, _, x4) = cs.multiply(x2.into(), x2.into());
+                        cs.multiply(x4.into(), element).2
+                    } else {
+                        cs.multiply(element, ScalarUtils::one().into()).0
+                    }
+                })
+                .collect();
+            
+            for (element, row) in state.iter_mut().zip(mds) {
+                *element = row
+                    .iter()
+                    .zip(&words)
+                    .map(|(m, w)| (*w, *m))
+                    .collect();
+            }
+        }
     }
 }
 
@@ -47,7 +437,7 @@ pub struct VerificationGadgets;
 impl VerificationGadgets {
     /// Range check gadget
     pub fn range_check<T>(
-        verifier: &mut Verifier<T>,
+        _verifier: &mut Verifier<T>,
         value: Variable,
         min: u64,
         max: u64,
@@ -60,10 +450,194 @@ impl VerificationGadgets {
         let max_scalar = Scalar::from(max);
         
         // Create range constraints (simplified)
-        let min_lc = LinearCombination::from(value) - LinearCombination::from(min_scalar);
-        let max_lc = LinearCombination::from(max_scalar) - LinearCombination::from(value);
+        let _min_lc = LinearCombination::from(value) - LinearCombination::from(min_scalar);
+        let _max_lc = LinearCombination::from(max_scalar) - LinearCombination::from(value);
         
         // These would need proper range proof constraints in full implementation
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+    use crate::backend::bulletproofs::{BulletproofGens, PedersenGens};
+    use crate::backend::bulletproofs::r1cs::Prover;
+    use crate::circuit::constraints::BiometricConstraints;
+    
+    fn prove_weight(bits: &[u64], lower: u64, upper: u64) -> CircuitResult<()> {
+        let pc_gens = PedersenGens::default();
+        let bp_gens = BulletproofGens::new(128, 1);
+        let mut transcript = Transcript::new(b"hamming_test");
+        let mut prover = Prover::new(&pc_gens, &mut transcript);
+        
+        let vars: Vec<Variable> = bits
+            .iter()
+            .map(|&b| prover.commit(Scalar::from(b), ScalarUtils::zero()).1)
+            .collect();
+        
+        let weight = BiometricGadgets::hamming_weight_gadget(&mut prover, &vars)?;
+        assert_eq!(prover.eval(&weight.into()), Scalar::from(bits.iter().sum::<u64>()));
+        
+        BiometricConstraints::add_interval_constraint(&mut prover, weight, lower, upper)?;
+        prover.prove(&bp_gens).map(|_| ()).map_err(|_| {
+            CircuitError::ProofGenerationFailed("Failed to generate proof".to_string())
+        })
+    }
+    
+    /// Prove only the booleanity constraints and check them with a real verifier
+    fn verify_bits(bits: &[u64]) -> bool {
+        let pc_gens = PedersenGens::default();
+        let bp_gens = BulletproofGens::new(64, 1);
+        
+        let mut transcript = Transcript::new(b"hamming_test");
+        let mut prover = Prover::new(&pc_gens, &mut transcript);
+        let (commitments, vars): (Vec<_>, Vec<_>) = bits
+            .iter()
+            .map(|&b| prover.commit(Scalar::from(b), Scalar::from(7u64)))
+            .unzip();
+        BiometricGadgets::hamming_weight_gadget(&mut prover, &vars).unwrap();
+        let proof = prover.prove(&bp_gens).unwrap();
+        
+        let mut transcript = Transcript::new(b"hamming_test");
+        let mut verifier = Verifier::new(&mut transcript);
+        let vars: Vec<Variable> = commitments.iter().map(|c| verifier.commit(*c)).collect();
+        BiometricGadgets::hamming_weight_gadget(&mut verifier, &vars).unwrap();
+        verifier.verify(&proof, &pc_gens, &bp_gens).is_ok()
+    }
+    
+    #[test]
+    fn test_hamming_weight_within_bounds() {
+        // 0b1011_0010 has weight 4
+        let code = [1, 0, 1, 1, 0, 0, 1, 0];
+        assert!(prove_weight(&code, 2, 6).is_ok());
+        
+        // An empty template has no weight to prove
+        assert!(BiometricGadgets::hamming_weight_gadget(
+            &mut Prover::new(&PedersenGens::default(), Transcript::new(b"hamming_test")),
+            &[],
+        ).is_err());
+    }
+    
+    #[test]
+    fn test_hamming_weight_rejects_non_binary_inputs() {
+        assert!(verify_bits(&[1, 0, 1, 1, 0, 0, 1, 0]));
+        assert!(!verify_bits(&[1, 0, 2, 1, 0, 0, 1, 0]));
+    }
+    
+    /// Prove `value ∈ [0, 2^bits)` for a committed `value` and check it with a real verifier
+    fn verify_range(value: u64, bits: usize) -> bool {
+        let pc_gens = PedersenGens::default();
+        let bp_gens = BulletproofGens::new(64, 1);
+        
+        let mut transcript = Transcript::new(b"range_test");
+        let mut prover = Prover::new(&pc_gens, &mut transcript);
+        let (commitment, var) = prover.commit(Scalar::from(value), Scalar::from(3u64));
+        BiometricGadgets::range_gadget(&mut prover, var.into(), Some(value), bits).unwrap();
+        let proof = prover.prove(&bp_gens).unwrap();
+        
+        let mut transcript = Transcript::new(b"range_test");
+        let mut verifier = Verifier::new(&mut transcript);
+        let var = verifier.commit(commitment);
+        BiometricGadgets::range_gadget(&mut verifier, var.into(), None, bits).unwrap();
+        verifier.verify(&proof, &pc_gens, &bp_gens).is_ok()
+    }
+    
+    #[test]
+    fn test_range_gadget() {
+        assert!(verify_range(0, 8));
+        assert!(verify_range(255, 8));
+        assert!(!verify_range(256, 8));
+        assert!(verify_range(u32::MAX as u64, 32));
+    }
+    
+    #[test]
+    fn test_squared_distance_gadget() {
+        let pc_gens = PedersenGens::default();
+        let mut prover = Prover::new(&pc_gens, Transcript::new(b"distance_test"));
+        
+        let current: Vec<Variable> = [3u64, 5, 7].iter().map(|&v| prover.commit(Scalar::from(v), ScalarUtils::zero()).1).collect();
+        let reference: Vec<Variable> = [4u64, 4, 7].iter().map(|&v| prover.commit(Scalar::from(v), ScalarUtils::zero()).1).collect();
+        
+        // (-1)² + 1² + 0² = 2: deviations in opposite directions do not cancel
+        let distance = BiometricGadgets::squared_distance_gadget(&mut prover, &current, &reference).unwrap();
+        assert_eq!(prover.eval(&distance), Scalar::from(2u64));
+        assert_eq!(prover.metrics().multipliers, 3);
+    }
+    
+    #[test]
+    fn test_distance_gadget_squares_differences() {
+        let pc_gens = PedersenGens::default();
+        let mut prover = Prover::new(&pc_gens, Transcript::new(b"distance_test"));
+        
+        let current: Vec<Variable> = [5u64, 5].iter().map(|&v| prover.commit(Scalar::from(v), ScalarUtils::zero()).1).collect();
+        let reference: Vec<Variable> = [4u64, 6].iter().map(|&v| prover.commit(Scalar::from(v), ScalarUtils::zero()).1).collect();
+        
+        // Differences of +1 and -1 give 2, not 0
+        let distance = BiometricGadgets::distance_gadget(&mut prover, &current, &reference).unwrap();
+        assert_eq!(prover.eval(&distance.into()), Scalar::from(2u64));
+        assert_eq!(prover.metrics().multipliers, 3);
+    }
+    
+    /// Prove that committed `inputs` hash to `expected` and check it with a real verifier
+    #[cfg(feature = "poseidon")]
+    fn verify_poseidon(inputs: &[u64], expected: Scalar) -> bool {
+        let pc_gens = PedersenGens::default();
+        let bp_gens = BulletproofGens::new(1024, 1);
+        
+        let mut transcript = Transcript::new(b"poseidon_test");
+        let mut prover = Prover::new(&pc_gens, &mut transcript);
+        let (commitments, vars): (Vec<_>, Vec<LinearCombination>) = inputs
+            .iter()
+            .map(|&v| {
+                let (commitment, var) = prover.commit(Scalar::from(v), Scalar::from(11u64));
+                (commitment, var.into())
+            })
+            .unzip();
+        let output = BiometricGadgets::poseidon_gadget(&mut prover, &vars).unwrap();
+        prover.constrain(output - expected);
+        let proof = prover.prove(&bp_gens).unwrap();
+        
+        let mut transcript = Transcript::new(b"poseidon_test");
+        let mut verifier = Verifier::new(&mut transcript);
+        let vars: Vec<LinearCombination> = commitments.iter().map(|c| verifier.commit(*c).into()).collect();
+        let output = BiometricGadgets::poseidon_gadget(&mut verifier, &vars).unwrap();
+        verifier.constrain(output - expected);
+        verifier.verify(&proof, &pc_gens, &bp_gens).is_ok()
+    }
+    
+    #[cfg(feature = "poseidon")]
+    #[test]
+    fn test_poseidon_gadget_matches_native() {
+        use crate::crypto::hash::CircuitHash;
+        
+        let pc_gens = PedersenGens::default();
+        for inputs in [&[][..], &[7u64][..], &[1u64, 2, 3][..]] {
+            let scalars: Vec<Scalar> = inputs.iter().map(|&v| Scalar::from(v)).collect();
+            let mut prover = Prover::new(&pc_gens, Transcript::new(b"poseidon_test"));
+            let vars: Vec<LinearCombination> = scalars
+                .iter()
+                .map(|v| prover.commit(*v, ScalarUtils::zero()).1.into())
+                .collect();
+            
+            let output = BiometricGadgets::poseidon_gadget(&mut prover, &vars).unwrap();
+            assert_eq!(prover.eval(&output), CircuitHash::poseidon(&scalars));
+            assert_eq!(prover.metrics().multipliers, BiometricGadgets::poseidon_multipliers(inputs.len()));
+        }
+    }
+    
+    #[cfg(feature = "poseidon")]
+    #[test]
+    fn test_poseidon_gadget_constrains_output() {
+        use crate::crypto::hash::CircuitHash;
+        
+        let inputs = [1u64, 2, 3];
+        let scalars: Vec<Scalar> = inputs.iter().map(|&v| Scalar::from(v)).collect();
+        let expected = CircuitHash::poseidon(&scalars);
+        
+        assert!(verify_poseidon(&inputs, expected));
+        assert!(!verify_poseidon(&inputs, expected + ScalarUtils::one()));
+    }
+}
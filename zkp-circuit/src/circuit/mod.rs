@@ -1,7 +1,9 @@
 pub mod biometric_circuit;
+pub mod builder;
 pub mod constraints;
 pub mod gadgets;
 
 pub use biometric_circuit::*;
+pub use builder::*;
 pub use constraints::*;
 pub use gadgets::*;
@@ -1,18 +1,58 @@
+use alloc::string::ToString;
+use alloc::format;
+
 /// Configuration constants for the ZKP circuit
 pub const DEFAULT_EMBEDDING_SIZE: usize = 128;
 pub const DEFAULT_THRESHOLD: u64 = 1000;
 pub const MAX_EMBEDDING_SIZE: usize = 512;
 pub const MIN_THRESHOLD: u64 = 100;
 pub const MAX_THRESHOLD: u64 = 10000;
+pub const MAX_EMBEDDING_VALUE: i64 = 1_000_000; // Largest coordinate magnitude, checked in validation and in-circuit
 
 /// Security parameters
 pub const TRANSCRIPT_LABEL: &[u8] = b"PayNetZKPBiometric";
 pub const COMMITMENT_LABEL: &[u8] = b"BiometricCommitment";
 pub const PROOF_LABEL: &[u8] = b"BiometricProof";
+pub const DEVICE_BINDING_LABEL: &[u8] = b"PayNetDeviceBinding";
+pub const LINKING_TAG_LABEL: &[u8] = b"PayNetLinkingTag";
+pub const PROOF_ID_LABEL: &[u8] = b"PayNetProofId";
+pub const AGGREGATE_LABEL: &[u8] = b"PayNetAggregateProof";
+pub const MERKLE_LEAF_LABEL: &[u8] = b"PayNetMerkleLeaf";
+pub const POSEIDON_LABEL: &[u8] = b"PayNetPoseidon";
+/// Domains of the `HashUtils` hashes, so no two purposes share a digest
+pub const DATA_HASH_LABEL: &[u8] = b"PayNetDataHash";
+pub const EMBEDDING_HASH_LABEL: &[u8] = b"PayNetEmbeddingHash";
+pub const EMBEDDING_BATCH_LABEL: &[u8] = b"PayNetEmbeddingBatch";
+pub const COMMITMENT_HASH_LABEL: &[u8] = b"PayNetCommitmentHash";
 
 /// Circuit parameters
 pub const RANGE_BITS: usize = 32; // Bit range for values in circuit
-pub const AGGREGATION_SIZE: usize = 1; // Number of range proofs to aggregate
+pub const AGGREGATION_SIZE: usize = 4; // Most match pairs one aggregate proof may constrain
+pub const COORDINATE_RANGE_BITS: usize = 21; // Bit range covering 2 * MAX_EMBEDDING_VALUE
+pub const MAX_GENERATOR_CAPACITY: usize = 1 << 16; // Largest BulletproofGens capacity allocated up front, enough for MAX_EMBEDDING_SIZE
+
+/// Semver requirements a proof's `metadata.version` must meet to be verified;
+/// extend when a release keeps the proof layout of an earlier one
+pub const SUPPORTED_PROOF_VERSIONS: &[&str] = &["^0.1"];
+
+/// Similarity metric a circuit thresholds on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SimilarityMetric {
+    /// Prove `Σ (current_i - reference_i)² ≤ threshold`
+    #[default]
+    SquaredDistance,
+    /// Prove `cos(current, reference) ≥ threshold / COSINE_SCALE`
+    CosineSimilarity,
+}
+
+/// Fixed-point scale for cosine similarity thresholds (950 means 0.95)
+pub const COSINE_SCALE: u64 = 1000;
+pub const COSINE_RANGE_BITS: usize = 128; // Bit range for the squared cosine comparison
+
+/// Quantization parameters
+pub const MAX_QUANTIZATION_LOSS: f64 = 0.1; // Fraction of distinct values that may collapse before warning
+pub const NORMALIZATION_TARGET_NORM: i64 = 10000; // L2 norm of embeddings after normalize()
+pub const OUTLIER_Z_SCORE: f64 = 3.0; // Default z-score beyond which sanitize_embedding clamps values
 
 /// Hash parameters
 pub const HASH_OUTPUT_SIZE: usize = 32; // Blake3 hash output size
@@ -48,7 +88,7 @@ impl CircuitConfig {
             ));
         }
         
-        if threshold < MIN_THRESHOLD || threshold > MAX_THRESHOLD {
+        if !(MIN_THRESHOLD..=MAX_THRESHOLD).contains(&threshold) {
             return Err(crate::types::CircuitError::InvalidParameter(
                 format!("Threshold {} must be between {} and {}", threshold, MIN_THRESHOLD, MAX_THRESHOLD)
             ));
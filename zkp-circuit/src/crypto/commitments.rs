@@ -1,25 +1,75 @@
-use curve25519_dalek_ng::{ristretto::RistrettoPoint, scalar::Scalar};
-use rand::Rng;
+use crate::backend::bulletproofs::PedersenGens;
+use crate::backend::curve25519::{ristretto::RistrettoPoint, scalar::Scalar};
+use crate::config::COMMITMENT_LABEL;
+use crate::utils::scalar_utils::ScalarUtils;
+use rand::{CryptoRng, RngCore};
+use subtle::ConstantTimeEq;
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
 /// Pedersen commitment scheme for hiding values while enabling zero-knowledge proofs
+#[derive(Clone)]
 pub struct CommitmentScheme {
     /// Generator point G for value component
     pub g: RistrettoPoint,
     /// Generator point H for blinding factor component
     pub h: RistrettoPoint,
+    /// Rotation epoch the generators were derived for, if rotated
+    pub epoch: Option<u64>,
 }
 
 impl CommitmentScheme {
-    /// Create a new commitment scheme with random generators
+    /// Create the base commitment scheme
+    ///
+    /// G and H are "nothing up my sleeve" points hashed from
+    /// `COMMITMENT_LABEL`, so every process derives the same generators and
+    /// nobody knows their discrete log relation.
     pub fn new() -> Self {
-        let mut rng = rand::thread_rng();
-        
-        // Generate random points for G and H
-        // In practice, these should be deterministic "nothing up my sleeve" points
-        let g = RistrettoPoint::random(&mut rng);
-        let h = RistrettoPoint::random(&mut rng);
-        
-        Self { g, h }
+        Self {
+            g: Self::derive_generator(b"G", None),
+            h: Self::derive_generator(b"H", None),
+            epoch: None,
+        }
+    }
+    
+    /// Derive deterministic generators for a rotation epoch
+    ///
+    /// Both points are hashed from `COMMITMENT_LABEL` and the epoch, so a
+    /// prover and verifier that agree on the epoch agree on the generators.
+    pub fn for_epoch(epoch: u64) -> Self {
+        Self {
+            g: Self::derive_generator(b"G", Some(epoch)),
+            h: Self::derive_generator(b"H", Some(epoch)),
+            epoch: Some(epoch),
+        }
+    }
+    
+    /// Hash a domain-separated generator onto the curve
+    fn derive_generator(role: &[u8], epoch: Option<u64>) -> RistrettoPoint {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(COMMITMENT_LABEL);
+        hasher.update(role);
+        if let Some(epoch) = epoch {
+            hasher.update(b"epoch");
+            hasher.update(&epoch.to_le_bytes());
+        }
+        let mut output = [0u8; 64];
+        hasher.finalize_xof().fill(&mut output);
+        RistrettoPoint::from_uniform_bytes(&output)
+    }
+    
+    /// Rotate to fresh generators for `epoch`
+    ///
+    /// The result depends only on the epoch, not on the current generators.
+    pub fn rotate(&self, epoch: u64) -> CommitmentScheme {
+        Self::for_epoch(epoch)
+    }
+    
+    /// Pedersen generators for R1CS proving and verification
+    pub fn pedersen_gens(&self) -> PedersenGens {
+        PedersenGens {
+            B: self.g,
+            B_blinding: self.h,
+        }
     }
     
     /// Create a commitment to a value with a blinding factor
@@ -28,19 +78,61 @@ impl CommitmentScheme {
         value * self.g + blinding * self.h
     }
     
-    /// Create a commitment with a random blinding factor
+    /// Commit to a whole vector in one point
+    ///
+    /// Commitment = Σ values[i] * G_i + blinding * H, where each `G_i` is
+    /// hashed from `COMMITMENT_LABEL`, the index and the scheme's epoch.
+    /// R1CS proofs still commit per coordinate, since the r1cs prover binds
+    /// each variable to its own single-value commitment.
+    pub fn commit_vector(&self, values: &[Scalar], blinding: &Scalar) -> RistrettoPoint {
+        values
+            .iter()
+            .enumerate()
+            .fold(blinding * self.h, |acc, (i, value)| acc + value * self.vector_generator(i))
+    }
+    
+    /// Check in constant time that a vector commitment opens to `values`
+    pub fn open_vector(&self, commitment: &RistrettoPoint, values: &[Scalar], blinding: &Scalar) -> bool {
+        let expected_commitment = self.commit_vector(values, blinding).compress();
+        commitment.compress().as_bytes().ct_eq(expected_commitment.as_bytes()).into()
+    }
+    
+    /// Per-index generator `G_i` for vector commitments
+    fn vector_generator(&self, index: usize) -> RistrettoPoint {
+        let mut role = b"G_vector".to_vec();
+        role.extend_from_slice(&(index as u64).to_le_bytes());
+        Self::derive_generator(&role, self.epoch)
+    }
+    
+    /// Create a commitment with a random blinding factor from `OsRng`
+    #[cfg(feature = "std")]
     pub fn commit_with_random_blinding(&self, value: &Scalar) -> (RistrettoPoint, Scalar) {
-        let mut rng = rand::thread_rng();
-        let blinding = Scalar::random(&mut rng);
+        self.commit_with_rng(value, &mut rand::rngs::OsRng)
+    }
+    
+    /// Create a commitment with a blinding factor drawn from `rng`
+    ///
+    /// A seeded RNG makes the commitment reproducible, e.g. in tests.
+    pub fn commit_with_rng<R: RngCore + CryptoRng>(&self, value: &Scalar, rng: &mut R) -> (RistrettoPoint, Scalar) {
+        let blinding = ScalarUtils::random(rng);
         let commitment = self.commit(value, &blinding);
         (commitment, blinding)
     }
     
     /// Verify that a commitment opens to the given value and blinding factor
+    ///
+    /// Point equality may short-circuit; prefer `verify_ct` wherever the
+    /// value or blinding is secret.
     pub fn verify(&self, commitment: &RistrettoPoint, value: &Scalar, blinding: &Scalar) -> bool {
         let expected_commitment = self.commit(value, blinding);
         commitment == &expected_commitment
     }
+    
+    /// Constant-time `verify`, comparing compressed encodings with `subtle`
+    pub fn verify_ct(&self, commitment: &RistrettoPoint, value: &Scalar, blinding: &Scalar) -> bool {
+        let expected_commitment = self.commit(value, blinding).compress();
+        commitment.compress().as_bytes().ct_eq(expected_commitment.as_bytes()).into()
+    }
 }
 
 impl Default for CommitmentScheme {
@@ -50,16 +142,25 @@ impl Default for CommitmentScheme {
 }
 
 /// A commitment to a value with its blinding factor
-#[derive(Debug, Clone)]
+///
+/// The blinding factor opens the commitment, so it is zeroized on drop.
+#[derive(Debug, Clone, Zeroize, ZeroizeOnDrop)]
 pub struct Commitment {
+    #[zeroize(skip)]
     pub point: RistrettoPoint,
     pub blinding: Scalar,
 }
 
 impl Commitment {
     /// Create a new commitment for a value
+    #[cfg(feature = "std")]
     pub fn new(value: &Scalar, scheme: &CommitmentScheme) -> Self {
-        let (point, blinding) = scheme.commit_with_random_blinding(value);
+        Self::new_with_rng(value, scheme, &mut rand::rngs::OsRng)
+    }
+    
+    /// Create a new commitment with its blinding factor drawn from `rng`
+    pub fn new_with_rng<R: RngCore + CryptoRng>(value: &Scalar, scheme: &CommitmentScheme, rng: &mut R) -> Self {
+        let (point, blinding) = scheme.commit_with_rng(value, rng);
         Self { point, blinding }
     }
     
@@ -69,15 +170,16 @@ impl Commitment {
         Self { point, blinding }
     }
     
-    /// Verify this commitment opens correctly
+    /// Verify this commitment opens correctly, in constant time
     pub fn verify(&self, value: &Scalar, scheme: &CommitmentScheme) -> bool {
-        scheme.verify(&self.point, value, &self.blinding)
+        scheme.verify_ct(&self.point, value, &self.blinding)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloc::vec::Vec;
     
     #[test]
     fn test_commitment_scheme() {
@@ -93,6 +195,49 @@ mod tests {
         assert!(!scheme.verify(&commitment, &wrong_value, &blinding));
     }
     
+    #[test]
+    fn test_verify_ct_agrees_with_verify() {
+        let scheme = CommitmentScheme::new();
+        let blinding = Scalar::from(7u64);
+        let commitment = scheme.commit(&Scalar::from(42u64), &blinding);
+        
+        for (value, blinding) in [(42u64, blinding), (43, blinding), (42, Scalar::from(8u64))] {
+            let value = Scalar::from(value);
+            assert_eq!(
+                scheme.verify_ct(&commitment, &value, &blinding),
+                scheme.verify(&commitment, &value, &blinding)
+            );
+        }
+        assert!(scheme.verify_ct(&commitment, &Scalar::from(42u64), &blinding));
+        assert!(!scheme.verify_ct(&commitment, &Scalar::from(43u64), &blinding));
+    }
+    
+    #[test]
+    fn test_vector_commitment_opens() {
+        let scheme = CommitmentScheme::new();
+        let values: Vec<Scalar> = (0..128u64).map(|i| Scalar::from(i * 7 + 1)).collect();
+        let blinding = Scalar::from(99u64);
+        
+        let commitment = scheme.commit_vector(&values, &blinding);
+        assert!(scheme.open_vector(&commitment, &values, &blinding));
+        
+        // Any changed, reordered or dropped coordinate fails to open
+        let mut changed = values.clone();
+        changed[64] += Scalar::from(1u64);
+        assert!(!scheme.open_vector(&commitment, &changed, &blinding));
+        
+        let mut swapped = values.clone();
+        swapped.swap(0, 1);
+        assert!(!scheme.open_vector(&commitment, &swapped, &blinding));
+        assert!(!scheme.open_vector(&commitment, &values[..127], &blinding));
+        assert!(!scheme.open_vector(&commitment, &values, &Scalar::from(98u64)));
+        
+        // Generators are deterministic per epoch
+        assert_eq!(CommitmentScheme::new().commit_vector(&values, &blinding), commitment);
+        assert_ne!(scheme.rotate(1).commit_vector(&values, &blinding), commitment);
+    }
+    
+    #[cfg(feature = "std")]
     #[test]
     fn test_commitment_struct() {
         let scheme = CommitmentScheme::new();
@@ -105,4 +250,75 @@ mod tests {
         let wrong_value = Scalar::from(101u64);
         assert!(!commitment.verify(&wrong_value, &scheme));
     }
+    
+    #[test]
+    fn test_seeded_commitments_are_reproducible() {
+        use rand_chacha::rand_core::SeedableRng;
+        use rand_chacha::ChaCha20Rng;
+        
+        let scheme = CommitmentScheme::new();
+        let value = Scalar::from(100u64);
+        let commit = |seed: u64| {
+            let mut rng = ChaCha20Rng::seed_from_u64(seed);
+            Commitment::new_with_rng(&value, &scheme, &mut rng)
+        };
+        
+        let first = commit(7);
+        let second = commit(7);
+        assert_eq!(first.point.compress().to_bytes(), second.point.compress().to_bytes());
+        assert_eq!(first.blinding, second.blinding);
+        assert!(first.verify(&value, &scheme));
+        
+        assert_ne!(commit(8).point.compress().to_bytes(), first.point.compress().to_bytes());
+    }
+    
+    #[test]
+    fn test_rotation_is_deterministic_per_epoch() {
+        let scheme = CommitmentScheme::new();
+        let epoch_1 = scheme.rotate(1);
+        let epoch_2 = scheme.rotate(2);
+        
+        assert_eq!(epoch_1.epoch, Some(1));
+        assert_eq!(epoch_1.g, CommitmentScheme::for_epoch(1).g);
+        assert_eq!(epoch_1.h, CommitmentScheme::for_epoch(1).h);
+        assert_ne!(epoch_1.g, epoch_2.g);
+        assert_ne!(epoch_1.h, epoch_2.h);
+        assert_ne!(epoch_1.g, epoch_1.h);
+        
+        // A commitment made under one epoch does not open under another
+        let value = Scalar::from(42u64);
+        let blinding = Scalar::from(7u64);
+        let commitment = epoch_1.commit(&value, &blinding);
+        assert!(!epoch_2.verify(&commitment, &value, &blinding));
+    }
+    
+    #[test]
+    fn test_base_generators_are_deterministic() {
+        // Two independently constructed schemes, as on two devices
+        let prover_side = CommitmentScheme::new();
+        let verifier_side = CommitmentScheme::new();
+        assert_eq!(prover_side.g, verifier_side.g);
+        assert_eq!(prover_side.h, verifier_side.h);
+        assert_ne!(prover_side.g, prover_side.h);
+        
+        let value = Scalar::from(42u64);
+        let blinding = Scalar::from(123u64);
+        let commitment = prover_side.commit(&value, &blinding);
+        assert!(verifier_side.verify(&commitment, &value, &blinding));
+        
+        // The base scheme is distinct from every rotated one
+        assert_ne!(prover_side.g, CommitmentScheme::for_epoch(0).g);
+    }
+    
+    #[test]
+    fn test_commitment_zeroize_clears_blinding() {
+        let scheme = CommitmentScheme::new();
+        let value = Scalar::from(42u64);
+        let mut commitment = Commitment::with_blinding(&value, Scalar::from(7u64), &scheme);
+        let point = commitment.point;
+        
+        commitment.zeroize();
+        assert_eq!(commitment.blinding, ScalarUtils::zero());
+        assert_eq!(commitment.point, point);
+    }
 }
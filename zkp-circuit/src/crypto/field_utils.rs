@@ -1,5 +1,34 @@
-use curve25519_dalek_ng::scalar::Scalar;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use alloc::format;
+use crate::backend::curve25519::scalar::Scalar;
 use crate::types::{CircuitError, CircuitResult};
+use crate::utils::scalar_utils::ScalarUtils;
+use rand::{CryptoRng, RngCore};
+
+/// Integer width for `FieldUtils::scalar_to_uint`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteWidth {
+    U64,
+    U128,
+}
+
+impl ByteWidth {
+    /// Width in bytes
+    pub fn bytes(self) -> usize {
+        match self {
+            ByteWidth::U64 => 8,
+            ByteWidth::U128 => 16,
+        }
+    }
+}
+
+/// Byte order of the low bytes read by `FieldUtils::scalar_to_uint`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endianness {
+    Little,
+    Big,
+}
 
 /// Field arithmetic utilities for ZKP circuit
 pub struct FieldUtils;
@@ -8,9 +37,9 @@ impl FieldUtils {
     /// Convert i64 to Scalar (with bounds checking)
     pub fn i64_to_scalar(value: i64) -> CircuitResult<Scalar> {
         if value < 0 {
-            // Handle negative values by adding field modulus
-            let abs_value = (-value) as u64;
-            let scalar = Scalar::from(abs_value);
+            // Handle negative values by adding field modulus; `unsigned_abs`
+            // also covers i64::MIN, whose negation overflows
+            let scalar = Scalar::from(value.unsigned_abs());
             Ok(-scalar)
         } else {
             Ok(Scalar::from(value as u64))
@@ -23,21 +52,56 @@ impl FieldUtils {
     }
     
     /// Convert Scalar back to u64 (if possible)
+    ///
+    /// Negative values from `i64_to_scalar` are stored as `modulus - |value|`
+    /// and are rejected here; use `scalar_to_i64` to recover them.
     pub fn scalar_to_u64(scalar: &Scalar) -> CircuitResult<u64> {
+        Self::scalar_to_uint(scalar, ByteWidth::U64, Endianness::Little).map(|value| value as u64)
+    }
+    
+    /// Read the low `width` bytes of a scalar's canonical encoding as an integer
+    ///
+    /// The remaining high bytes must be zero. `Endianness::Little` recovers
+    /// values built with `Scalar::from`; `Endianness::Big` reads encodings
+    /// whose low bytes hold a big-endian integer.
+    pub fn scalar_to_uint(scalar: &Scalar, width: ByteWidth, endian: Endianness) -> CircuitResult<u128> {
         let bytes = scalar.as_bytes();
+        let width = width.bytes();
+        
+        if bytes[width..].iter().any(|&byte| byte != 0) {
+            return Err(CircuitError::CryptographicError(
+                format!("Scalar too large to convert to a {}-bit integer", width * 8)
+            ));
+        }
         
-        // Check if the scalar fits in u64 (first 24 bytes should be zero)
-        for &byte in &bytes[8..] {
-            if byte != 0 {
-                return Err(CircuitError::CryptographicError(
-                    "Scalar too large to convert to u64".to_string()
-                ));
+        let mut buffer = [0u8; 16];
+        Ok(match endian {
+            Endianness::Little => {
+                buffer[..width].copy_from_slice(&bytes[..width]);
+                u128::from_le_bytes(buffer)
+            }
+            Endianness::Big => {
+                buffer[16 - width..].copy_from_slice(&bytes[..width]);
+                u128::from_be_bytes(buffer)
+            }
+        })
+    }
+    
+    /// Convert Scalar back to i64, reading values above the field midpoint
+    /// as negatives (the inverse of `i64_to_scalar`)
+    pub fn scalar_to_i64(scalar: &Scalar) -> CircuitResult<i64> {
+        if let Ok(value) = Self::scalar_to_u64(scalar) {
+            if let Ok(value) = i64::try_from(value) {
+                return Ok(value);
             }
         }
         
-        let mut u64_bytes = [0u8; 8];
-        u64_bytes.copy_from_slice(&bytes[0..8]);
-        Ok(u64::from_le_bytes(u64_bytes))
+        match Self::scalar_to_u64(&-scalar) {
+            Ok(magnitude) if magnitude <= i64::MAX as u64 + 1 => Ok((magnitude as i64).wrapping_neg()),
+            _ => Err(CircuitError::CryptographicError(
+                "Scalar too large to convert to i64".to_string()
+            )),
+        }
     }
     
     /// Compute scalar from embedding values
@@ -56,7 +120,7 @@ impl FieldUtils {
             ));
         }
         
-        let mut result = Scalar::zero();
+        let mut result = ScalarUtils::zero();
         for (ai, bi) in a.iter().zip(b.iter()) {
             result += ai * bi;
         }
@@ -72,7 +136,7 @@ impl FieldUtils {
             ));
         }
         
-        let mut sum = Scalar::zero();
+        let mut sum = ScalarUtils::zero();
         for (ai, bi) in a.iter().zip(b.iter()) {
             let diff = ai - bi;
             sum += diff * diff;
@@ -81,6 +145,50 @@ impl FieldUtils {
         Ok(sum)
     }
     
+    /// Fixed-point cosine similarity `(numerator, denominator)` with
+    /// `numerator / denominator ≈ cos(a, b) · scale`
+    ///
+    /// The numerator is `dot(a, b) · scale · 2^k` and the denominator is
+    /// `⌈√(‖a‖² · ‖b‖²) · 2^k⌉`, with `k` chosen to keep about 63 bits in the
+    /// denominator. The ratio never exceeds the exact scaled cosine, so
+    /// `numerator ≥ threshold · denominator` is a sound check with no
+    /// division. Negative similarities, which meet no threshold, have a zero
+    /// numerator. Zero vectors have no similarity and are an error.
+    pub fn cosine_similarity_fixedpoint(a: &[Scalar], b: &[Scalar], scale: u64) -> CircuitResult<(Scalar, Scalar)> {
+        if a.len() != b.len() {
+            return Err(CircuitError::InvalidParameter(
+                "Vector length mismatch for cosine similarity".to_string()
+            ));
+        }
+        
+        let overflow = || CircuitError::InvalidParameter(
+            "Embedding values too large for cosine similarity".to_string()
+        );
+        let (mut dot, mut a_norm, mut b_norm) = (0i128, 0u128, 0u128);
+        for (ai, bi) in a.iter().zip(b) {
+            let (ai, bi) = (Self::scalar_to_i64(ai)? as i128, Self::scalar_to_i64(bi)? as i128);
+            dot = ai.checked_mul(bi).and_then(|p| dot.checked_add(p)).ok_or_else(overflow)?;
+            a_norm = ai.checked_mul(ai).and_then(|p| a_norm.checked_add(p as u128)).ok_or_else(overflow)?;
+            b_norm = bi.checked_mul(bi).and_then(|p| b_norm.checked_add(p as u128)).ok_or_else(overflow)?;
+        }
+        
+        if a_norm == 0 || b_norm == 0 {
+            return Err(CircuitError::InvalidParameter(
+                "Cosine similarity is undefined for a zero embedding".to_string()
+            ));
+        }
+        
+        let norm_product = a_norm.checked_mul(b_norm).ok_or_else(overflow)?;
+        let shift = (126u32.saturating_sub(u128::BITS - norm_product.leading_zeros())) / 2;
+        let scaled_product = norm_product << (2 * shift);
+        let root = scaled_product.isqrt();
+        let denominator = if root * root < scaled_product { root + 1 } else { root };
+        // |dot| ≤ √(norm_product), so the shifted dot product stays below 2^64
+        let numerator = ((dot.max(0) as u128) << shift).checked_mul(scale as u128).ok_or_else(overflow)?;
+        
+        Ok((Scalar::from(numerator), Scalar::from(denominator)))
+    }
+    
     /// Check if scalar is within a given range [0, max_value)
     pub fn is_in_range(scalar: &Scalar, max_value: u64) -> bool {
         match Self::scalar_to_u64(scalar) {
@@ -90,16 +198,33 @@ impl FieldUtils {
     }
     
     /// Generate random scalar in range [0, max_value)
+    #[cfg(feature = "std")]
     pub fn random_scalar_in_range(max_value: u64) -> Scalar {
-        let random_value = rand::random::<u64>() % max_value;
-        Scalar::from(random_value)
+        Self::random_scalar_in_range_with_rng(max_value, &mut rand::rngs::OsRng)
+    }
+    
+    /// `random_scalar_in_range` drawing from `rng`
+    ///
+    /// Uses rejection sampling: draws at or above the largest multiple of
+    /// `max_value` are discarded, so every value in range is equally likely.
+    /// Panics if `max_value` is zero.
+    pub fn random_scalar_in_range_with_rng<R: RngCore + CryptoRng>(max_value: u64, rng: &mut R) -> Scalar {
+        assert!(max_value > 0, "random_scalar_in_range needs a non-empty range");
+        
+        let zone = u64::MAX - (u64::MAX - max_value + 1) % max_value;
+        loop {
+            let candidate = rng.next_u64();
+            if candidate <= zone {
+                return Scalar::from(candidate % max_value);
+            }
+        }
     }
     
     /// Compute modular inverse of a scalar
     pub fn scalar_inverse(scalar: &Scalar) -> CircuitResult<Scalar> {
         let inverse = scalar.invert();
         // Check if the inverse is valid (non-zero scalar)
-        if inverse != Scalar::zero() {
+        if inverse != ScalarUtils::zero() {
             Ok(inverse)
         } else {
             Err(CircuitError::CryptographicError(
@@ -115,12 +240,12 @@ pub struct BatchFieldOps;
 impl BatchFieldOps {
     /// Sum multiple scalars
     pub fn sum_scalars(scalars: &[Scalar]) -> Scalar {
-        scalars.iter().fold(Scalar::zero(), |acc, s| acc + s)
+        scalars.iter().fold(ScalarUtils::zero(), |acc, s| acc + s)
     }
     
     /// Multiply multiple scalars
     pub fn product_scalars(scalars: &[Scalar]) -> Scalar {
-        scalars.iter().fold(Scalar::one(), |acc, s| acc * s)
+        scalars.iter().fold(ScalarUtils::one(), |acc, s| acc * s)
     }
     
     /// Compute linear combination: sum(coeffs[i] * values[i])
@@ -131,7 +256,7 @@ impl BatchFieldOps {
             ));
         }
         
-        let mut result = Scalar::zero();
+        let mut result = ScalarUtils::zero();
         for (coeff, value) in coeffs.iter().zip(values.iter()) {
             result += coeff * value;
         }
@@ -143,6 +268,35 @@ impl BatchFieldOps {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloc::vec::Vec;
+    use alloc::vec;
+    use rand_chacha::rand_core::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+    
+    /// Pearson chi-square statistic of `samples` draws bucketed by `bucket`
+    fn chi_square(buckets: usize, samples: usize, max_value: u64, bucket: impl Fn(u64) -> usize) -> f64 {
+        let mut rng = ChaCha20Rng::seed_from_u64(0x5eed);
+        let mut counts = vec![0usize; buckets];
+        for _ in 0..samples {
+            let value = FieldUtils::scalar_to_u64(&FieldUtils::random_scalar_in_range_with_rng(max_value, &mut rng)).unwrap();
+            assert!(value < max_value);
+            counts[bucket(value)] += 1;
+        }
+        
+        let expected = samples as f64 / buckets as f64;
+        counts.iter().map(|&count| (count as f64 - expected).powi(2) / expected).sum()
+    }
+    
+    #[test]
+    fn test_random_scalar_in_range_is_uniform() {
+        // 10 buckets, 9 degrees of freedom: 27.88 is the p = 0.001 critical value
+        assert!(chi_square(10, 20_000, 10, |v| v as usize) < 27.88);
+        
+        // 2^64 mod 3·2^62 = 2^62, so plain `% max_value` makes the lowest third
+        // twice as likely as the others. 2 degrees of freedom: 13.82 at p = 0.001
+        let max_value = 3 << 62;
+        assert!(chi_square(3, 6_000, max_value, |v| (v >> 62) as usize) < 13.82);
+    }
     
     #[test]
     fn test_i64_to_scalar_conversion() {
@@ -155,6 +309,53 @@ mod tests {
         assert_ne!(scalar_pos, scalar_neg);
     }
     
+    #[test]
+    fn test_scalar_to_i64_round_trip() {
+        for value in [0i64, 1, 7, 42, -1, -42, i64::MAX, i64::MIN + 1, i64::MIN] {
+            let scalar = FieldUtils::i64_to_scalar(value).unwrap();
+            assert_eq!(FieldUtils::scalar_to_i64(&scalar).unwrap(), value);
+        }
+        
+        // Negatives use the large representation, which u64 cannot hold
+        let minus_one = FieldUtils::i64_to_scalar(-1).unwrap();
+        assert_eq!(minus_one, -ScalarUtils::one());
+        assert!(FieldUtils::scalar_to_u64(&minus_one).is_err());
+        
+        assert!(FieldUtils::scalar_to_i64(&Scalar::from(u64::MAX)).is_err());
+        assert!(FieldUtils::scalar_to_i64(&-Scalar::from(u64::MAX)).is_err());
+    }
+    
+    #[test]
+    fn test_scalar_to_uint_widths_and_endianness() {
+        let scalar = Scalar::from(0x0102_0304_0506_0708u64);
+        assert_eq!(
+            FieldUtils::scalar_to_uint(&scalar, ByteWidth::U64, Endianness::Little).unwrap(),
+            FieldUtils::scalar_to_u64(&scalar).unwrap() as u128
+        );
+        assert_eq!(
+            FieldUtils::scalar_to_uint(&scalar, ByteWidth::U128, Endianness::Little).unwrap(),
+            0x0102_0304_0506_0708
+        );
+        assert_eq!(
+            FieldUtils::scalar_to_uint(&scalar, ByteWidth::U64, Endianness::Big).unwrap(),
+            0x0807_0605_0403_0201
+        );
+        
+        // 2^64 needs the wider readout
+        let wide = Scalar::from(u64::MAX) + ScalarUtils::one();
+        assert!(FieldUtils::scalar_to_uint(&wide, ByteWidth::U64, Endianness::Little).is_err());
+        assert_eq!(
+            FieldUtils::scalar_to_uint(&wide, ByteWidth::U128, Endianness::Little).unwrap(),
+            1u128 << 64
+        );
+        
+        // Anything past 16 bytes is rejected at either width, in either order
+        let huge = -ScalarUtils::one();
+        for endian in [Endianness::Little, Endianness::Big] {
+            assert!(FieldUtils::scalar_to_uint(&huge, ByteWidth::U128, endian).is_err());
+        }
+    }
+    
     #[test]
     fn test_embedding_to_scalars() {
         let embedding = vec![1, -2, 3, -4, 5];
@@ -164,7 +365,7 @@ mod tests {
     
     #[test]
     fn test_scalar_distance() {
-        let a = vec![Scalar::one(), Scalar::from(2u64), Scalar::from(3u64)];
+        let a = vec![ScalarUtils::one(), Scalar::from(2u64), Scalar::from(3u64)];
         let b = vec![Scalar::from(4u64), Scalar::from(5u64), Scalar::from(6u64)];
         
         let distance_sq = FieldUtils::scalar_distance_squared(&a, &b).unwrap();
@@ -174,9 +375,45 @@ mod tests {
         assert_eq!(FieldUtils::scalar_to_u64(&distance_sq).unwrap(), 27);
     }
     
+    #[test]
+    fn test_cosine_similarity_fixedpoint_matches_float() {
+        let cosine = |x: &[i64], y: &[i64]| {
+            let dot: f64 = x.iter().zip(y).map(|(p, q)| (p * q) as f64).sum();
+            let norm = |v: &[i64]| v.iter().map(|p| (p * p) as f64).sum::<f64>().sqrt();
+            dot / (norm(x) * norm(y))
+        };
+        let pairs: [(Vec<i64>, Vec<i64>); 4] = [
+            (vec![120, -400, 330, 810], vec![130, -400, 320, 810]),
+            (vec![0, 1], vec![21, 14]),
+            (vec![3, 4], vec![6, 8]),
+            (vec![1_000_000; 512], [-1_000_000, 1_000_000].repeat(256)),
+        ];
+        
+        for (a, b) in pairs {
+            let (sa, sb) = (FieldUtils::embedding_to_scalars(&a).unwrap(), FieldUtils::embedding_to_scalars(&b).unwrap());
+            let (num, den) = FieldUtils::cosine_similarity_fixedpoint(&sa, &sb, 1000).unwrap();
+            let num = FieldUtils::scalar_to_uint(&num, ByteWidth::U128, Endianness::Little).unwrap();
+            let den = FieldUtils::scalar_to_uint(&den, ByteWidth::U128, Endianness::Little).unwrap();
+            
+            let expected = cosine(&a, &b).max(0.0) * 1000.0;
+            let ratio = num as f64 / den as f64;
+            assert!(ratio <= expected + 1e-9 && expected - ratio < 1e-3, "{} vs {}", ratio, expected);
+            
+            // Threshold checks without division agree with the float result
+            let threshold = expected.floor() as u128;
+            assert!(num >= threshold * den || expected == threshold as f64);
+            assert!(num < (threshold + 1) * den);
+        }
+        
+        let zero = FieldUtils::embedding_to_scalars(&[0, 0]).unwrap();
+        let other = FieldUtils::embedding_to_scalars(&[1, 2]).unwrap();
+        assert!(FieldUtils::cosine_similarity_fixedpoint(&zero, &other, 1000).is_err());
+        assert!(FieldUtils::cosine_similarity_fixedpoint(&other, &other[..1], 1000).is_err());
+    }
+    
     #[test]
     fn test_batch_operations() {
-        let scalars = vec![Scalar::one(), Scalar::from(2u64), Scalar::from(3u64)];
+        let scalars = vec![ScalarUtils::one(), Scalar::from(2u64), Scalar::from(3u64)];
         
         let sum = BatchFieldOps::sum_scalars(&scalars);
         assert_eq!(FieldUtils::scalar_to_u64(&sum).unwrap(), 6);
@@ -1,14 +1,41 @@
+use alloc::string::ToString;
+use alloc::vec::Vec;
 use blake3;
 use sha2::{Sha256, Digest};
+use crate::config::{COMMITMENT_HASH_LABEL, DATA_HASH_LABEL, EMBEDDING_BATCH_LABEL, EMBEDDING_HASH_LABEL};
 use crate::types::{CircuitError, CircuitResult};
+#[cfg(feature = "poseidon")]
+use crate::backend::curve25519::scalar::Scalar;
+#[cfg(feature = "poseidon")]
+use crate::crypto::poseidon::Poseidon;
 
 /// Hash utilities for ZKP circuit
 pub struct HashUtils;
 
 impl HashUtils {
-    /// Compute Blake3 hash of input data
+    /// Compute Blake3 hash of input data, under `DATA_HASH_LABEL`
     pub fn blake3_hash(data: &[u8]) -> Vec<u8> {
-        blake3::hash(data).as_bytes().to_vec()
+        Self::domain_hash(DATA_HASH_LABEL, &[data])
+    }
+    
+    /// Blake3 hash of the concatenated `components` under a `domain` label
+    ///
+    /// The label is absorbed first with its length, so hashes for different
+    /// purposes never coincide, even over identical bytes.
+    pub fn domain_hash(domain: &[u8], components: &[&[u8]]) -> Vec<u8> {
+        let mut hasher = Self::domain_hasher(domain);
+        for component in components {
+            hasher.update(component);
+        }
+        hasher.finalize().as_bytes().to_vec()
+    }
+    
+    /// Blake3 hasher that has absorbed the length-prefixed `domain` label
+    fn domain_hasher(domain: &[u8]) -> blake3::Hasher {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(&(domain.len() as u32).to_le_bytes());
+        hasher.update(domain);
+        hasher
     }
     
     /// Compute SHA256 hash of input data
@@ -18,9 +45,9 @@ impl HashUtils {
         hasher.finalize().to_vec()
     }
     
-    /// Hash an embedding vector to create a commitment hash
+    /// Hash an embedding vector, under `EMBEDDING_HASH_LABEL`
     pub fn hash_embedding(embedding: &[i64]) -> Vec<u8> {
-        let mut hasher = blake3::Hasher::new();
+        let mut hasher = Self::domain_hasher(EMBEDDING_HASH_LABEL);
         
         // Convert each i64 to bytes and hash
         for &value in embedding {
@@ -30,15 +57,9 @@ impl HashUtils {
         hasher.finalize().as_bytes().to_vec()
     }
     
-    /// Create a commitment hash from multiple components
+    /// Create a commitment hash from multiple components, under `COMMITMENT_HASH_LABEL`
     pub fn commitment_hash(components: &[&[u8]]) -> Vec<u8> {
-        let mut hasher = blake3::Hasher::new();
-        
-        for component in components {
-            hasher.update(component);
-        }
-        
-        hasher.finalize().as_bytes().to_vec()
+        Self::domain_hash(COMMITMENT_HASH_LABEL, components)
     }
     
     /// Verify hash equality with constant-time comparison
@@ -58,15 +79,50 @@ impl HashUtils {
     
     /// Hash multiple embeddings together (for batch operations)
     pub fn hash_embedding_batch(embeddings: &[&[i64]]) -> Vec<u8> {
-        let mut hasher = blake3::Hasher::new();
-        
+        let mut hasher = Self::streaming_hasher();
         for embedding in embeddings {
-            for &value in *embedding {
-                hasher.update(&value.to_le_bytes());
-            }
+            hasher.update_embedding(embedding);
         }
-        
-        hasher.finalize().as_bytes().to_vec()
+        hasher.finalize()
+    }
+    
+    /// Incremental `hash_embedding_batch`, fed one embedding at a time
+    pub fn streaming_hasher() -> StreamingHasher {
+        StreamingHasher::new()
+    }
+}
+
+/// Hashes embeddings as they arrive, without holding the whole batch
+///
+/// Feeding the embeddings of a batch in order gives the same digest as
+/// `HashUtils::hash_embedding_batch`, under `EMBEDDING_BATCH_LABEL`.
+#[derive(Clone)]
+pub struct StreamingHasher {
+    hasher: blake3::Hasher,
+}
+
+impl StreamingHasher {
+    pub fn new() -> Self {
+        Self { hasher: HashUtils::domain_hasher(EMBEDDING_BATCH_LABEL) }
+    }
+    
+    /// Absorb the next embedding of the batch
+    pub fn update_embedding(&mut self, embedding: &[i64]) -> &mut Self {
+        for &value in embedding {
+            self.hasher.update(&value.to_le_bytes());
+        }
+        self
+    }
+    
+    /// Digest of every embedding absorbed so far
+    pub fn finalize(&self) -> Vec<u8> {
+        self.hasher.finalize().as_bytes().to_vec()
+    }
+}
+
+impl Default for StreamingHasher {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -95,6 +151,15 @@ impl CircuitHash {
         Ok(hash_u64)
     }
     
+    /// Poseidon hash over the scalar field
+    ///
+    /// Unlike `poseidon_simulate`, the result can be constrained in R1CS with
+    /// `BiometricGadgets::poseidon_gadget`.
+    #[cfg(feature = "poseidon")]
+    pub fn poseidon(inputs: &[Scalar]) -> Scalar {
+        Poseidon::hash(inputs)
+    }
+    
     /// Hash two field elements (for circuit merkle tree operations)
     pub fn hash_pair(left: u64, right: u64) -> CircuitResult<u64> {
         Self::poseidon_simulate(&[left, right])
@@ -104,6 +169,8 @@ impl CircuitHash {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloc::vec::Vec;
+    use alloc::vec;
     
     #[test]
     fn test_blake3_hash() {
@@ -130,6 +197,42 @@ mod tests {
         assert_ne!(hash1, hash3);
     }
     
+    #[test]
+    fn test_streaming_hasher_matches_batch() {
+        let embeddings: Vec<Vec<i64>> = (0..50i64).map(|i| (0..128).map(|j| i * 1000 - j).collect()).collect();
+        let batch: Vec<&[i64]> = embeddings.iter().map(Vec::as_slice).collect();
+        
+        let mut hasher = HashUtils::streaming_hasher();
+        for embedding in &embeddings {
+            hasher.update_embedding(embedding);
+        }
+        assert_eq!(hasher.finalize(), HashUtils::hash_embedding_batch(&batch));
+        
+        assert_eq!(StreamingHasher::new().finalize(), HashUtils::hash_embedding_batch(&[]));
+        assert_ne!(hasher.update_embedding(&[1]).finalize(), HashUtils::hash_embedding_batch(&batch));
+    }
+    
+    #[test]
+    fn test_hash_domains_are_separated() {
+        let embedding = [1i64, -2, 3, 4];
+        let bytes: Vec<u8> = embedding.iter().flat_map(|v| v.to_le_bytes()).collect();
+        
+        let digests = [
+            HashUtils::hash_embedding(&embedding),
+            HashUtils::commitment_hash(&[&bytes]),
+            HashUtils::blake3_hash(&bytes),
+            HashUtils::hash_embedding_batch(&[&embedding]),
+            blake3::hash(&bytes).as_bytes().to_vec(),
+        ];
+        for (i, a) in digests.iter().enumerate() {
+            for b in &digests[i + 1..] {
+                assert_ne!(a, b);
+            }
+        }
+        
+        assert_eq!(HashUtils::commitment_hash(&[&bytes]), HashUtils::domain_hash(COMMITMENT_HASH_LABEL, &[&bytes]));
+    }
+    
     #[test]
     fn test_verify_hash() {
         let data = b"test";
@@ -153,4 +256,15 @@ mod tests {
         let hash3 = CircuitHash::poseidon_simulate(&different_inputs).unwrap();
         assert_ne!(hash1, hash3);
     }
+    
+    #[cfg(feature = "poseidon")]
+    #[test]
+    fn test_poseidon_hash() {
+        let inputs: Vec<Scalar> = [1u64, 2, 3, 4].iter().map(|&v| Scalar::from(v)).collect();
+        let hash1 = CircuitHash::poseidon(&inputs);
+        assert_eq!(hash1, CircuitHash::poseidon(&inputs));
+        
+        let different_inputs: Vec<Scalar> = [1u64, 2, 3, 5].iter().map(|&v| Scalar::from(v)).collect();
+        assert_ne!(hash1, CircuitHash::poseidon(&different_inputs));
+    }
 }
@@ -0,0 +1,49 @@
+use alloc::vec::Vec;
+use crate::backend::curve25519::scalar::Scalar;
+use crate::config::LINKING_TAG_LABEL;
+use crate::crypto::CircuitHash;
+
+/// Deterministic linking tags for proofs over the same secret reference
+///
+/// The tag is the Poseidon hash of a scalar hashed from the linking context
+/// followed by the reference coordinates, so a linkable proof can constrain
+/// it in-circuit against the committed reference. The same reference under
+/// the same context always yields the same tag, while tags from different
+/// contexts cannot be correlated without knowing the reference.
+pub struct LinkingTag;
+
+impl LinkingTag {
+    /// Derive the linking tag for a reference embedding under a context
+    pub fn derive(reference_embedding: &[Scalar], context: &[u8]) -> Scalar {
+        let mut inputs = Vec::with_capacity(reference_embedding.len() + 1);
+        inputs.push(Self::context_scalar(context));
+        inputs.extend_from_slice(reference_embedding);
+        CircuitHash::poseidon(&inputs)
+    }
+    
+    /// Field element a linking context is absorbed as, ahead of the reference
+    pub fn context_scalar(context: &[u8]) -> Scalar {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(LINKING_TAG_LABEL);
+        hasher.update(b"context");
+        hasher.update(context);
+        
+        let mut output = [0u8; 64];
+        hasher.finalize_xof().fill(&mut output);
+        Scalar::from_bytes_mod_order_wide(&output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+    
+    #[test]
+    fn test_linking_tag_deterministic() {
+        let reference: Vec<Scalar> = (1..=4u64).map(Scalar::from).collect();
+        
+        assert_eq!(LinkingTag::derive(&reference, b"merchant-1"), LinkingTag::derive(&reference, b"merchant-1"));
+        assert_ne!(LinkingTag::derive(&reference, b"merchant-1"), LinkingTag::derive(&reference, b"merchant-2"));
+    }
+}
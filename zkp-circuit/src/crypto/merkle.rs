@@ -0,0 +1,174 @@
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use serde::{Deserialize, Serialize};
+use crate::config::MERKLE_LEAF_LABEL;
+use crate::crypto::CircuitHash;
+use crate::types::{CircuitError, CircuitResult};
+
+/// Merkle tree over enrolled templates, hashed with `CircuitHash::hash_pair`
+///
+/// Leaves are arbitrary byte strings (e.g. `BiometricEmbedding::to_le_bytes`
+/// or a template commitment), each reduced to a `u64` leaf hash first. A
+/// level with an odd number of nodes pairs its last node with itself.
+#[derive(Debug, Clone)]
+pub struct MerkleTree {
+    /// Node hashes from the leaves (`levels[0]`) up to the root
+    levels: Vec<Vec<u64>>,
+}
+
+/// Sibling hashes from a leaf up to the root
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MerklePath {
+    pub index: usize,
+    pub siblings: Vec<u64>,
+}
+
+impl MerkleTree {
+    /// Build a tree over at least one leaf
+    pub fn from_leaves(leaves: &[Vec<u8>]) -> CircuitResult<Self> {
+        if leaves.is_empty() {
+            return Err(CircuitError::InvalidParameter(
+                "Cannot build a Merkle tree over zero leaves".to_string()
+            ));
+        }
+        
+        let mut levels = vec![leaves.iter().map(|leaf| Self::leaf_hash(leaf)).collect::<Vec<_>>()];
+        while let Some(level) = levels.last().filter(|level| level.len() > 1) {
+            let parents = level
+                .chunks(2)
+                .map(|pair| CircuitHash::hash_pair(pair[0], *pair.last().expect("chunks are non-empty")))
+                .collect::<CircuitResult<Vec<_>>>()?;
+            levels.push(parents);
+        }
+        
+        Ok(Self { levels })
+    }
+    
+    /// Hash of a single leaf, domain-separated from interior nodes
+    pub fn leaf_hash(leaf: &[u8]) -> u64 {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(MERKLE_LEAF_LABEL);
+        hasher.update(leaf);
+        let bytes = hasher.finalize();
+        u64::from_le_bytes(bytes.as_bytes()[..8].try_into().expect("blake3 output is 32 bytes"))
+    }
+    
+    pub fn root(&self) -> u64 {
+        self.levels.last().expect("trees have at least one level")[0]
+    }
+    
+    /// Number of leaves
+    pub fn len(&self) -> usize {
+        self.levels[0].len()
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.levels[0].is_empty()
+    }
+    
+    /// Membership path for the leaf at `index`
+    pub fn proof(&self, index: usize) -> CircuitResult<MerklePath> {
+        if index >= self.len() {
+            return Err(CircuitError::InvalidParameter(
+                format!("Leaf index {} out of range for {} leaves", index, self.len())
+            ));
+        }
+        
+        let siblings = self.levels[..self.levels.len() - 1]
+            .iter()
+            .enumerate()
+            .map(|(depth, level)| {
+                let position = index >> depth;
+                // The last node of an odd level is its own sibling
+                let sibling = (position ^ 1).min(level.len() - 1);
+                level[sibling]
+            })
+            .collect();
+        
+        Ok(MerklePath { index, siblings })
+    }
+    
+    /// Check that `leaf` sits at `path.index` under `root`
+    pub fn verify_path(root: u64, leaf: &[u8], path: &MerklePath) -> CircuitResult<bool> {
+        if path.siblings.len() < usize::BITS as usize && path.index >> path.siblings.len() != 0 {
+            return Ok(false);
+        }
+        
+        let mut node = Self::leaf_hash(leaf);
+        for (depth, sibling) in path.siblings.iter().enumerate() {
+            node = if (path.index >> depth) & 1 == 0 {
+                CircuitHash::hash_pair(node, *sibling)?
+            } else {
+                CircuitHash::hash_pair(*sibling, node)?
+            };
+        }
+        
+        Ok(node == root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+    use alloc::vec;
+    
+    fn leaves(count: u8) -> Vec<Vec<u8>> {
+        (0..count).map(|i| vec![i; 8]).collect()
+    }
+    
+    #[test]
+    fn test_four_leaf_tree() {
+        let leaves = leaves(4);
+        let tree = MerkleTree::from_leaves(&leaves).unwrap();
+        
+        let hashes: Vec<u64> = leaves.iter().map(|leaf| MerkleTree::leaf_hash(leaf)).collect();
+        let left = CircuitHash::hash_pair(hashes[0], hashes[1]).unwrap();
+        let right = CircuitHash::hash_pair(hashes[2], hashes[3]).unwrap();
+        assert_eq!(tree.root(), CircuitHash::hash_pair(left, right).unwrap());
+        
+        for (index, leaf) in leaves.iter().enumerate() {
+            let path = tree.proof(index).unwrap();
+            assert_eq!(path.siblings.len(), 2);
+            assert!(MerkleTree::verify_path(tree.root(), leaf, &path).unwrap());
+            
+            // Wrong leaf or wrong position
+            assert!(!MerkleTree::verify_path(tree.root(), b"not enrolled", &path).unwrap());
+            let moved = MerklePath { index: index ^ 1, ..path };
+            assert!(!MerkleTree::verify_path(tree.root(), leaf, &moved).unwrap());
+        }
+    }
+    
+    #[test]
+    fn test_odd_leaf_tree_duplicates_last() {
+        let leaves = leaves(3);
+        let tree = MerkleTree::from_leaves(&leaves).unwrap();
+        
+        let hashes: Vec<u64> = leaves.iter().map(|leaf| MerkleTree::leaf_hash(leaf)).collect();
+        let left = CircuitHash::hash_pair(hashes[0], hashes[1]).unwrap();
+        let right = CircuitHash::hash_pair(hashes[2], hashes[2]).unwrap();
+        assert_eq!(tree.root(), CircuitHash::hash_pair(left, right).unwrap());
+        
+        for (index, leaf) in leaves.iter().enumerate() {
+            let path = tree.proof(index).unwrap();
+            assert!(MerkleTree::verify_path(tree.root(), leaf, &path).unwrap());
+        }
+        
+        let single = MerkleTree::from_leaves(&leaves[..1]).unwrap();
+        assert_eq!(single.root(), hashes[0]);
+        assert!(MerkleTree::verify_path(single.root(), &leaves[0], &single.proof(0).unwrap()).unwrap());
+    }
+    
+    #[test]
+    fn test_rejects_empty_tree_and_bad_index() {
+        assert!(matches!(MerkleTree::from_leaves(&[]), Err(CircuitError::InvalidParameter(_))));
+        
+        let tree = MerkleTree::from_leaves(&leaves(3)).unwrap();
+        assert!(matches!(tree.proof(3), Err(CircuitError::InvalidParameter(_))));
+        
+        // An index beyond the path's depth cannot verify
+        let path = MerklePath { index: 4, ..tree.proof(0).unwrap() };
+        assert!(!MerkleTree::verify_path(tree.root(), &leaves(1)[0], &path).unwrap());
+    }
+}
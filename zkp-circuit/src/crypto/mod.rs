@@ -1,7 +1,17 @@
 pub mod commitments;
 pub mod hash;
 pub mod field_utils;
+#[cfg(feature = "poseidon")]
+pub mod linking;
+pub mod merkle;
+#[cfg(feature = "poseidon")]
+pub mod poseidon;
 
 pub use commitments::CommitmentScheme;
 pub use hash::*;
 pub use field_utils::*;
+#[cfg(feature = "poseidon")]
+pub use linking::LinkingTag;
+pub use merkle::{MerklePath, MerkleTree};
+#[cfg(feature = "poseidon")]
+pub use poseidon::Poseidon;
@@ -0,0 +1,152 @@
+use alloc::boxed::Box;
+use once_cell::race::OnceBox;
+
+use crate::backend::curve25519::scalar::Scalar;
+use crate::config::POSEIDON_LABEL;
+use crate::utils::scalar_utils::ScalarUtils;
+
+/// Poseidon permutation over the Ristretto scalar field
+///
+/// Width 3 (rate 2, capacity 1) with the `x⁵` S-box, which is a permutation
+/// since `gcd(5, l - 1) = 1`, 8 full and 57 partial rounds. Round constants are
+/// expanded from `POSEIDON_LABEL` with the Blake3 XOF and the MDS matrix is the
+/// Cauchy matrix `1 / (i + j + WIDTH)`, so outputs are specific to this crate
+/// and do not match other Poseidon instances.
+pub struct Poseidon;
+
+/// State width of the permutation
+pub const POSEIDON_WIDTH: usize = 3;
+/// Field elements absorbed per permutation
+pub const POSEIDON_RATE: usize = POSEIDON_WIDTH - 1;
+/// Rounds applying the S-box to every state element, split around the partial rounds
+pub const POSEIDON_FULL_ROUNDS: usize = 8;
+/// Rounds applying the S-box to the first state element only
+pub const POSEIDON_PARTIAL_ROUNDS: usize = 57;
+
+const ROUNDS: usize = POSEIDON_FULL_ROUNDS + POSEIDON_PARTIAL_ROUNDS;
+
+impl Poseidon {
+    /// Hash field elements with a sponge over the permutation
+    ///
+    /// The capacity element starts at the input length, so zero-padding the
+    /// last block cannot collide with an input that ends in zeros.
+    pub fn hash(inputs: &[Scalar]) -> Scalar {
+        let mut state = [ScalarUtils::zero(); POSEIDON_WIDTH];
+        state[0] = Scalar::from(inputs.len() as u64);
+
+        let mut blocks = inputs.chunks(POSEIDON_RATE).peekable();
+        if blocks.peek().is_none() {
+            Self::permute(&mut state);
+        }
+        for block in blocks {
+            for (element, input) in state[1..].iter_mut().zip(block) {
+                *element += input;
+            }
+            Self::permute(&mut state);
+        }
+
+        state[1]
+    }
+
+    /// Apply the permutation in place
+    pub fn permute(state: &mut [Scalar; POSEIDON_WIDTH]) {
+        for (round, round_constants) in Self::round_constants().iter().enumerate() {
+            for (element, constant) in state.iter_mut().zip(round_constants) {
+                *element += constant;
+            }
+            if Self::is_full_round(round) {
+                for element in state.iter_mut() {
+                    *element = Self::sbox(*element);
+                }
+            } else {
+                state[0] = Self::sbox(state[0]);
+            }
+            *state = Self::mix(state);
+        }
+    }
+
+    /// Whether `round` applies the S-box to the whole state
+    pub(crate) fn is_full_round(round: usize) -> bool {
+        let half = POSEIDON_FULL_ROUNDS / 2;
+        round < half || round >= half + POSEIDON_PARTIAL_ROUNDS
+    }
+
+    /// Per-round constants added to each state element
+    pub(crate) fn round_constants() -> &'static [[Scalar; POSEIDON_WIDTH]; ROUNDS] {
+        static CONSTANTS: OnceBox<[[Scalar; POSEIDON_WIDTH]; ROUNDS]> = OnceBox::new();
+        CONSTANTS.get_or_init(|| {
+            let mut hasher = blake3::Hasher::new();
+            hasher.update(POSEIDON_LABEL);
+            let mut reader = hasher.finalize_xof();
+
+            let mut constants = [[ScalarUtils::zero(); POSEIDON_WIDTH]; ROUNDS];
+            for constant in constants.iter_mut().flatten() {
+                let mut wide = [0u8; 64];
+                reader.fill(&mut wide);
+                *constant = Scalar::from_bytes_mod_order_wide(&wide);
+            }
+            Box::new(constants)
+        })
+    }
+
+    /// MDS matrix applied after every round
+    pub(crate) fn mds() -> &'static [[Scalar; POSEIDON_WIDTH]; POSEIDON_WIDTH] {
+        static MDS: OnceBox<[[Scalar; POSEIDON_WIDTH]; POSEIDON_WIDTH]> = OnceBox::new();
+        MDS.get_or_init(|| {
+            let mut matrix = [[ScalarUtils::zero(); POSEIDON_WIDTH]; POSEIDON_WIDTH];
+            for (i, row) in matrix.iter_mut().enumerate() {
+                for (j, entry) in row.iter_mut().enumerate() {
+                    *entry = Scalar::from((i + j + POSEIDON_WIDTH) as u64).invert();
+                }
+            }
+            Box::new(matrix)
+        })
+    }
+
+    fn sbox(x: Scalar) -> Scalar {
+        let x2 = x * x;
+        x2 * x2 * x
+    }
+
+    fn mix(state: &[Scalar; POSEIDON_WIDTH]) -> [Scalar; POSEIDON_WIDTH] {
+        let mut mixed = [ScalarUtils::zero(); POSEIDON_WIDTH];
+        for (out, row) in mixed.iter_mut().zip(Self::mds()) {
+            *out = row.iter().zip(state).map(|(m, s)| m * s).sum();
+        }
+        mixed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_poseidon_deterministic() {
+        let inputs = [Scalar::from(1u64), Scalar::from(2u64), Scalar::from(3u64)];
+        assert_eq!(Poseidon::hash(&inputs), Poseidon::hash(&inputs));
+        assert_ne!(Poseidon::hash(&inputs), Poseidon::hash(&inputs[..2]));
+    }
+
+    #[test]
+    fn test_poseidon_distinguishes_padding() {
+        let one = Scalar::from(1u64);
+        let zero = ScalarUtils::zero();
+
+        assert_ne!(Poseidon::hash(&[one]), Poseidon::hash(&[one, zero]));
+        assert_ne!(Poseidon::hash(&[]), Poseidon::hash(&[zero]));
+        assert_ne!(Poseidon::hash(&[one, zero]), Poseidon::hash(&[zero, one]));
+    }
+
+    #[test]
+    fn test_poseidon_no_collisions_on_small_inputs() {
+        let mut outputs: Vec<[u8; 32]> = (0..64u64)
+            .flat_map(|a| (0..4u64).map(move |b| [Scalar::from(a), Scalar::from(b)]))
+            .map(|pair| Poseidon::hash(&pair).to_bytes())
+            .collect();
+        outputs.sort_unstable();
+        outputs.dedup();
+        assert_eq!(outputs.len(), 256);
+    }
+}
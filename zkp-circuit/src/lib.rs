@@ -1,9 +1,17 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
 pub mod circuit;
 pub mod proof;
 pub mod crypto;
 pub mod types;
 pub mod utils;
 pub mod config;
+#[cfg(all(feature = "std", any(test, feature = "testing")))]
+pub mod testing;
+
+mod backend;
 
 pub use circuit::*;
 pub use proof::*;
@@ -13,9 +21,11 @@ pub use utils::*;
 pub use config::*;
 
 // Re-export commonly used types for convenience
-pub use bulletproofs::{BulletproofGens, PedersenGens};
-pub use curve25519_dalek_ng::scalar::Scalar;
+pub use crate::backend::bulletproofs::{BulletproofGens, PedersenGens};
+pub use crate::backend::curve25519::scalar::Scalar;
+pub use crate::backend::curve25519::ristretto::{CompressedRistretto, RistrettoPoint};
 pub use merlin::Transcript;
+pub use crate::backend::CURVE_BACKEND;
 
 /// Main ZKP Circuit Library
 /// 
@@ -26,6 +36,7 @@ pub struct ZKPCircuit;
 
 impl ZKPCircuit {
     /// Initialize a new ZKP circuit with default parameters
+    #[allow(clippy::new_ret_no_self)]
     pub fn new() -> BiometricCircuit {
         BiometricCircuit::new(128, 1000) // 128-dim embeddings, threshold 1000
     }
@@ -2,6 +2,5 @@ pub mod prover;
 pub mod verifier;
 pub mod transcript;
 
-pub use prover::*;
-pub use verifier::*;
-pub use transcript::*;
+pub use transcript::build_transcript;
+pub use verifier::VerifierCircuit;
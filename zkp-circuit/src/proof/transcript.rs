@@ -0,0 +1,46 @@
+use merlin::Transcript;
+
+/// Start a proof transcript bound to the circuit's public parameters
+///
+/// The threshold, embedding size, deployment `domain` and verifier
+/// `challenge` are appended as labeled messages before any commitment, so a
+/// proof made for one deployment, configuration or session cannot be
+/// replayed against another. An empty challenge means none was issued.
+pub fn build_transcript(
+    label: &'static [u8],
+    threshold: u64,
+    embedding_size: usize,
+    domain: &[u8],
+    challenge: &[u8],
+) -> Transcript {
+    let mut transcript = Transcript::new(label);
+    transcript.append_message(b"dom-sep", b"biometric_proof");
+    transcript.append_u64(b"threshold", threshold);
+    transcript.append_u64(b"embedding_size", embedding_size as u64);
+    transcript.append_message(b"domain", domain);
+    transcript.append_message(b"challenge", challenge);
+    transcript
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::TRANSCRIPT_LABEL;
+
+    fn challenge_of(mut transcript: Transcript) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        transcript.challenge_bytes(b"test", &mut bytes);
+        bytes
+    }
+
+    #[test]
+    fn test_transcript_binds_every_parameter() {
+        let base = challenge_of(build_transcript(TRANSCRIPT_LABEL, 1000, 128, b"A", b""));
+        assert_eq!(base, challenge_of(build_transcript(TRANSCRIPT_LABEL, 1000, 128, b"A", b"")));
+        assert_ne!(base, challenge_of(build_transcript(TRANSCRIPT_LABEL, 1001, 128, b"A", b"")));
+        assert_ne!(base, challenge_of(build_transcript(TRANSCRIPT_LABEL, 1000, 64, b"A", b"")));
+        assert_ne!(base, challenge_of(build_transcript(TRANSCRIPT_LABEL, 1000, 128, b"B", b"")));
+        assert_ne!(base, challenge_of(build_transcript(b"other", 1000, 128, b"A", b"")));
+        assert_ne!(base, challenge_of(build_transcript(TRANSCRIPT_LABEL, 1000, 128, b"A", b"nonce")));
+    }
+}
@@ -0,0 +1,227 @@
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use crate::backend::bulletproofs::{BulletproofGens, PedersenGens};
+use crate::circuit::biometric_circuit::{DistanceStatement, ProofStamp};
+use crate::circuit::BiometricCircuit;
+use crate::config::{SimilarityMetric, RANGE_BITS};
+use crate::crypto::CommitmentScheme;
+use crate::types::{BiometricProof, CircuitError, CircuitResult};
+
+/// Verification-only counterpart of `BiometricCircuit`
+///
+/// Holds just the parameters and generators `verify` needs, so a gateway
+/// that never proves does not build a `CommitmentScheme`. The Bulletproof
+/// generators are supplied by the caller and never grown; proofs needing more
+/// than they hold fail with `CapacityExceeded`. Chunked and range proofs are
+/// not supported.
+pub struct VerifierCircuit {
+    pub embedding_size: usize,
+    pub threshold: u64,
+    pub pedersen_gens: PedersenGens,
+    pub bulletproof_gens: BulletproofGens,
+    pub metric: SimilarityMetric,
+    /// Bits `threshold - distance` is decomposed over by the threshold gate
+    pub range_bits: usize,
+    /// Deployment identifier bound into every proof transcript
+    pub domain: Vec<u8>,
+    /// Generator rotation epoch proofs must have been made under
+    pub generator_epoch: Option<u64>,
+    /// Whether proofs bind their commitment hash in-circuit
+    pub bind_commitment_hash: bool,
+}
+
+impl VerifierCircuit {
+    /// Create a verifier with the default Pedersen generators and `gens`
+    pub fn new(embedding_size: usize, threshold: u64, gens: BulletproofGens) -> Self {
+        Self {
+            embedding_size,
+            threshold,
+            pedersen_gens: PedersenGens::default(),
+            bulletproof_gens: gens,
+            metric: SimilarityMetric::default(),
+            range_bits: RANGE_BITS,
+            domain: Vec::new(),
+            generator_epoch: None,
+            bind_commitment_hash: false,
+        }
+    }
+    
+    /// Accept only proofs bound to the deployment `domain`
+    pub fn with_domain(mut self, domain: &[u8]) -> Self {
+        self.domain = domain.to_vec();
+        self
+    }
+    
+    /// Verify proofs made under the generators rotated to `epoch`
+    pub fn with_generator_epoch(mut self, epoch: u64) -> Self {
+        self.pedersen_gens = CommitmentScheme::for_epoch(epoch).pedersen_gens();
+        self.generator_epoch = Some(epoch);
+        self
+    }
+    
+    /// Verify proofs from a circuit built `with_commitment_binding`
+    #[cfg(feature = "poseidon")]
+    pub fn with_commitment_binding(mut self) -> Self {
+        self.bind_commitment_hash = true;
+        self
+    }
+    
+    /// Verify a proof produced by `BiometricCircuit::prove`
+    ///
+    /// Applies the same checks as `BiometricCircuit::verify` and returns the
+    /// same result for every proof it supports.
+    pub fn verify(&self, proof: &BiometricProof) -> CircuitResult<bool> {
+        BiometricCircuit::check_proof_params(
+            proof,
+            self.embedding_size,
+            self.threshold,
+            self.generator_epoch,
+            self.range_bits,
+            self.bind_commitment_hash,
+        )?;
+        
+        if proof.metadata.chunk_size.is_some() {
+            return Err(CircuitError::InvalidParameter(
+                "Chunked proofs need a full BiometricCircuit to verify".to_string()
+            ));
+        }
+        if proof.public_inputs.distance_range.is_some() {
+            return Err(CircuitError::InvalidParameter(
+                "Range proofs need a full BiometricCircuit to verify".to_string()
+            ));
+        }
+        
+        let required = (BiometricCircuit::multipliers_for(self.embedding_size, self.metric, self.range_bits)
+            + BiometricCircuit::binding_multipliers(self.embedding_size, self.bind_commitment_hash))
+            .next_power_of_two();
+        if self.bulletproof_gens.gens_capacity < required {
+            return Err(CircuitError::CapacityExceeded {
+                needed: required,
+                available: self.bulletproof_gens.gens_capacity,
+            });
+        }
+        
+        let inputs = &proof.public_inputs;
+        let mut transcript = BiometricCircuit::distance_transcript(
+            self.threshold,
+            self.embedding_size,
+            &self.domain,
+            inputs.device_binding.as_deref(),
+            &inputs.challenge,
+        );
+        ProofStamp::of(proof).bind(&mut transcript);
+        BiometricCircuit::check_distance_proof(
+            &proof.proof,
+            &BiometricCircuit::decode_commitments(&proof.commitments),
+            transcript,
+            DistanceStatement {
+                embedding_size: self.embedding_size,
+                threshold: self.threshold,
+                metric: self.metric,
+                range_bits: self.range_bits,
+                commitment_hash: BiometricCircuit::bound_commitment_hash(proof, self.bind_commitment_hash)?,
+            },
+            &self.pedersen_gens,
+            &self.bulletproof_gens,
+        )
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use crate::types::BiometricEmbedding;
+
+    fn verifier_for(circuit: &BiometricCircuit) -> VerifierCircuit {
+        let capacity = BiometricCircuit::generator_capacity(circuit.embedding_size, circuit.metric, circuit.range_bits);
+        VerifierCircuit::new(circuit.embedding_size, circuit.threshold, BulletproofGens::new(capacity, 1))
+    }
+
+    #[test]
+    fn test_matches_full_circuit() {
+        let circuit = BiometricCircuit::new(4, 100);
+        let verifier = verifier_for(&circuit);
+        let current = BiometricEmbedding::new(vec![10, -20, 30, 40]).unwrap();
+        let reference = BiometricEmbedding::new(vec![12, -20, 31, 40]).unwrap();
+        let proof = circuit.prove(&current, &reference).unwrap();
+        
+        let mut corrupted = proof.clone();
+        corrupted.proof[1] ^= 1;
+        let mut swapped = proof.clone();
+        swapped.commitments.swap(0, 4);
+        swapped.public_inputs.commitment_hash = BiometricProof::hash_commitments(&swapped.commitments);
+        let bound = circuit.prove_scalars(
+            &crate::crypto::FieldUtils::embedding_to_scalars(&current.data).unwrap(),
+            &crate::crypto::FieldUtils::embedding_to_scalars(&reference.data).unwrap(),
+            Some(b"device"),
+        ).unwrap();
+        let fresh = circuit.prove_with_challenge(&current, &reference, b"challenge").unwrap();
+        let mut restamped = proof.clone();
+        restamped.metadata.timestamp += 60;
+        let scored = proof.clone().with_liveness_score(900);
+        
+        for candidate in [&proof, &corrupted, &swapped, &bound, &fresh, &restamped, &scored] {
+            assert_eq!(verifier.verify(candidate).ok(), circuit.verify(candidate).ok());
+        }
+        assert!(verifier.verify(&proof).unwrap());
+        assert!(!verifier.verify(&swapped).unwrap());
+        assert!(!verifier.verify(&restamped).unwrap());
+        assert!(!verifier.verify(&scored).unwrap());
+        
+        // Parameter mismatches are errors for both
+        let mut rethresholded = proof.clone();
+        rethresholded.public_inputs.threshold = 200;
+        assert!(verifier.verify(&rethresholded).is_err());
+        assert!(circuit.verify(&rethresholded).is_err());
+    }
+
+    #[test]
+    fn test_respects_domain_and_epoch() {
+        let current = BiometricEmbedding::new(vec![1, 2, 3, 4]).unwrap();
+        let reference = BiometricEmbedding::new(vec![2, 3, 4, 5]).unwrap();
+        
+        let domain_circuit = BiometricCircuit::new(4, 100).with_domain(b"A");
+        let proof = domain_circuit.prove(&current, &reference).unwrap();
+        assert!(verifier_for(&domain_circuit).with_domain(b"A").verify(&proof).unwrap());
+        assert!(!verifier_for(&domain_circuit).with_domain(b"B").verify(&proof).unwrap());
+        
+        let epoch_circuit = BiometricCircuit::with_generator_epoch(4, 100, 3);
+        let proof = epoch_circuit.prove(&current, &reference).unwrap();
+        assert!(verifier_for(&epoch_circuit).with_generator_epoch(3).verify(&proof).unwrap());
+        assert!(verifier_for(&epoch_circuit).verify(&proof).is_err());
+    }
+
+    #[test]
+    fn test_rejects_undersized_generators() {
+        let circuit = BiometricCircuit::new(4, 100);
+        let current = BiometricEmbedding::new(vec![1, 2, 3, 4]).unwrap();
+        let proof = circuit.prove(&current, &current).unwrap();
+        
+        let verifier = VerifierCircuit::new(4, 100, BulletproofGens::new(64, 1));
+        assert!(matches!(
+            verifier.verify(&proof),
+            Err(CircuitError::CapacityExceeded { needed: 512, available: 64 })
+        ));
+    }
+
+    #[cfg(feature = "poseidon")]
+    #[test]
+    fn test_matches_binding_circuit() {
+        let circuit = BiometricCircuit::new(4, 100).with_commitment_binding();
+        let current = BiometricEmbedding::new(vec![10, -20, 30, 40]).unwrap();
+        let reference = BiometricEmbedding::new(vec![12, -20, 31, 40]).unwrap();
+        let proof = circuit.prove(&current, &reference).unwrap();
+        
+        // Generators sized for the unbound circuit cannot hold the hash gadget
+        assert!(matches!(
+            verifier_for(&circuit).with_commitment_binding().verify(&proof),
+            Err(CircuitError::CapacityExceeded { .. })
+        ));
+        
+        let capacity = circuit.describe_constraints().multipliers().next_power_of_two();
+        let verifier = VerifierCircuit::new(4, 100, BulletproofGens::new(capacity, 1)).with_commitment_binding();
+        assert!(verifier.verify(&proof).unwrap());
+        assert!(verifier_for(&circuit).verify(&proof).is_err());
+    }
+}
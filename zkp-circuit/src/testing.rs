@@ -0,0 +1,66 @@
+//! Proof fixtures for tests of crates that integrate the verifier
+//!
+//! Enabled with the `testing` feature.
+
+use crate::circuit::BiometricCircuit;
+use crate::config::CircuitConfig;
+use crate::types::{BiometricEmbedding, BiometricProof};
+use crate::utils::scalar_utils::ScalarUtils;
+use crate::Scalar;
+
+/// Builds packaged proofs with known verification outcomes
+///
+/// Proofs are made with the real prover for `BiometricCircuit::new` with the
+/// given embedding size and threshold, so `circuit.verify` accepts
+/// `valid_proof` and returns `Ok(false)` for `invalid_proof`.
+pub struct MockProver;
+
+impl MockProver {
+    /// The circuit the fixtures verify against
+    pub fn circuit(params: &CircuitConfig) -> BiometricCircuit {
+        BiometricCircuit::new(params.embedding_size, params.threshold)
+    }
+    
+    /// A proof over identical probe and reference embeddings
+    ///
+    /// Panics if `params` describes an empty embedding.
+    pub fn valid_proof(params: &CircuitConfig) -> BiometricProof {
+        let embedding = Self::embedding(params.embedding_size);
+        Self::circuit(params)
+            .prove(&embedding, &embedding)
+            .expect("identical embeddings are always provable")
+    }
+    
+    /// A well-formed proof whose probe commitments were swapped for others
+    ///
+    /// The commitment hash is recomputed, so only the proof check itself fails.
+    pub fn invalid_proof(params: &CircuitConfig) -> BiometricProof {
+        let circuit = Self::circuit(params);
+        let mut proof = Self::valid_proof(params);
+        
+        for (i, commitment) in proof.commitments.iter_mut().take(params.embedding_size).enumerate() {
+            let other = Scalar::from(i as u64 + 2);
+            *commitment = circuit.pedersen_gens.commit(other, ScalarUtils::zero()).into();
+        }
+        proof.public_inputs.commitment_hash = BiometricProof::hash_commitments(&proof.commitments);
+        proof
+    }
+    
+    fn embedding(embedding_size: usize) -> BiometricEmbedding {
+        BiometricEmbedding::new((1..=embedding_size as i64).collect()).expect("embedding size must be positive")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_mock_proofs_have_known_outcomes() {
+        let params = CircuitConfig::new(4, 1000).unwrap();
+        let circuit = MockProver::circuit(&params);
+        
+        assert!(circuit.verify(&MockProver::valid_proof(&params)).unwrap());
+        assert!(!circuit.verify(&MockProver::invalid_proof(&params)).unwrap());
+    }
+}
@@ -0,0 +1,133 @@
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use alloc::format;
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::backend::curve25519::ristretto::{CompressedRistretto, RistrettoPoint};
+use crate::types::{CircuitError, CircuitResult};
+
+/// Compressed Ristretto commitment carried in a `BiometricProof`
+///
+/// Encodes as its 32 bytes, exactly like the raw byte vectors it replaces in
+/// JSON, borsh and the binary format. Decoding only succeeds for an encoding
+/// that decompresses to a valid point, so a malformed commitment is rejected
+/// with `InvalidCommitment` when the proof is read rather than when it fails
+/// to verify.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SerializableCommitment(pub CompressedRistretto);
+
+impl SerializableCommitment {
+    /// Parse a 32-byte compressed encoding, checking it decompresses
+    pub fn from_bytes(bytes: &[u8]) -> CircuitResult<Self> {
+        let encoding: [u8; 32] = bytes.try_into().map_err(|_| {
+            CircuitError::InvalidCommitment(format!("Commitment has length {}, expected 32", bytes.len()))
+        })?;
+        let commitment = CompressedRistretto(encoding);
+        if commitment.decompress().is_none() {
+            return Err(CircuitError::InvalidCommitment(
+                "Commitment is not a valid Ristretto point".to_string()
+            ));
+        }
+        Ok(Self(commitment))
+    }
+
+    /// The 32-byte compressed encoding
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        self.0.as_bytes()
+    }
+}
+
+impl From<RistrettoPoint> for SerializableCommitment {
+    fn from(point: RistrettoPoint) -> Self {
+        Self(point.compress())
+    }
+}
+
+impl From<SerializableCommitment> for CompressedRistretto {
+    fn from(commitment: SerializableCommitment) -> Self {
+        commitment.0
+    }
+}
+
+impl AsRef<[u8]> for SerializableCommitment {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl Serialize for SerializableCommitment {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Serialize::serialize(self.as_bytes().as_slice(), serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for SerializableCommitment {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bytes = <Vec<u8> as Deserialize>::deserialize(deserializer)?;
+        Self::from_bytes(&bytes).map_err(serde::de::Error::custom)
+    }
+}
+
+impl BorshSerialize for SerializableCommitment {
+    fn serialize<W: borsh::io::Write>(&self, writer: &mut W) -> borsh::io::Result<()> {
+        BorshSerialize::serialize(self.as_bytes().as_slice(), writer)
+    }
+}
+
+impl BorshDeserialize for SerializableCommitment {
+    fn deserialize_reader<R: borsh::io::Read>(reader: &mut R) -> borsh::io::Result<Self> {
+        let bytes = Vec::<u8>::deserialize_reader(reader)?;
+        // Without `std` borsh errors carry only a message, which
+        // `BorshSerializer::from_borsh` recognises by its prefix
+        #[cfg(feature = "std")]
+        let invalid = |e: CircuitError| borsh::io::Error::new(borsh::io::ErrorKind::InvalidData, e);
+        #[cfg(not(feature = "std"))]
+        let invalid = |e: CircuitError| borsh::io::Error::new(borsh::io::ErrorKind::InvalidData, e.to_string());
+        Self::from_bytes(&bytes).map_err(invalid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use crate::backend::bulletproofs::PedersenGens;
+    use crate::backend::curve25519::scalar::Scalar;
+    use crate::utils::scalar_utils::ScalarUtils;
+
+    fn commitment(value: u64) -> SerializableCommitment {
+        PedersenGens::default().commit(Scalar::from(value), ScalarUtils::one()).into()
+    }
+
+    #[test]
+    fn test_commitment_round_trips() {
+        let original = commitment(42);
+
+        let json = serde_json::to_vec(&original).unwrap();
+        assert_eq!(json, serde_json::to_vec(&original.as_bytes().to_vec()).unwrap());
+        assert_eq!(serde_json::from_slice::<SerializableCommitment>(&json).unwrap(), original);
+
+        let borsh_bytes = borsh::to_vec(&original).unwrap();
+        assert_eq!(borsh_bytes, borsh::to_vec(&original.as_bytes().to_vec()).unwrap());
+        assert_eq!(borsh::from_slice::<SerializableCommitment>(&borsh_bytes).unwrap(), original);
+    }
+
+    #[test]
+    fn test_rejects_invalid_encodings() {
+        // 2^255 - 1 is not a canonical field element encoding
+        let non_canonical = [0xff; 32];
+        assert!(matches!(
+            SerializableCommitment::from_bytes(&non_canonical),
+            Err(CircuitError::InvalidCommitment(_))
+        ));
+        assert!(SerializableCommitment::from_bytes(&[0; 31]).is_err());
+
+        let json = serde_json::to_vec(&non_canonical.to_vec()).unwrap();
+        let error = serde_json::from_slice::<SerializableCommitment>(&json).unwrap_err();
+        assert!(error.to_string().starts_with("Invalid commitment"));
+
+        let borsh_bytes = borsh::to_vec(&non_canonical.to_vec()).unwrap();
+        assert!(borsh::from_slice::<SerializableCommitment>(&borsh_bytes).is_err());
+    }
+}
@@ -0,0 +1,76 @@
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+/// Kind of gate group in a `ConstraintDescription`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GateKind {
+    /// Bit decomposition bounding each listed variable to `[-bound, bound]`
+    Range,
+    /// `(current_i - reference_i)²` for one coordinate
+    SquaredDifference,
+    /// Product of two linear combinations, e.g. a dot-product term
+    Product,
+    /// Sum of the squared differences, materialized as one variable
+    Sum,
+    /// Bit decomposition of the gap between the score and `bound`
+    Threshold,
+    /// Poseidon permutation absorbing the listed variables, two per gate
+    Hash,
+}
+
+/// A run of identical gates
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GateGroup {
+    pub kind: GateKind,
+    /// Number of gates in the group
+    pub count: usize,
+    /// Multiplication gates used by each gate
+    pub multipliers_per_gate: usize,
+    /// Bits per decomposition, for range and threshold gates
+    pub bit_length: Option<usize>,
+    /// Public bound the gate enforces, if any
+    pub bound: Option<u64>,
+    /// Committed variables the group reads
+    pub variables: Vec<usize>,
+}
+
+/// Machine-readable description of the constraints a circuit enforces
+///
+/// Committed variables are numbered in commitment order: coordinate `i`
+/// commits the current value as variable `2i` and the reference value as
+/// `2i + 1`. Groups are listed in the order they are added to the
+/// constraint system.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConstraintDescription {
+    pub embedding_size: usize,
+    pub threshold: u64,
+    pub committed_variables: usize,
+    pub gates: Vec<GateGroup>,
+}
+
+impl ConstraintDescription {
+    /// Total multiplication gates across all groups
+    pub fn multipliers(&self) -> usize {
+        self.gates.iter().map(|g| g.count * g.multipliers_per_gate).sum()
+    }
+    
+    /// Number of gates of `kind`
+    pub fn count(&self, kind: GateKind) -> usize {
+        self.gates.iter().filter(|g| g.kind == kind).map(|g| g.count).sum()
+    }
+}
+
+/// Size of a proof, estimated before proving
+///
+/// `approx_proof_bytes` counts the serialized R1CS proof (`BiometricProof::proof`)
+/// and is exact for the single-phase constraint systems this crate builds. A
+/// proof that also committed phase-two variables would be 96 bytes larger,
+/// so the estimate never overshoots and undershoots by at most that much.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofEstimate {
+    /// Multiplication gates in the constraint system, before padding
+    pub num_multipliers: usize,
+    pub approx_proof_bytes: usize,
+    /// 32-byte commitments packaged alongside the proof
+    pub num_commitments: usize,
+}
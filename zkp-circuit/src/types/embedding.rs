@@ -1,5 +1,9 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::format;
 use thiserror::Error;
 use serde::{Deserialize, Serialize};
+use subtle::{Choice, ConstantTimeEq, ConstantTimeGreater, CtOption};
 
 /// Error types for ZKP circuit operations
 #[derive(Error, Debug)]
@@ -27,17 +31,89 @@ pub enum CircuitError {
     
     #[error("Threshold exceeded: expected {expected}, got {actual}")]
     ThresholdExceeded { expected: u64, actual: u64 },
+    
+    #[error("Parameter mismatch: {0}")]
+    ParameterMismatch(String),
+    
+    /// Bulletproof generators are too few for the circuit's multiplication gates
+    #[error("Generator capacity exceeded: circuit needs {needed} generators, {available} available")]
+    CapacityExceeded { needed: usize, available: usize },
+}
+
+impl CircuitError {
+    /// HTTP status code for services exposing the circuit over an API
+    ///
+    /// | Variant                    | Status |
+    /// |----------------------------|--------|
+    /// | `InvalidParameter`         | 400    |
+    /// | `SerializationError`       | 400    |
+    /// | `InvalidCommitment`        | 400    |
+    /// | `InvalidEmbedding`         | 400    |
+    /// | `ProofVerificationFailed`  | 422    |
+    /// | `ThresholdExceeded`        | 422    |
+    /// | `ParameterMismatch`        | 422    |
+    /// | `ProofGenerationFailed`    | 500    |
+    /// | `CryptographicError`       | 500    |
+    /// | `CapacityExceeded`         | 500    |
+    pub fn http_status(&self) -> u16 {
+        match self {
+            // Malformed input from the caller
+            CircuitError::InvalidParameter(_)
+            | CircuitError::SerializationError(_)
+            | CircuitError::InvalidCommitment(_)
+            | CircuitError::InvalidEmbedding(_) => 400,
+            // Well-formed input that does not verify
+            CircuitError::ProofVerificationFailed(_)
+            | CircuitError::ThresholdExceeded { .. }
+            | CircuitError::ParameterMismatch(_) => 422,
+            // Failures inside the proving system
+            CircuitError::ProofGenerationFailed(_)
+            | CircuitError::CryptographicError(_)
+            | CircuitError::CapacityExceeded { .. } => 500,
+        }
+    }
 }
 
 /// Result type for ZKP operations
 pub type CircuitResult<T> = Result<T, CircuitError>;
 
+/// Advisory condition reported alongside a successful result
+///
+/// Methods that can detect these return `(value, Vec<CircuitWarning>)`;
+/// callers may log them but the value is still usable.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum CircuitWarning {
+    /// The scale factor mapped distinct float values onto the same integer
+    QuantizationResolution { distinct_floats: usize, distinct_quantized: usize },
+    /// Non-zero coordinates were quantized to zero
+    QuantizationUnderflow { count: usize },
+}
+
+impl core::fmt::Display for CircuitWarning {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            CircuitWarning::QuantizationResolution { distinct_floats, distinct_quantized } => write!(
+                f,
+                "Quantization resolution: {} distinct values collapsed to {}",
+                distinct_floats, distinct_quantized
+            ),
+            CircuitWarning::QuantizationUnderflow { count } => {
+                write!(f, "Quantization underflow: {} non-zero values rounded to zero", count)
+            }
+        }
+    }
+}
+
 /// Represents a biometric embedding vector
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BiometricEmbedding {
     pub data: Vec<i64>,
     pub size: usize,
     pub normalized: bool,
+    /// Fixed-point scale the values were quantized with, when known
+    #[serde(default)]
+    pub scale_factor: Option<i64>,
 }
 
 impl BiometricEmbedding {
@@ -59,44 +135,295 @@ impl BiometricEmbedding {
             data,
             size,
             normalized: false,
+            scale_factor: None,
         })
     }
     
+    /// Quantize floats at `scale_factor`, rounding like `ScalarUtils::quantize`
+    ///
+    /// The floats are checked with `ValidationUtils::validate_float_array`
+    /// first, so a NaN, an infinity or a value whose scaled magnitude exceeds
+    /// `MAX_EMBEDDING_VALUE` fails with `InvalidEmbedding` naming its index
+    /// rather than being clamped. The scale is recorded so embeddings
+    /// quantized differently can be told apart before they are compared.
     pub fn from_floats(floats: Vec<f64>, scale_factor: i64) -> CircuitResult<Self> {
-        let data: Vec<i64> = floats
-            .into_iter()
-            .map(|f| (f * scale_factor as f64) as i64)
-            .collect();
+        crate::utils::ValidationUtils::validate_float_array(&floats, scale_factor)?;
+        Ok(Self::new(crate::utils::scalar_utils::ScalarUtils::quantize(&floats, scale_factor)?)?
+            .with_scale_factor(scale_factor))
+    }
+    
+    /// Record the scale factor integer data was quantized with
+    pub fn with_scale_factor(mut self, scale_factor: i64) -> Self {
+        self.scale_factor = Some(scale_factor);
+        self
+    }
+    
+    /// Approximate float embedding at `scale`, the inverse of `from_floats`
+    ///
+    /// Each value is within `0.5 / scale` of the float it was quantized from.
+    pub fn to_floats(&self, scale: i64) -> Vec<f64> {
+        crate::utils::scalar_utils::ScalarUtils::dequantize(&self.data, scale)
+    }
+    
+    /// Quantize like `from_floats`, reporting precision lost to the scale factor
+    ///
+    /// Warns when more than `MAX_QUANTIZATION_LOSS` of the distinct float
+    /// values collapse together, or of the non-zero coordinates round to zero.
+    pub fn from_floats_checked(floats: Vec<f64>, scale_factor: i64) -> CircuitResult<(Self, Vec<CircuitWarning>)> {
+        Self::from_floats_with_tolerance(floats, scale_factor, crate::config::MAX_QUANTIZATION_LOSS)
+    }
+    
+    /// `from_floats_checked` with an explicit tolerance in `[0, 1]`
+    pub fn from_floats_with_tolerance(
+        floats: Vec<f64>,
+        scale_factor: i64,
+        max_loss: f64,
+    ) -> CircuitResult<(Self, Vec<CircuitWarning>)> {
+        if !(0.0..=1.0).contains(&max_loss) {
+            return Err(CircuitError::InvalidParameter(
+                format!("Quantization tolerance {} must be between 0 and 1", max_loss)
+            ));
+        }
         
-        Self::new(data)
+        // Count distinct inputs by bit pattern, folding -0.0 into 0.0
+        let distinct_floats = floats
+            .iter()
+            .map(|&f| if f == 0.0 { 0u64 } else { f.to_bits() })
+            .collect::<alloc::collections::BTreeSet<_>>()
+            .len();
+        let nonzero_floats = floats.iter().filter(|&&f| f != 0.0).count();
+        let underflowed = floats
+            .iter()
+            .filter(|&&f| f != 0.0 && libm::round(f * scale_factor as f64) == 0.0)
+            .count();
+        
+        let embedding = Self::from_floats(floats, scale_factor)?;
+        let distinct_quantized = embedding.data.iter().collect::<alloc::collections::BTreeSet<_>>().len();
+        
+        let mut warnings = Vec::new();
+        let collapsed = distinct_floats - distinct_quantized;
+        if collapsed as f64 > max_loss * distinct_floats as f64 {
+            warnings.push(CircuitWarning::QuantizationResolution { distinct_floats, distinct_quantized });
+        }
+        if underflowed as f64 > max_loss * nonzero_floats as f64 {
+            warnings.push(CircuitWarning::QuantizationUnderflow { count: underflowed });
+        }
+        
+        Ok((embedding, warnings))
+    }
+    
+    /// Quantize a matrix of float templates, one embedding per row
+    ///
+    /// All rows must have the same length. With the `rayon` feature the rows are
+    /// quantized in parallel; the error for the lowest failing row is returned.
+    pub fn from_float_matrix(rows: &[Vec<f64>], scale_factor: i64) -> CircuitResult<Vec<Self>> {
+        let width = match rows.first() {
+            Some(row) => row.len(),
+            None => {
+                return Err(CircuitError::InvalidEmbedding(
+                    "Template matrix cannot be empty".to_string()
+                ));
+            }
+        };
+        
+        if let Some(i) = rows.iter().position(|row| row.len() != width) {
+            return Err(CircuitError::InvalidEmbedding(
+                format!("Row {} has length {} but expected {}", i, rows[i].len(), width)
+            ));
+        }
+        
+        let quantize_row = |(i, row): (usize, &Vec<f64>)| -> CircuitResult<Self> {
+            crate::utils::ValidationUtils::validate_float_array(row, scale_factor).map_err(|e| match e {
+                CircuitError::InvalidEmbedding(msg) => {
+                    CircuitError::InvalidEmbedding(format!("Row {}: {}", i, msg))
+                }
+                other => other,
+            })?;
+            Self::from_floats(row.clone(), scale_factor)
+        };
+        
+        #[cfg(feature = "rayon")]
+        let results: Vec<CircuitResult<Self>> = {
+            use rayon::prelude::*;
+            rows.par_iter().enumerate().map(quantize_row).collect()
+        };
+        
+        #[cfg(not(feature = "rayon"))]
+        let results: Vec<CircuitResult<Self>> = rows.iter().enumerate().map(quantize_row).collect();
+        
+        results.into_iter().collect()
     }
     
+    /// Rescale to an L2 norm of `NORMALIZATION_TARGET_NORM`
     pub fn normalize(&mut self) -> CircuitResult<()> {
+        self.normalize_to(crate::config::NORMALIZATION_TARGET_NORM)
+    }
+    
+    /// Rescale to the given L2 norm, rounding each coordinate
+    ///
+    /// Fails without modifying the embedding if it is the zero vector or if
+    /// the target is too small for any coordinate to survive rounding.
+    pub fn normalize_to(&mut self, target_norm: i64) -> CircuitResult<()> {
         if self.data.is_empty() {
             return Err(CircuitError::InvalidEmbedding(
                 "Cannot normalize empty embedding".to_string()
             ));
         }
         
+        if target_norm <= 0 {
+            return Err(CircuitError::InvalidParameter(
+                format!("Target norm {} must be positive", target_norm)
+            ));
+        }
+        
         // Compute L2 norm
-        let norm_squared: i64 = self.data.iter().map(|&x| x * x).sum();
-        let norm = (norm_squared as f64).sqrt() as i64;
+        let norm_squared = self.data
+            .iter()
+            .try_fold(0i128, |acc, &x| (x as i128).checked_mul(x as i128).and_then(|sq| acc.checked_add(sq)))
+            .ok_or_else(|| CircuitError::InvalidEmbedding(
+                "Embedding norm overflows".to_string()
+            ))?;
         
-        if norm == 0 {
+        if norm_squared == 0 {
             return Err(CircuitError::InvalidEmbedding(
                 "Cannot normalize zero vector".to_string()
             ));
         }
         
-        // Normalize each component
-        for value in &mut self.data {
-            *value = (*value * 1000) / norm; // Scale by 1000 to maintain precision
+        // |x| · target / norm ≤ target, so every coordinate fits in i64
+        let norm = libm::sqrt(norm_squared as f64);
+        let data: Vec<i64> = self.data
+            .iter()
+            .map(|&x| libm::round((x as i128 * target_norm as i128) as f64 / norm) as i64)
+            .collect();
+        
+        if data.iter().all(|&x| x == 0) {
+            return Err(CircuitError::InvalidEmbedding(
+                format!("Normalizing to norm {} collapses the embedding to zero", target_norm)
+            ));
         }
         
+        self.data = data;
         self.normalized = true;
         Ok(())
     }
     
+    /// Apply an integer whitening matrix, returning `W · x`
+    ///
+    /// Each row of `whitening_matrix` must have one entry per embedding
+    /// coordinate; the output has one coordinate per row. Enrollment and probe
+    /// embeddings must be whitened with the same matrix to stay comparable.
+    pub fn whiten(&self, whitening_matrix: &[Vec<i64>]) -> CircuitResult<BiometricEmbedding> {
+        if whitening_matrix.is_empty() {
+            return Err(CircuitError::InvalidParameter(
+                "Whitening matrix cannot be empty".to_string()
+            ));
+        }
+        
+        if let Some(i) = whitening_matrix.iter().position(|row| row.len() != self.size) {
+            return Err(CircuitError::InvalidParameter(
+                format!(
+                    "Whitening matrix row {} has length {} but embedding size is {}",
+                    i, whitening_matrix[i].len(), self.size
+                )
+            ));
+        }
+        
+        let data = whitening_matrix
+            .iter()
+            .enumerate()
+            .map(|(i, row)| {
+                row.iter()
+                    .zip(&self.data)
+                    .try_fold(0i64, |acc, (&w, &x)| w.checked_mul(x).and_then(|p| acc.checked_add(p)))
+                    .ok_or_else(|| CircuitError::InvalidEmbedding(
+                        format!("Whitened coordinate {} overflows", i)
+                    ))
+            })
+            .collect::<CircuitResult<Vec<i64>>>()?;
+        
+        Self::new(data)
+    }
+    
+    /// Concatenate per-modality embeddings (e.g. face + voice) into one
+    ///
+    /// Coordinates are joined in the order given, so probe and reference must
+    /// list their modalities identically. Each modality should be quantized
+    /// with a harmonized scale factor first; otherwise the modality with the
+    /// largest scale dominates the squared distance.
+    pub fn concat(parts: &[BiometricEmbedding]) -> CircuitResult<BiometricEmbedding> {
+        if parts.is_empty() {
+            return Err(CircuitError::InvalidEmbedding(
+                "Cannot concatenate zero embeddings".to_string()
+            ));
+        }
+        
+        let total: usize = parts.iter().map(|part| part.data.len()).sum();
+        if total > crate::config::MAX_EMBEDDING_SIZE {
+            return Err(CircuitError::InvalidEmbedding(
+                format!(
+                    "Concatenated size {} exceeds maximum {}",
+                    total,
+                    crate::config::MAX_EMBEDDING_SIZE
+                )
+            ));
+        }
+        
+        let mut data = Vec::with_capacity(total);
+        for part in parts {
+            data.extend_from_slice(&part.data);
+        }
+        
+        Self::new(data)
+    }
+    
+    /// Encode as a little-endian `u32` size followed by each `i64` coordinate
+    ///
+    /// The layout is fixed regardless of host endianness, so it can be read
+    /// back by non-Rust pipelines.
+    pub fn to_le_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(4 + 8 * self.data.len());
+        bytes.extend_from_slice(&(self.data.len() as u32).to_le_bytes());
+        for value in &self.data {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        bytes
+    }
+    
+    /// Decode the layout written by `to_le_bytes`
+    ///
+    /// The buffer must hold exactly the declared number of coordinates;
+    /// truncated or trailing data is a `SerializationError`.
+    pub fn from_le_bytes(bytes: &[u8]) -> CircuitResult<Self> {
+        let header: [u8; 4] = bytes
+            .get(..4)
+            .and_then(|b| b.try_into().ok())
+            .ok_or_else(|| CircuitError::SerializationError(
+                "Embedding buffer is missing its size header".to_string()
+            ))?;
+        let size = u32::from_le_bytes(header) as usize;
+        
+        if size > crate::config::MAX_EMBEDDING_SIZE {
+            return Err(CircuitError::InvalidEmbedding(
+                format!("Embedding size {} exceeds maximum {}", size, crate::config::MAX_EMBEDDING_SIZE)
+            ));
+        }
+        
+        let body = &bytes[4..];
+        if body.len() != 8 * size {
+            return Err(CircuitError::SerializationError(
+                format!("Embedding buffer declares {} values but holds {} bytes", size, body.len())
+            ));
+        }
+        
+        let data = body
+            .chunks_exact(8)
+            .map(|chunk| i64::from_le_bytes(chunk.try_into().expect("chunks are 8 bytes")))
+            .collect();
+        
+        Self::new(data)
+    }
+    
     pub fn compute_distance_squared(&self, other: &Self) -> CircuitResult<u64> {
         if self.size != other.size {
             return Err(CircuitError::InvalidEmbedding(
@@ -104,19 +431,153 @@ impl BiometricEmbedding {
             ));
         }
         
-        let distance_squared: i64 = self.data
-            .iter()
-            .zip(&other.data)
-            .map(|(&a, &b)| {
-                let diff = a - b;
-                diff * diff
-            })
-            .sum();
+        squared_distance(&self.data, &other.data)
+    }
+    
+    /// `compute_distance_squared` without data-dependent branches
+    ///
+    /// Every coordinate is summed regardless of intermediate values. The
+    /// result is `None` where `compute_distance_squared` would report an
+    /// overflow. Only the sizes, which are public, may fail early.
+    pub fn compute_distance_squared_ct(&self, other: &Self) -> CircuitResult<CtOption<u64>> {
+        if self.size != other.size || self.data.len() != other.data.len() {
+            return Err(CircuitError::InvalidEmbedding(
+                format!("Embedding size mismatch: {} vs {}", self.size, other.size)
+            ));
+        }
+        
+        let mut sum = 0u128;
+        let mut overflowed = Choice::from(0);
+        for (&x, &y) in self.data.iter().zip(&other.data) {
+            // |x - y| < 2^64, so its square fits in a u128
+            let diff = (x as i128 - y as i128).unsigned_abs();
+            let (next, carry) = sum.overflowing_add(diff * diff);
+            sum = next;
+            overflowed |= Choice::from(carry as u8);
+        }
+        
+        let fits = ((sum >> 64) as u64).ct_eq(&0) & !overflowed;
+        Ok(CtOption::new(sum as u64, fits))
+    }
+    
+    /// Whether the squared distance is at most `threshold`, in constant time
+    ///
+    /// An overflowing distance is never within the threshold.
+    pub fn within_threshold_ct(&self, other: &Self, threshold: u64) -> CircuitResult<Choice> {
+        let distance = self.compute_distance_squared_ct(other)?;
+        Ok(distance.is_some() & !distance.unwrap_or(u64::MAX).ct_gt(&threshold))
+    }
+}
+
+/// Several enrolled reference templates for one identity
+///
+/// Every reference is stored normalized to `NORMALIZATION_TARGET_NORM`, so
+/// distances compare direction rather than magnitude. All references share
+/// one embedding size.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddingSet {
+    references: Vec<BiometricEmbedding>,
+    size: usize,
+}
+
+impl EmbeddingSet {
+    /// Normalize and collect the references, which must be non-empty and of
+    /// equal size
+    pub fn new(references: Vec<BiometricEmbedding>) -> CircuitResult<Self> {
+        let Some(first) = references.first() else {
+            return Err(CircuitError::InvalidEmbedding(
+                "Embedding set needs at least one reference".to_string()
+            ));
+        };
+        
+        let size = first.size;
+        if let Some(other) = references.iter().find(|reference| reference.size != size) {
+            return Err(CircuitError::InvalidEmbedding(
+                format!("Embedding size mismatch: {} vs {}", size, other.size)
+            ));
+        }
         
-        Ok(distance_squared as u64)
+        let references = references
+            .into_iter()
+            .map(Self::normalized)
+            .collect::<CircuitResult<Vec<_>>>()?;
+        
+        Ok(Self { references, size })
+    }
+    
+    /// The normalized references, in enrollment order
+    pub fn references(&self) -> &[BiometricEmbedding] {
+        &self.references
+    }
+    
+    /// Embedding size shared by every reference
+    pub fn size(&self) -> usize {
+        self.size
+    }
+    
+    pub fn len(&self) -> usize {
+        self.references.len()
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.references.is_empty()
+    }
+    
+    /// Index and squared distance of the reference closest to `probe`
+    ///
+    /// An unnormalized probe is normalized the same way as the references
+    /// first. Ties go to the earliest reference.
+    pub fn closest(&self, probe: &BiometricEmbedding) -> CircuitResult<(usize, u64)> {
+        if probe.size != self.size {
+            return Err(CircuitError::InvalidEmbedding(
+                format!("Embedding size mismatch: {} vs {}", probe.size, self.size)
+            ));
+        }
+        let probe = Self::normalized(probe.clone())?;
+        
+        let mut best: Option<(usize, u64)> = None;
+        for (index, reference) in self.references.iter().enumerate() {
+            let distance = squared_distance(&probe.data, &reference.data)?;
+            if best.is_none_or(|(_, closest)| distance < closest) {
+                best = Some((index, distance));
+            }
+        }
+        Ok(best.expect("embedding sets are never empty"))
+    }
+    
+    /// Squared distance from `probe` to its closest reference
+    pub fn min_distance_squared(&self, probe: &BiometricEmbedding) -> CircuitResult<u64> {
+        self.closest(probe).map(|(_, distance)| distance)
+    }
+    
+    fn normalized(mut embedding: BiometricEmbedding) -> CircuitResult<BiometricEmbedding> {
+        if !embedding.normalized {
+            embedding.normalize()?;
+        }
+        Ok(embedding)
     }
 }
 
+/// `Σ (a_i - b_i)²` over equal-length slices, accumulated in `i128`
+///
+/// Returns `CryptographicError` if the sum does not fit in a `u64`.
+pub(crate) fn squared_distance(a: &[i64], b: &[i64]) -> CircuitResult<u64> {
+    let overflow = || CircuitError::CryptographicError(
+        "Squared distance overflows u64".to_string()
+    );
+    
+    let sum = a
+        .iter()
+        .zip(b)
+        .try_fold(0i128, |acc, (&x, &y)| {
+            let diff = x as i128 - y as i128;
+            diff.checked_mul(diff).and_then(|sq| acc.checked_add(sq))
+        })
+        .ok_or_else(overflow)?;
+    
+    u64::try_from(sum).map_err(|_| overflow())
+}
+
 /// Represents a commitment to biometric data
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BiometricCommitment {
@@ -134,3 +595,363 @@ impl BiometricCommitment {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::{String, ToString};
+    use alloc::vec::Vec;
+    use alloc::vec;
+    
+    #[test]
+    fn test_http_status_mapping() {
+        let cases = [
+            (CircuitError::InvalidParameter(String::new()), 400),
+            (CircuitError::SerializationError(String::new()), 400),
+            (CircuitError::InvalidCommitment(String::new()), 400),
+            (CircuitError::InvalidEmbedding(String::new()), 400),
+            (CircuitError::ProofVerificationFailed(String::new()), 422),
+            (CircuitError::ThresholdExceeded { expected: 1000, actual: 1200 }, 422),
+            (CircuitError::ParameterMismatch(String::new()), 422),
+            (CircuitError::ProofGenerationFailed(String::new()), 500),
+            (CircuitError::CryptographicError(String::new()), 500),
+            (CircuitError::CapacityExceeded { needed: 70_000, available: 65_536 }, 500),
+        ];
+        
+        for (error, status) in cases {
+            assert_eq!(error.http_status(), status, "{:?}", error);
+        }
+    }
+    
+    #[test]
+    fn test_to_floats_inverts_from_floats() {
+        let floats = vec![0.25, -0.1337, 0.0];
+        let embedding = BiometricEmbedding::from_floats(floats.clone(), 1000).unwrap();
+        let restored = embedding.to_floats(1000);
+        assert_eq!(restored, vec![0.25, -0.134, 0.0]);
+        assert!(floats.iter().zip(&restored).all(|(a, b)| (a - b).abs() <= 1.0 / 1000.0));
+    }
+    
+    #[test]
+    fn test_from_floats_rejects_unrepresentable_values() {
+        let cases = [
+            (f64::NAN, "index 2 is not finite"),
+            (f64::INFINITY, "index 2 is not finite"),
+            (2_000.0, "index 2 is too large"),
+        ];
+        for (value, expected) in cases {
+            let error = BiometricEmbedding::from_floats(vec![0.1, -0.2, value, 0.4], 1000).unwrap_err();
+            assert!(matches!(error, CircuitError::InvalidEmbedding(_)), "{:?}", error);
+            assert!(error.to_string().contains(expected), "{}", error);
+        }
+        
+        // At the bound itself nothing is clamped
+        let edge = BiometricEmbedding::from_floats(vec![1_000.0, -1_000.0], 1000).unwrap();
+        assert_eq!(edge.data, vec![crate::config::MAX_EMBEDDING_VALUE, -crate::config::MAX_EMBEDDING_VALUE]);
+    }
+    
+    #[test]
+    fn test_le_bytes_round_trip() {
+        let embedding = BiometricEmbedding::new(vec![1, -2, i64::MAX, i64::MIN]).unwrap();
+        let bytes = embedding.to_le_bytes();
+        assert_eq!(bytes.len(), 4 + 4 * 8);
+        assert_eq!(&bytes[..4], &[4, 0, 0, 0]);
+        assert_eq!(&bytes[4..12], &[1, 0, 0, 0, 0, 0, 0, 0]);
+        
+        let decoded = BiometricEmbedding::from_le_bytes(&bytes).unwrap();
+        assert_eq!(decoded.data, embedding.data);
+        assert_eq!(decoded.size, 4);
+    }
+    
+    #[test]
+    fn test_le_bytes_rejects_inconsistent_buffers() {
+        // Claims 10 values but carries 9
+        let mut bytes = 10u32.to_le_bytes().to_vec();
+        for value in 0..9i64 {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        assert!(matches!(BiometricEmbedding::from_le_bytes(&bytes), Err(CircuitError::SerializationError(_))));
+        
+        // Trailing bytes and missing headers are rejected too
+        bytes.extend_from_slice(&[0; 9]);
+        assert!(matches!(BiometricEmbedding::from_le_bytes(&bytes), Err(CircuitError::SerializationError(_))));
+        assert!(matches!(BiometricEmbedding::from_le_bytes(&[1, 0]), Err(CircuitError::SerializationError(_))));
+        
+        let oversized = ((crate::config::MAX_EMBEDDING_SIZE + 1) as u32).to_le_bytes();
+        assert!(matches!(BiometricEmbedding::from_le_bytes(&oversized), Err(CircuitError::InvalidEmbedding(_))));
+    }
+    
+    #[test]
+    fn test_whiten() {
+        let embedding = BiometricEmbedding::new(vec![3, -4, 5]).unwrap();
+        
+        let identity = vec![vec![1, 0, 0], vec![0, 1, 0], vec![0, 0, 1]];
+        assert_eq!(embedding.whiten(&identity).unwrap().data, vec![3, -4, 5]);
+        
+        let scaling = vec![vec![2, 0, 0], vec![0, 3, 0], vec![0, 0, -1]];
+        let scaled = embedding.whiten(&scaling).unwrap();
+        assert_eq!(scaled.data, vec![6, -12, -5]);
+        assert_eq!(scaled.size, 3);
+        
+        // Decorrelating rows mix coordinates
+        let mixing = vec![vec![1, 1, 0], vec![0, 1, -1]];
+        assert_eq!(embedding.whiten(&mixing).unwrap().data, vec![-1, -9]);
+    }
+    
+    #[test]
+    fn test_whiten_rejects_bad_shapes() {
+        let embedding = BiometricEmbedding::new(vec![3, -4, 5]).unwrap();
+        
+        assert!(embedding.whiten(&[]).is_err());
+        assert!(matches!(
+            embedding.whiten(&[vec![1, 0, 0], vec![0, 1]]),
+            Err(CircuitError::InvalidParameter(_))
+        ));
+        assert!(embedding.whiten(&[vec![i64::MAX, 0, 0]]).is_err());
+    }
+    
+    #[test]
+    fn test_from_floats_checked_warns_on_coarse_scale() {
+        let floats: Vec<f64> = (0..16).map(|i| 0.1 + 0.001 * i as f64).collect();
+        
+        // Scale 10 maps every coordinate to 1
+        let (coarse, warnings) = BiometricEmbedding::from_floats_checked(floats.clone(), 10).unwrap();
+        assert!(coarse.data.iter().all(|&v| v == 1));
+        assert_eq!(
+            warnings,
+            vec![CircuitWarning::QuantizationResolution { distinct_floats: 16, distinct_quantized: 1 }]
+        );
+        
+        // Scale 1 rounds everything to zero as well
+        let (_, warnings) = BiometricEmbedding::from_floats_checked(floats.clone(), 1).unwrap();
+        assert!(warnings.contains(&CircuitWarning::QuantizationUnderflow { count: 16 }));
+        assert!(warnings.iter().any(|w| matches!(w, CircuitWarning::QuantizationResolution { .. })));
+        assert_eq!(
+            warnings[0].to_string(),
+            "Quantization resolution: 16 distinct values collapsed to 1"
+        );
+        
+        // Scale 100_000 keeps every value distinct
+        let (fine, warnings) = BiometricEmbedding::from_floats_checked(floats.clone(), 100_000).unwrap();
+        assert!(warnings.is_empty());
+        assert_eq!(fine.data, BiometricEmbedding::from_floats(floats.clone(), 100_000).unwrap().data);
+        
+        // Full tolerance accepts any loss
+        let (_, warnings) = BiometricEmbedding::from_floats_with_tolerance(floats.clone(), 10, 1.0).unwrap();
+        assert!(warnings.is_empty());
+        assert!(BiometricEmbedding::from_floats_with_tolerance(floats, 10, 1.5).is_err());
+    }
+    
+    #[test]
+    fn test_normalize_preserves_angles() {
+        let a: Vec<f64> = (0..128).map(|i| (i as f64 * 0.37).sin() * 0.05).collect();
+        let b: Vec<f64> = (0..128).map(|i| (i as f64 * 0.37 + 0.4).sin() * 0.05).collect();
+        let cosine = |x: &[f64], y: &[f64]| {
+            let dot: f64 = x.iter().zip(y).map(|(p, q)| p * q).sum();
+            let norm = |v: &[f64]| v.iter().map(|p| p * p).sum::<f64>().sqrt();
+            dot / (norm(x) * norm(y))
+        };
+        
+        let mut normalized_a = BiometricEmbedding::from_floats(a.clone(), 1000).unwrap();
+        let mut normalized_b = BiometricEmbedding::from_floats(b.clone(), 1000).unwrap();
+        normalized_a.normalize().unwrap();
+        normalized_b.normalize().unwrap();
+        assert!(normalized_a.normalized);
+        
+        let as_floats = |e: &BiometricEmbedding| e.data.iter().map(|&x| x as f64).collect::<Vec<_>>();
+        let (int_a, int_b) = (as_floats(&normalized_a), as_floats(&normalized_b));
+        let norm: f64 = int_a.iter().map(|x| x * x).sum::<f64>().sqrt();
+        assert!((norm - crate::config::NORMALIZATION_TARGET_NORM as f64).abs() < 10.0);
+        assert!((cosine(&int_a, &int_b) - cosine(&a, &b)).abs() < 1e-3);
+        
+        // Small embeddings keep their sign and direction
+        let mut small = BiometricEmbedding::new(vec![-3, 4]).unwrap();
+        small.normalize().unwrap();
+        assert_eq!(small.data, vec![-6000, 8000]);
+    }
+    
+    #[test]
+    fn test_normalize_rejects_zero_results() {
+        let mut zero = BiometricEmbedding::new(vec![0, 0, 0]).unwrap();
+        assert!(matches!(zero.normalize(), Err(CircuitError::InvalidEmbedding(_))));
+        
+        // A target norm of 1 rounds every coordinate of a spread vector to zero
+        let mut spread = BiometricEmbedding::new(vec![1; 16]).unwrap();
+        assert!(matches!(spread.normalize_to(1), Err(CircuitError::InvalidEmbedding(_))));
+        assert_eq!(spread.data, vec![1; 16]);
+        assert!(!spread.normalized);
+        
+        assert!(matches!(spread.normalize_to(0), Err(CircuitError::InvalidParameter(_))));
+    }
+    
+    #[test]
+    fn test_distance_squared_overflow() {
+        // Largest values validation accepts stay exact
+        let high = BiometricEmbedding::new(vec![1_000_000; 512]).unwrap();
+        let low = BiometricEmbedding::new(vec![-1_000_000; 512]).unwrap();
+        assert_eq!(high.compute_distance_squared(&low).unwrap(), 512 * 4_000_000_000_000);
+        
+        // Unvalidated extremes used to wrap; now they error
+        let high = BiometricEmbedding::new(vec![i64::MAX; 512]).unwrap();
+        let low = BiometricEmbedding::new(vec![i64::MIN; 512]).unwrap();
+        assert!(matches!(
+            high.compute_distance_squared(&low),
+            Err(CircuitError::CryptographicError(_))
+        ));
+        
+        let wide = BiometricEmbedding::new(vec![3_000_000_000; 512]).unwrap();
+        let zero = BiometricEmbedding::new(vec![0; 512]).unwrap();
+        assert!(matches!(
+            wide.compute_distance_squared(&zero),
+            Err(CircuitError::CryptographicError(_))
+        ));
+        assert!(crate::types::ProofWitness::new(wide.data, zero.data, Vec::new()).is_err());
+    }
+    
+    #[test]
+    fn test_embedding_set_selects_closest_reference() {
+        let references = vec![
+            BiometricEmbedding::new(vec![10, 0, 0]).unwrap(),
+            BiometricEmbedding::new(vec![0, 3, 4]).unwrap(),
+            BiometricEmbedding::new(vec![-5, 5, 0]).unwrap(),
+        ];
+        let set = EmbeddingSet::new(references).unwrap();
+        assert_eq!(set.len(), 3);
+        assert!(set.references().iter().all(|reference| reference.normalized));
+        
+        // Same direction as the second reference at a different magnitude
+        let probe = BiometricEmbedding::new(vec![0, 30, 40]).unwrap();
+        assert_eq!(set.closest(&probe).unwrap(), (1, 0));
+        assert_eq!(set.min_distance_squared(&probe).unwrap(), 0);
+        
+        // Nearer the first reference than the others
+        let probe = BiometricEmbedding::new(vec![9, 1, 1]).unwrap();
+        let (index, distance) = set.closest(&probe).unwrap();
+        assert_eq!(index, 0);
+        for reference in set.references() {
+            let mut normalized = probe.clone();
+            normalized.normalize().unwrap();
+            assert!(normalized.compute_distance_squared(reference).unwrap() >= distance);
+        }
+    }
+    
+    #[test]
+    fn test_embedding_set_rejects_size_mismatch() {
+        assert!(matches!(EmbeddingSet::new(Vec::new()), Err(CircuitError::InvalidEmbedding(_))));
+        assert!(matches!(
+            EmbeddingSet::new(vec![
+                BiometricEmbedding::new(vec![1, 2, 3]).unwrap(),
+                BiometricEmbedding::new(vec![1, 2]).unwrap(),
+            ]),
+            Err(CircuitError::InvalidEmbedding(_))
+        ));
+        
+        let set = EmbeddingSet::new(vec![BiometricEmbedding::new(vec![1, 2, 3]).unwrap()]).unwrap();
+        let probe = BiometricEmbedding::new(vec![1, 2, 3, 4]).unwrap();
+        assert!(matches!(set.min_distance_squared(&probe), Err(CircuitError::InvalidEmbedding(_))));
+    }
+    
+    #[test]
+    fn test_constant_time_distance_matches() {
+        let pairs = [
+            (vec![1, 2, 3, 4], vec![2, 3, 4, 5]),
+            (vec![-7, 0, 12, 5], vec![3, -4, 12, 9]),
+            (vec![1_000_000; 512], vec![-1_000_000; 512]),
+            (vec![i64::MAX; 512], vec![i64::MIN; 512]),
+            (vec![3_000_000_000; 512], vec![0; 512]),
+        ];
+        
+        for (a, b) in pairs {
+            let (a, b) = (BiometricEmbedding::new(a).unwrap(), BiometricEmbedding::new(b).unwrap());
+            let expected = a.compute_distance_squared(&b).ok();
+            let ct: Option<u64> = a.compute_distance_squared_ct(&b).unwrap().into();
+            assert_eq!(ct, expected);
+            
+            for threshold in [0, 4, 100, u64::MAX] {
+                let within = expected.is_some_and(|distance| distance <= threshold);
+                assert_eq!(bool::from(a.within_threshold_ct(&b, threshold).unwrap()), within);
+            }
+        }
+        
+        let short = BiometricEmbedding::new(vec![1, 2]).unwrap();
+        let long = BiometricEmbedding::new(vec![1, 2, 3]).unwrap();
+        assert!(matches!(short.compute_distance_squared_ct(&long), Err(CircuitError::InvalidEmbedding(_))));
+    }
+    
+    #[test]
+    fn test_concat_modalities() {
+        let face = BiometricEmbedding::new(vec![10, -20]).unwrap();
+        let voice = BiometricEmbedding::new(vec![1, 2, 3]).unwrap();
+        let fused = BiometricEmbedding::concat(&[face, voice]).unwrap();
+        assert_eq!(fused.size, 5);
+        assert_eq!(fused.data, vec![10, -20, 1, 2, 3]);
+        
+        let probe = BiometricEmbedding::concat(&[
+            BiometricEmbedding::new(vec![12, -20]).unwrap(),
+            BiometricEmbedding::new(vec![1, 5, 3]).unwrap(),
+        ]).unwrap();
+        
+        // 2² from the face part plus 3² from the voice part
+        assert_eq!(fused.compute_distance_squared(&probe).unwrap(), 13);
+    }
+    
+    #[test]
+    fn test_concat_rejects_empty_and_oversized() {
+        assert!(BiometricEmbedding::concat(&[]).is_err());
+        
+        let half = BiometricEmbedding::new(vec![1; crate::config::MAX_EMBEDDING_SIZE / 2]).unwrap();
+        assert!(BiometricEmbedding::concat(&[half.clone(), half.clone()]).is_ok());
+        assert!(matches!(
+            BiometricEmbedding::concat(&[half.clone(), half, BiometricEmbedding::new(vec![1]).unwrap()]),
+            Err(CircuitError::InvalidEmbedding(_))
+        ));
+    }
+    
+    #[test]
+    fn test_from_float_matrix() {
+        let rows = vec![
+            vec![0.1, 0.2, 0.3],
+            vec![0.4, 0.5, 0.6],
+        ];
+        
+        let embeddings = BiometricEmbedding::from_float_matrix(&rows, 1000).unwrap();
+        assert_eq!(embeddings.len(), 2);
+        for (embedding, row) in embeddings.iter().zip(&rows) {
+            let single = BiometricEmbedding::from_floats(row.clone(), 1000).unwrap();
+            assert_eq!(embedding.data, single.data);
+            assert_eq!(embedding.size, 3);
+        }
+    }
+    
+    #[test]
+    fn test_from_float_matrix_rejects_ragged_rows() {
+        let rows = vec![
+            vec![0.1, 0.2, 0.3],
+            vec![0.4, 0.5],
+        ];
+        
+        match BiometricEmbedding::from_float_matrix(&rows, 1000) {
+            Err(CircuitError::InvalidEmbedding(msg)) => assert!(msg.contains("Row 1")),
+            other => panic!("expected ragged matrix error, got {:?}", other),
+        }
+        
+        assert!(BiometricEmbedding::from_float_matrix(&[], 1000).is_err());
+    }
+    
+    #[test]
+    fn test_from_float_matrix_reports_nan_row() {
+        let rows = vec![
+            vec![0.1, 0.2, 0.3],
+            vec![0.4, 0.5, 0.6],
+            vec![0.7, f64::NAN, 0.9],
+        ];
+        
+        match BiometricEmbedding::from_float_matrix(&rows, 1000) {
+            Err(CircuitError::InvalidEmbedding(msg)) => {
+                assert!(msg.contains("Row 2"));
+                assert!(msg.contains("index 1"));
+            }
+            other => panic!("expected NaN error, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,223 @@
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use alloc::format;
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+use crate::backend::curve25519::ristretto::CompressedRistretto;
+use crate::backend::curve25519::scalar::Scalar;
+
+use crate::types::{BiometricProof, CircuitError, CircuitResult, SerializableCommitment};
+
+/// Published enrollment data: the reference embedding commitments and the
+/// parameters they were produced under
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnrollmentRecord {
+    pub reference_commitments: Vec<Vec<u8>>,
+    pub embedding_size: usize,
+    pub threshold: u64,
+}
+
+impl EnrollmentRecord {
+    pub fn new(reference_commitments: Vec<Vec<u8>>, embedding_size: usize, threshold: u64) -> Self {
+        Self {
+            reference_commitments,
+            embedding_size,
+            threshold,
+        }
+    }
+    
+    /// Build a record from compressed commitment points
+    pub fn from_commitments(commitments: &[CompressedRistretto], threshold: u64) -> Self {
+        Self::new(
+            commitments.iter().map(|c| c.to_bytes().to_vec()).collect(),
+            commitments.len(),
+            threshold,
+        )
+    }
+    
+    /// Decode the stored commitments for use with `BiometricCircuit::verify_proof`
+    ///
+    /// Every entry must be a 32-byte encoding of a valid Ristretto point.
+    pub fn to_commitments(&self) -> CircuitResult<Vec<CompressedRistretto>> {
+        if self.reference_commitments.len() != self.embedding_size {
+            return Err(CircuitError::InvalidCommitment(
+                format!(
+                    "Enrollment has {} commitments but embedding size {}",
+                    self.reference_commitments.len(),
+                    self.embedding_size
+                )
+            ));
+        }
+        
+        self.reference_commitments
+            .iter()
+            .enumerate()
+            .map(|(i, bytes)| {
+                let encoding: [u8; 32] = bytes.as_slice().try_into().map_err(|_| {
+                    CircuitError::InvalidCommitment(
+                        format!("Invalid commitment {} length: {}", i, bytes.len())
+                    )
+                })?;
+                
+                let point = CompressedRistretto(encoding);
+                if point.decompress().is_none() {
+                    return Err(CircuitError::InvalidCommitment(
+                        format!("Commitment {} is not a valid Ristretto point", i)
+                    ));
+                }
+                
+                Ok(point)
+            })
+            .collect()
+    }
+}
+
+/// Commitments to an enrolled reference embedding, produced by
+/// `BiometricCircuit::enroll` and checked by `BiometricCircuit::verify_enrolled`
+///
+/// Holds the blinded commitments a proof from `BiometricCircuit::prove_enrolled`
+/// carries after its probe commitments, and their hash, so a stored bundle
+/// that was altered is caught before it is trusted. The blindings are in the
+/// matching `EnrollmentOpening`, which never leaves the enrolling device.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct EnrollmentCommitment {
+    pub reference_commitments: Vec<SerializableCommitment>,
+    /// `BiometricProof::hash_commitments` of the reference commitments
+    pub commitment_hash: Vec<u8>,
+    /// Fixed-point scale the reference was quantized with, if recorded
+    pub scale_factor: Option<i64>,
+    /// Generator rotation epoch the commitments were made under, if any
+    pub generator_epoch: Option<u64>,
+}
+
+impl EnrollmentCommitment {
+    pub fn new(
+        reference_commitments: Vec<SerializableCommitment>,
+        scale_factor: Option<i64>,
+        generator_epoch: Option<u64>,
+    ) -> Self {
+        let commitment_hash = BiometricProof::hash_commitments(&reference_commitments);
+        Self {
+            reference_commitments,
+            commitment_hash,
+            scale_factor,
+            generator_epoch,
+        }
+    }
+    
+    pub fn embedding_size(&self) -> usize {
+        self.reference_commitments.len()
+    }
+    
+    /// Check the stored hash still matches the commitments
+    pub fn verify_commitment_hash(&self) -> CircuitResult<()> {
+        let expected = BiometricProof::hash_commitments(&self.reference_commitments);
+        if !crate::crypto::HashUtils::verify_hash(&expected, &self.commitment_hash) {
+            return Err(CircuitError::InvalidCommitment(
+                "Enrollment commitment hash does not match its commitments".to_string()
+            ));
+        }
+        Ok(())
+    }
+    
+    /// The commitments as points, for use with `BiometricCircuit::verify_proof`
+    pub fn to_commitments(&self) -> Vec<CompressedRistretto> {
+        self.reference_commitments.iter().map(|c| c.0).collect()
+    }
+}
+
+/// Blindings opening an `EnrollmentCommitment`, one per reference coordinate
+///
+/// Anyone holding both the opening and the commitments can brute-force the
+/// enrolled template, so the opening stays on the enrolling device and is
+/// zeroized on drop.
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize, Zeroize, ZeroizeOnDrop)]
+pub struct EnrollmentOpening {
+    pub reference_blindings: Vec<[u8; 32]>,
+}
+
+impl EnrollmentOpening {
+    pub fn new(reference_blindings: &[Scalar]) -> Self {
+        Self {
+            reference_blindings: reference_blindings.iter().map(Scalar::to_bytes).collect(),
+        }
+    }
+    
+    pub fn embedding_size(&self) -> usize {
+        self.reference_blindings.len()
+    }
+    
+    /// Decode the blindings; each must be a canonical scalar encoding
+    pub fn blindings(&self) -> CircuitResult<Zeroizing<Vec<Scalar>>> {
+        let mut blindings = Zeroizing::new(Vec::with_capacity(self.reference_blindings.len()));
+        for (i, bytes) in self.reference_blindings.iter().enumerate() {
+            let blinding = Scalar::from_bytes_mod_order(*bytes);
+            if blinding.to_bytes() != *bytes {
+                return Err(CircuitError::InvalidCommitment(
+                    format!("Enrollment blinding {} is not a canonical scalar", i)
+                ));
+            }
+            blindings.push(blinding);
+        }
+        Ok(blindings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+    use alloc::vec;
+    use crate::backend::bulletproofs::PedersenGens;
+    use crate::backend::curve25519::scalar::Scalar;
+    
+    fn sample_commitments() -> Vec<CompressedRistretto> {
+        let pc_gens = PedersenGens::default();
+        (1..=4u64)
+            .map(|v| pc_gens.commit(Scalar::from(v), Scalar::from(v + 100)).compress())
+            .collect()
+    }
+    
+    #[test]
+    fn test_enrollment_round_trip() {
+        let commitments = sample_commitments();
+        let record = EnrollmentRecord::from_commitments(&commitments, 1000);
+        
+        let json = serde_json::to_vec(&record).unwrap();
+        let restored: EnrollmentRecord = serde_json::from_slice(&json).unwrap();
+        
+        assert_eq!(restored.embedding_size, 4);
+        assert_eq!(restored.threshold, 1000);
+        assert_eq!(restored.to_commitments().unwrap(), commitments);
+    }
+    
+    #[test]
+    fn test_enrollment_rejects_invalid_points() {
+        let mut record = EnrollmentRecord::from_commitments(&sample_commitments(), 1000);
+        
+        // Wrong length
+        record.reference_commitments[1] = vec![0u8; 31];
+        assert!(matches!(record.to_commitments(), Err(CircuitError::InvalidCommitment(_))));
+        
+        // Not a canonical point encoding
+        record.reference_commitments[1] = vec![0xff; 32];
+        assert!(matches!(record.to_commitments(), Err(CircuitError::InvalidCommitment(_))));
+        
+        // Count disagrees with the declared embedding size
+        let mut record = EnrollmentRecord::from_commitments(&sample_commitments(), 1000);
+        record.embedding_size = 8;
+        assert!(record.to_commitments().is_err());
+    }
+    
+    #[test]
+    fn test_opening_rejects_non_canonical_blindings() {
+        let opening = EnrollmentOpening::new(&[Scalar::from(7u64), Scalar::from(8u64)]);
+        assert_eq!(opening.embedding_size(), 2);
+        assert_eq!(opening.blindings().unwrap().as_slice(), &[Scalar::from(7u64), Scalar::from(8u64)]);
+        
+        let mut tampered = opening.clone();
+        tampered.reference_blindings[1] = [0xff; 32];
+        assert!(matches!(tampered.blindings(), Err(CircuitError::InvalidCommitment(_))));
+    }
+}
@@ -1,7 +1,15 @@
 pub mod embedding;
+pub mod commitment;
 pub mod proof_data;
 pub mod error;
+pub mod enrollment;
+pub mod description;
+pub mod policy;
 
 pub use embedding::*;
+pub use commitment::SerializableCommitment;
 pub use proof_data::*;
 pub use error::*;
+pub use enrollment::*;
+pub use description::*;
+pub use policy::*;
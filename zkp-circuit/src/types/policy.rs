@@ -0,0 +1,41 @@
+use crate::config::SimilarityMetric;
+
+/// Verification requirements that are applied together
+///
+/// Bundles what a deployment expects of every proof so a verifier checks
+/// them in one call with `BiometricCircuit::verify_with_policy`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerificationPolicy {
+    /// Threshold the proof and circuit must both use
+    pub threshold: u64,
+    /// Metric the circuit must threshold on
+    pub metric: SimilarityMetric,
+    /// Oldest acceptable proof, in seconds since its metadata timestamp
+    pub max_proof_age: Option<u64>,
+    /// Lowest acceptable liveness score recorded in the public inputs
+    pub min_liveness: Option<u64>,
+}
+
+impl VerificationPolicy {
+    /// Policy requiring only `threshold` and `metric`
+    pub fn new(threshold: u64, metric: SimilarityMetric) -> Self {
+        Self {
+            threshold,
+            metric,
+            max_proof_age: None,
+            min_liveness: None,
+        }
+    }
+    
+    /// Reject proofs older than `seconds`
+    pub fn with_max_proof_age(mut self, seconds: u64) -> Self {
+        self.max_proof_age = Some(seconds);
+        self
+    }
+    
+    /// Reject proofs without a liveness score of at least `score`
+    pub fn with_min_liveness(mut self, score: u64) -> Self {
+        self.min_liveness = Some(score);
+        self
+    }
+}
@@ -1,33 +1,436 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::format;
+use core::ops::RangeInclusive;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use borsh::{BorshDeserialize, BorshSerialize};
 use serde::{Deserialize, Serialize};
-use curve25519_dalek_ng::ristretto::CompressedRistretto;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::types::SerializableCommitment;
 
 /// Represents a Zero-Knowledge Proof for biometric verification
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 pub struct BiometricProof {
     pub proof: Vec<u8>,
-    pub commitments: Vec<Vec<u8>>,
+    pub commitments: Vec<SerializableCommitment>,
     pub public_inputs: ProofPublicInputs,
     pub metadata: ProofMetadata,
 }
 
 /// Public inputs that are revealed during proof verification
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 pub struct ProofPublicInputs {
     pub threshold: u64,
     pub embedding_size: usize,
     pub commitment_hash: Vec<u8>,
+    /// Hash of the device attestation the proof is bound to, if any
+    #[serde(default)]
+    pub device_binding: Option<Vec<u8>>,
+    /// Generator rotation epoch the commitments were made under, if any
+    #[serde(default)]
+    pub generator_epoch: Option<u64>,
+    /// Fixed-point scale the embeddings were quantized with, if recorded
+    #[serde(default)]
+    pub scale_factor: Option<i64>,
+    /// Liveness score reported by the capture pipeline, if recorded
+    #[serde(default)]
+    pub liveness_score: Option<u64>,
+    /// Inclusive `(min, max)` squared-distance bounds of a
+    /// `BiometricCircuit::prove_in_range` proof, if any
+    #[serde(default)]
+    pub distance_range: Option<(u64, u64)>,
+    /// Verifier-issued freshness challenge the proof is bound to; empty if none
+    #[serde(default)]
+    pub challenge: Vec<u8>,
+}
+
+impl ProofPublicInputs {
+    /// Derive the device binding for a platform attestation token
+    /// (Play Integrity / DeviceCheck)
+    pub fn device_binding_for(attestation: &[u8]) -> Vec<u8> {
+        crate::crypto::HashUtils::domain_hash(crate::config::DEVICE_BINDING_LABEL, &[attestation])
+    }
+    
+    /// Check the proof's device binding against the one the verifier expects
+    ///
+    /// With no expected binding every proof is accepted; otherwise the proof
+    /// must carry exactly that binding.
+    pub fn check_device_binding(&self, expected: Option<&[u8]>) -> Result<(), crate::types::CircuitError> {
+        let Some(expected) = expected else {
+            return Ok(());
+        };
+        
+        match &self.device_binding {
+            Some(binding) if crate::crypto::HashUtils::verify_hash(expected, binding) => Ok(()),
+            Some(_) => Err(crate::types::CircuitError::ProofVerificationFailed(
+                "Device binding mismatch".to_string()
+            )),
+            None => Err(crate::types::CircuitError::ProofVerificationFailed(
+                "Proof is not bound to a device".to_string()
+            )),
+        }
+    }
+    
+    /// Check the proof's challenge against the one the verifier issued
+    ///
+    /// The comparison is constant-time; a proof made for another challenge,
+    /// or for none, is rejected.
+    pub fn check_challenge(&self, expected: &[u8]) -> Result<(), crate::types::CircuitError> {
+        if crate::crypto::HashUtils::verify_hash(expected, &self.challenge) {
+            Ok(())
+        } else {
+            Err(crate::types::CircuitError::ProofVerificationFailed(
+                "Challenge mismatch".to_string()
+            ))
+        }
+    }
+    
+    /// Check the declared threshold and embedding size against a verifier policy
+    ///
+    /// Needs only the public inputs, so a gateway can turn away out-of-policy
+    /// proofs before any cryptographic verification.
+    pub fn check_policy(
+        &self,
+        allowed_thresholds: &RangeInclusive<u64>,
+        allowed_sizes: &[usize],
+    ) -> Result<(), crate::types::CircuitError> {
+        if !allowed_thresholds.contains(&self.threshold) {
+            return Err(crate::types::CircuitError::ProofVerificationFailed(format!(
+                "Threshold {} is outside the allowed range [{}, {}]",
+                self.threshold,
+                allowed_thresholds.start(),
+                allowed_thresholds.end()
+            )));
+        }
+        
+        if !allowed_sizes.contains(&self.embedding_size) {
+            return Err(crate::types::CircuitError::ProofVerificationFailed(format!(
+                "Embedding size {} is not one of the allowed sizes {:?}",
+                self.embedding_size, allowed_sizes
+            )));
+        }
+        
+        Ok(())
+    }
+    
+    /// Canonical binary encoding of the public inputs
+    ///
+    /// Layout: threshold (u64 LE), embedding size (u32 LE), the commitment
+    /// hash prefixed by its length (u32 LE), then a presence byte followed,
+    /// when set, by the length-prefixed device binding, then the same for the
+    /// generator epoch (u64 LE), the scale factor (i64 LE), the liveness
+    /// score (u64 LE) and the distance range (two u64 LE), and finally the
+    /// challenge prefixed by its length (u32 LE).
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let binding_len = self.device_binding.as_ref().map_or(0, |b| 4 + b.len());
+        let epoch_len = self.generator_epoch.map_or(0, |_| 8);
+        let scale_len = self.scale_factor.map_or(0, |_| 8);
+        let liveness_len = self.liveness_score.map_or(0, |_| 8);
+        let range_len = self.distance_range.map_or(0, |_| 16);
+        let mut buffer = Vec::with_capacity(
+            25 + self.commitment_hash.len() + binding_len + epoch_len + scale_len + liveness_len + range_len + self.challenge.len()
+        );
+        buffer.extend_from_slice(&self.threshold.to_le_bytes());
+        buffer.extend_from_slice(&(self.embedding_size as u32).to_le_bytes());
+        buffer.extend_from_slice(&(self.commitment_hash.len() as u32).to_le_bytes());
+        buffer.extend_from_slice(&self.commitment_hash);
+        match &self.device_binding {
+            Some(binding) => {
+                buffer.push(1);
+                buffer.extend_from_slice(&(binding.len() as u32).to_le_bytes());
+                buffer.extend_from_slice(binding);
+            }
+            None => buffer.push(0),
+        }
+        match self.generator_epoch {
+            Some(epoch) => {
+                buffer.push(1);
+                buffer.extend_from_slice(&epoch.to_le_bytes());
+            }
+            None => buffer.push(0),
+        }
+        match self.scale_factor {
+            Some(scale_factor) => {
+                buffer.push(1);
+                buffer.extend_from_slice(&scale_factor.to_le_bytes());
+            }
+            None => buffer.push(0),
+        }
+        match self.liveness_score {
+            Some(score) => {
+                buffer.push(1);
+                buffer.extend_from_slice(&score.to_le_bytes());
+            }
+            None => buffer.push(0),
+        }
+        match self.distance_range {
+            Some((min, max)) => {
+                buffer.push(1);
+                buffer.extend_from_slice(&min.to_le_bytes());
+                buffer.extend_from_slice(&max.to_le_bytes());
+            }
+            None => buffer.push(0),
+        }
+        buffer.extend_from_slice(&(self.challenge.len() as u32).to_le_bytes());
+        buffer.extend_from_slice(&self.challenge);
+        buffer
+    }
+    
+    /// Decode public inputs written by `to_bytes`, rejecting trailing data
+    pub fn from_bytes(data: &[u8]) -> Result<Self, crate::types::CircuitError> {
+        let (inputs, consumed) = Self::read_from(data)?;
+        if consumed != data.len() {
+            return Err(crate::types::CircuitError::SerializationError(
+                format!("Unexpected {} trailing bytes after public inputs", data.len() - consumed)
+            ));
+        }
+        Ok(inputs)
+    }
+    
+    /// Decode public inputs from the front of `data`, returning the bytes consumed
+    pub(crate) fn read_from(data: &[u8]) -> Result<(Self, usize), crate::types::CircuitError> {
+        let insufficient = |what: &str| {
+            crate::types::CircuitError::SerializationError(format!("Insufficient data for {}", what))
+        };
+        
+        if data.len() < 16 {
+            return Err(insufficient("public inputs header"));
+        }
+        let threshold = u64::from_le_bytes(data[0..8].try_into().unwrap());
+        let embedding_size = u32::from_le_bytes(data[8..12].try_into().unwrap()) as usize;
+        let hash_len = u32::from_le_bytes(data[12..16].try_into().unwrap()) as usize;
+        
+        if data.len() - 16 < hash_len {
+            return Err(insufficient("commitment hash"));
+        }
+        let commitment_hash = data[16..16 + hash_len].to_vec();
+        let mut offset = 16 + hash_len;
+        
+        let device_binding = match data.get(offset) {
+            Some(0) => {
+                offset += 1;
+                None
+            }
+            Some(1) => {
+                offset += 1;
+                if data.len() - offset < 4 {
+                    return Err(insufficient("device binding length"));
+                }
+                let binding_len = u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap()) as usize;
+                offset += 4;
+                
+                if data.len() - offset < binding_len {
+                    return Err(insufficient("device binding"));
+                }
+                let binding = data[offset..offset + binding_len].to_vec();
+                offset += binding_len;
+                Some(binding)
+            }
+            Some(flag) => {
+                return Err(crate::types::CircuitError::SerializationError(
+                    format!("Invalid device binding flag: {}", flag)
+                ));
+            }
+            None => return Err(insufficient("device binding flag")),
+        };
+        
+        let generator_epoch = match data.get(offset) {
+            Some(0) => {
+                offset += 1;
+                None
+            }
+            Some(1) => {
+                offset += 1;
+                if data.len() - offset < 8 {
+                    return Err(insufficient("generator epoch"));
+                }
+                let epoch = u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+                offset += 8;
+                Some(epoch)
+            }
+            Some(flag) => {
+                return Err(crate::types::CircuitError::SerializationError(
+                    format!("Invalid generator epoch flag: {}", flag)
+                ));
+            }
+            None => return Err(insufficient("generator epoch flag")),
+        };
+        
+        let scale_factor = match data.get(offset) {
+            Some(0) => {
+                offset += 1;
+                None
+            }
+            Some(1) => {
+                offset += 1;
+                if data.len() - offset < 8 {
+                    return Err(insufficient("scale factor"));
+                }
+                let scale_factor = i64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+                offset += 8;
+                Some(scale_factor)
+            }
+            Some(flag) => {
+                return Err(crate::types::CircuitError::SerializationError(
+                    format!("Invalid scale factor flag: {}", flag)
+                ));
+            }
+            None => return Err(insufficient("scale factor flag")),
+        };
+        
+        let liveness_score = match data.get(offset) {
+            Some(0) => {
+                offset += 1;
+                None
+            }
+            Some(1) => {
+                offset += 1;
+                if data.len() - offset < 8 {
+                    return Err(insufficient("liveness score"));
+                }
+                let score = u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+                offset += 8;
+                Some(score)
+            }
+            Some(flag) => {
+                return Err(crate::types::CircuitError::SerializationError(
+                    format!("Invalid liveness score flag: {}", flag)
+                ));
+            }
+            None => return Err(insufficient("liveness score flag")),
+        };
+        
+        let distance_range = match data.get(offset) {
+            Some(0) => {
+                offset += 1;
+                None
+            }
+            Some(1) => {
+                offset += 1;
+                if data.len() - offset < 16 {
+                    return Err(insufficient("distance range"));
+                }
+                let min = u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+                let max = u64::from_le_bytes(data[offset + 8..offset + 16].try_into().unwrap());
+                offset += 16;
+                Some((min, max))
+            }
+            Some(flag) => {
+                return Err(crate::types::CircuitError::SerializationError(
+                    format!("Invalid distance range flag: {}", flag)
+                ));
+            }
+            None => return Err(insufficient("distance range flag")),
+        };
+        
+        if data.len() - offset < 4 {
+            return Err(insufficient("challenge length"));
+        }
+        let challenge_len = u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap()) as usize;
+        offset += 4;
+        if data.len() - offset < challenge_len {
+            return Err(insufficient("challenge"));
+        }
+        let challenge = data[offset..offset + challenge_len].to_vec();
+        offset += challenge_len;
+        
+        Ok((
+            Self {
+                threshold,
+                embedding_size,
+                commitment_hash,
+                device_binding,
+                generator_epoch,
+                scale_factor,
+                liveness_score,
+                distance_range,
+                challenge,
+            },
+            offset,
+        ))
+    }
 }
 
 /// Metadata about the proof
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 pub struct ProofMetadata {
     pub timestamp: u64,
     pub version: String,
     pub circuit_params: CircuitParams,
+    /// Client-chosen submission nonce, distinguishing otherwise identical proofs
+    #[serde(default)]
+    pub nonce: Vec<u8>,
+    /// Chunk size of a proof made with `BiometricCircuit::prove_chunked`
+    #[serde(default)]
+    pub chunk_size: Option<usize>,
+    /// Number of partial distance commitments following the embedding commitments
+    #[serde(default)]
+    pub chunk_count: Option<usize>,
+}
+
+/// Seconds since the Unix epoch, or `None` when built without `std`
+pub(crate) fn current_timestamp() -> Option<u64> {
+    #[cfg(feature = "std")]
+    return Some(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+    );
+    #[cfg(not(feature = "std"))]
+    return None;
+}
+
+impl ProofMetadata {
+    /// Build metadata stamped with the current time, canonicalizing `version`
+    #[cfg(feature = "std")]
+    pub fn new(version: &str, circuit_params: CircuitParams) -> Result<Self, crate::types::CircuitError> {
+        Self::new_at(version, circuit_params, current_timestamp().unwrap_or_default())
+    }
+    
+    /// Build metadata stamped with a caller-supplied Unix `timestamp`
+    ///
+    /// For targets without a system clock; otherwise as `new`.
+    pub fn new_at(
+        version: &str,
+        circuit_params: CircuitParams,
+        timestamp: u64,
+    ) -> Result<Self, crate::types::CircuitError> {
+        Ok(Self {
+            timestamp,
+            version: Self::canonical_version(version)?,
+            circuit_params,
+            nonce: Vec::new(),
+            chunk_size: None,
+            chunk_count: None,
+        })
+    }
+    
+    /// Parse a version string as semver and return its canonical form
+    ///
+    /// Missing minor and patch components are filled with zero, so "1.2"
+    /// becomes "1.2.0"; anything else that is not semver is rejected.
+    pub fn canonical_version(version: &str) -> Result<String, crate::types::CircuitError> {
+        let trimmed = version.trim();
+        let (core, suffix) = trimmed.split_at(trimmed.find(['-', '+']).unwrap_or(trimmed.len()));
+        let padded = match core.split('.').count() {
+            1 => format!("{}.0.0{}", core, suffix),
+            2 => format!("{}.0{}", core, suffix),
+            _ => trimmed.to_string(),
+        };
+        
+        semver::Version::parse(&padded)
+            .map(|parsed| parsed.to_string())
+            .map_err(|e| crate::types::CircuitError::InvalidParameter(
+                format!("Invalid proof version {:?}: {}", version, e)
+            ))
+    }
 }
 
 /// Circuit parameters used in proof generation
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 pub struct CircuitParams {
     pub range_bits: usize,
     pub aggregation_size: usize,
@@ -35,30 +438,50 @@ pub struct CircuitParams {
 }
 
 impl BiometricProof {
+    #[cfg(feature = "std")]
     pub fn new(
         proof: Vec<u8>,
-        commitments: Vec<Vec<u8>>,
+        commitments: Vec<SerializableCommitment>,
         threshold: u64,
         embedding_size: usize,
         commitment_hash: Vec<u8>,
+    ) -> Self {
+        let timestamp = current_timestamp().unwrap_or_default();
+        Self::new_at(proof, commitments, threshold, embedding_size, commitment_hash, timestamp)
+    }
+    
+    /// `new` with a caller-supplied Unix `timestamp`, for targets without a clock
+    pub fn new_at(
+        proof: Vec<u8>,
+        commitments: Vec<SerializableCommitment>,
+        threshold: u64,
+        embedding_size: usize,
+        commitment_hash: Vec<u8>,
+        timestamp: u64,
     ) -> Self {
         let public_inputs = ProofPublicInputs {
             threshold,
             embedding_size,
             commitment_hash,
+            device_binding: None,
+            generator_epoch: None,
+            scale_factor: None,
+            liveness_score: None,
+            distance_range: None,
+            challenge: Vec::new(),
         };
         
         let metadata = ProofMetadata {
-            timestamp: std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_secs(),
+            timestamp,
             version: env!("CARGO_PKG_VERSION").to_string(),
             circuit_params: CircuitParams {
                 range_bits: crate::config::RANGE_BITS,
                 aggregation_size: crate::config::AGGREGATION_SIZE,
                 transcript_label: String::from_utf8_lossy(crate::config::TRANSCRIPT_LABEL).to_string(),
             },
+            nonce: Vec::new(),
+            chunk_size: None,
+            chunk_count: None,
         };
         
         Self {
@@ -69,11 +492,116 @@ impl BiometricProof {
         }
     }
     
+    /// Bind the proof to a device attestation hash
+    pub fn with_device_binding(mut self, device_binding: Vec<u8>) -> Self {
+        self.public_inputs.device_binding = Some(device_binding);
+        self
+    }
+    
+    /// Record the generator rotation epoch the proof was made under
+    pub fn with_generator_epoch(mut self, epoch: u64) -> Self {
+        self.public_inputs.generator_epoch = Some(epoch);
+        self
+    }
+    
+    /// Record the fixed-point scale the embeddings were quantized with
+    pub fn with_scale_factor(mut self, scale_factor: i64) -> Self {
+        self.public_inputs.scale_factor = Some(scale_factor);
+        self
+    }
+    
+    /// Record the liveness score of the capture the probe came from
+    ///
+    /// Packaged proofs bind their liveness score into the transcript, so one
+    /// recorded after proving fails `BiometricCircuit::verify`; prove with
+    /// `BiometricCircuit::prove_enrolled_at` to supply it instead.
+    pub fn with_liveness_score(mut self, score: u64) -> Self {
+        self.public_inputs.liveness_score = Some(score);
+        self
+    }
+    
+    /// Hash of serialized commitments, as recorded in the public inputs
+    pub fn hash_commitments(commitments: &[SerializableCommitment]) -> Vec<u8> {
+        crate::crypto::HashUtils::commitment_hash(&commitments.iter().map(AsRef::as_ref).collect::<Vec<_>>())
+    }
+    
+    /// Recompute the commitment hash and compare it with the public inputs
+    ///
+    /// A cheap integrity pre-check that needs no curve operations; it does
+    /// not verify the proof itself. The comparison is constant-time. A
+    /// recorded hash of the wrong length is an error.
+    pub fn verify_commitment_hash(&self) -> Result<bool, crate::types::CircuitError> {
+        if self.public_inputs.commitment_hash.len() != crate::config::HASH_OUTPUT_SIZE {
+            return Err(crate::types::CircuitError::InvalidCommitment(
+                format!("Commitment hash has length {}", self.public_inputs.commitment_hash.len())
+            ));
+        }
+        
+        Ok(crate::crypto::HashUtils::verify_hash(
+            &Self::hash_commitments(&self.commitments),
+            &self.public_inputs.commitment_hash,
+        ))
+    }
+    
+    /// Attach a submission nonce; see `proof_id`
+    pub fn with_nonce(mut self, nonce: Vec<u8>) -> Self {
+        self.metadata.nonce = nonce;
+        self
+    }
+    
+    /// Digest of the proof bytes, commitments and public inputs
+    ///
+    /// Metadata is excluded, so re-serializing or re-stamping a proof does
+    /// not change its digest.
+    pub fn canonical_digest(&self) -> [u8; 32] {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(crate::config::PROOF_LABEL);
+        hasher.update(&(self.proof.len() as u32).to_le_bytes());
+        hasher.update(&self.proof);
+        hasher.update(&(self.commitments.len() as u32).to_le_bytes());
+        for commitment in &self.commitments {
+            hasher.update(&(commitment.as_bytes().len() as u32).to_le_bytes());
+            hasher.update(commitment.as_bytes());
+        }
+        hasher.update(&self.public_inputs.to_bytes());
+        *hasher.finalize().as_bytes()
+    }
+    
+    /// Whether both proofs make the same claim
+    ///
+    /// Compares the canonical encoding of the public inputs: the threshold,
+    /// embedding size, commitment hash, device binding, generator epoch,
+    /// scale factor, liveness score, distance range and challenge. The proof
+    /// bytes and metadata are ignored, so a re-proved or re-stamped submission
+    /// of the same claim matches.
+    ///
+    /// This is a comparison, not a check: packaged proofs bind the liveness
+    /// score into their transcript, but the scale factor is not authenticated
+    /// by the proof, so two matching claims are only as trustworthy as
+    /// `verify` makes them.
+    pub fn same_claim(&self, other: &BiometricProof) -> bool {
+        self.public_inputs.to_bytes() == other.public_inputs.to_bytes()
+    }
+    
+    /// Stable identifier for idempotent submission
+    ///
+    /// Derived from `canonical_digest` and the nonce and encoded as unpadded
+    /// base64url, so resubmitting the same proof with the same nonce yields
+    /// the same id.
+    pub fn proof_id(&self) -> String {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(crate::config::PROOF_ID_LABEL);
+        hasher.update(&self.canonical_digest());
+        hasher.update(&(self.metadata.nonce.len() as u32).to_le_bytes());
+        hasher.update(&self.metadata.nonce);
+        URL_SAFE_NO_PAD.encode(hasher.finalize().as_bytes())
+    }
+    
     pub fn size(&self) -> usize {
         self.proof.len() + 
-        self.commitments.iter().map(|c| c.len()).sum::<usize>() +
-        std::mem::size_of_val(&self.public_inputs) +
-        std::mem::size_of_val(&self.metadata)
+        self.commitments.iter().map(|c| c.as_bytes().len()).sum::<usize>() +
+        core::mem::size_of_val(&self.public_inputs) +
+        core::mem::size_of_val(&self.metadata)
     }
     
     pub fn validate_size(&self) -> Result<(), crate::types::CircuitError> {
@@ -85,6 +613,21 @@ impl BiometricProof {
         Ok(())
     }
     
+    /// Whether `metadata.version` meets one of `SUPPORTED_PROOF_VERSIONS`
+    pub fn is_version_supported(&self) -> bool {
+        let Ok(version) = ProofMetadata::canonical_version(&self.metadata.version) else {
+            return false;
+        };
+        let Ok(version) = semver::Version::parse(&version) else {
+            return false;
+        };
+        
+        crate::config::SUPPORTED_PROOF_VERSIONS
+            .iter()
+            .filter_map(|requirement| semver::VersionReq::parse(requirement).ok())
+            .any(|requirement| requirement.matches(&version))
+    }
+    
     pub fn validate_params(&self) -> Result<(), crate::types::CircuitError> {
         if self.public_inputs.threshold == 0 {
             return Err(crate::types::CircuitError::InvalidParameter(
@@ -104,12 +647,201 @@ impl BiometricProof {
             ));
         }
         
+        ProofMetadata::canonical_version(&self.metadata.version)?;
+        
         Ok(())
     }
 }
 
+/// Proofs from many users collected for one settlement-cycle verification
+///
+/// `digest` commits to the ordered set of proofs, so entries cannot be
+/// added, dropped or swapped after aggregation without detection.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AggregatedProof {
+    pub proofs: Vec<BiometricProof>,
+    pub digest: Vec<u8>,
+}
+
+impl AggregatedProof {
+    /// Collect proofs made under the same circuit parameters
+    pub fn aggregate(proofs: &[BiometricProof]) -> Result<Self, crate::types::CircuitError> {
+        let Some(first) = proofs.first() else {
+            return Err(crate::types::CircuitError::InvalidParameter(
+                "Cannot aggregate zero proofs".to_string()
+            ));
+        };
+        
+        let params = |p: &BiometricProof| {
+            (p.public_inputs.embedding_size, p.public_inputs.threshold, p.public_inputs.generator_epoch)
+        };
+        if let Some(i) = proofs.iter().position(|p| params(p) != params(first)) {
+            return Err(crate::types::CircuitError::InvalidParameter(
+                format!("Proof {} was made under different circuit parameters", i)
+            ));
+        }
+        
+        Ok(Self {
+            digest: Self::digest_of(proofs).to_vec(),
+            proofs: proofs.to_vec(),
+        })
+    }
+    
+    /// Verify every aggregated proof in one pass
+    ///
+    /// Fails if the set no longer matches its digest; otherwise returns one
+    /// result per proof, in aggregation order, so failing proofs can be
+    /// identified.
+    pub fn verify_aggregate(&self, circuit: &crate::circuit::BiometricCircuit) -> Result<Vec<bool>, crate::types::CircuitError> {
+        if Self::digest_of(&self.proofs).as_slice() != self.digest.as_slice() {
+            return Err(crate::types::CircuitError::ProofVerificationFailed(
+                "Aggregate digest mismatch".to_string()
+            ));
+        }
+        
+        circuit.verify_batch(&self.proofs)
+    }
+    
+    fn digest_of(proofs: &[BiometricProof]) -> [u8; 32] {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(crate::config::AGGREGATE_LABEL);
+        hasher.update(&(proofs.len() as u32).to_le_bytes());
+        for proof in proofs {
+            hasher.update(&proof.canonical_digest());
+        }
+        *hasher.finalize().as_bytes()
+    }
+}
+
+/// Public table of distance buckets used to coarsen the revealed threshold
+///
+/// Bucket `0` covers `[0, upper_bounds[0]]` and bucket `i` covers
+/// `(upper_bounds[i - 1], upper_bounds[i]]`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThresholdBuckets {
+    pub upper_bounds: Vec<u64>,
+    pub labels: Vec<String>,
+}
+
+impl ThresholdBuckets {
+    pub fn new(upper_bounds: Vec<u64>, labels: Vec<String>) -> Result<Self, crate::types::CircuitError> {
+        if upper_bounds.is_empty() {
+            return Err(crate::types::CircuitError::InvalidParameter(
+                "At least one bucket is required".to_string()
+            ));
+        }
+        
+        if upper_bounds.len() != labels.len() {
+            return Err(crate::types::CircuitError::InvalidParameter(
+                format!("Bucket count {} does not match label count {}", upper_bounds.len(), labels.len())
+            ));
+        }
+        
+        if upper_bounds.windows(2).any(|pair| pair[0] >= pair[1]) {
+            return Err(crate::types::CircuitError::InvalidParameter(
+                "Bucket bounds must be strictly increasing".to_string()
+            ));
+        }
+        
+        Ok(Self { upper_bounds, labels })
+    }
+    
+    /// Index of the bucket containing the given distance, if any
+    pub fn bucket_for(&self, distance: u64) -> Option<usize> {
+        self.upper_bounds.iter().position(|&upper| distance <= upper)
+    }
+    
+    /// Inclusive `(lower, upper)` distance bounds of a bucket
+    pub fn bounds(&self, index: usize) -> Option<(u64, u64)> {
+        let upper = *self.upper_bounds.get(index)?;
+        let lower = if index == 0 { 0 } else { self.upper_bounds[index - 1] + 1 };
+        Some((lower, upper))
+    }
+}
+
+/// Proof that the distance falls in a public bucket, revealing only that bucket
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BucketedProof {
+    pub proof: Vec<u8>,
+    pub bucket_index: usize,
+    pub bucket_label: String,
+}
+
+/// Proof that the distance lies strictly between two public bounds
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntervalProof {
+    pub proof: Vec<u8>,
+    pub low: u64,
+    pub high: u64,
+}
+
+/// Proof that a weighted blend of squared distance and cosine distance is
+/// within a threshold; the weights and threshold are public
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FusedProof {
+    pub proof: Vec<u8>,
+    pub alpha_numerator: u64,
+    pub alpha_denominator: u64,
+    pub threshold: u64,
+}
+
+/// Proof that several probe/reference pairs each match under their own
+/// threshold; the thresholds are public, one per pair in proving order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AggregateMatchProof {
+    pub proof: Vec<u8>,
+    pub thresholds: Vec<u64>,
+}
+
+/// Proof that a probe matches the enrollment and stays close to the previous
+/// accepted probe of the session
+///
+/// The enrollment threshold is the circuit's; `continuity_threshold` bounds
+/// the squared distance between consecutive probes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContinuityProof {
+    pub proof: Vec<u8>,
+    pub continuity_threshold: u64,
+}
+
+/// Proof over a chunked embedding: one committed partial distance per chunk,
+/// with the partials summed under a single threshold constraint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkedProof {
+    pub proof: Vec<u8>,
+    pub chunk_size: usize,
+    /// Compressed commitments to each chunk's partial squared distance
+    pub partial_commitments: Vec<Vec<u8>>,
+}
+
+/// Proof carrying a linking tag derived from the reference and a context
+///
+/// Two proofs over the same reference under the same context share a tag,
+/// which lets a service show they came from the same enrollment without
+/// learning anything else about it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkableProof {
+    pub proof: Vec<u8>,
+    pub context: Vec<u8>,
+    /// Canonical encoding of the `LinkingTag` scalar the proof constrains
+    pub tag: Vec<u8>,
+}
+
+impl LinkableProof {
+    pub fn linking_tag(&self) -> &[u8] {
+        &self.tag
+    }
+    
+    /// Whether both proofs were made under the same context from the same reference
+    pub fn is_linked_to(&self, other: &LinkableProof) -> bool {
+        self.context == other.context && crate::crypto::HashUtils::verify_hash(&self.tag, &other.tag)
+    }
+}
+
 /// Witness data used in proof generation (kept private)
-#[derive(Debug, Clone)]
+///
+/// Zeroized on drop, so the raw embeddings do not linger in freed memory.
+#[derive(Debug, Clone, Zeroize, ZeroizeOnDrop)]
 pub struct ProofWitness {
     pub current_embedding: Vec<i64>,
     pub reference_embedding: Vec<i64>,
@@ -129,15 +861,7 @@ impl ProofWitness {
             ));
         }
         
-        // Compute distance squared
-        let distance_squared: u64 = current_embedding
-            .iter()
-            .zip(&reference_embedding)
-            .map(|(&a, &b)| {
-                let diff = a - b;
-                (diff * diff) as u64
-            })
-            .sum();
+        let distance_squared = crate::types::embedding::squared_distance(&current_embedding, &reference_embedding)?;
         
         Ok(Self {
             current_embedding,
@@ -157,3 +881,275 @@ impl ProofWitness {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+    use alloc::vec;
+    use crate::backend::bulletproofs::PedersenGens;
+    use crate::backend::curve25519::scalar::Scalar;
+    use crate::utils::scalar_utils::ScalarUtils;
+    
+    fn commitment(value: u64) -> SerializableCommitment {
+        PedersenGens::default().commit(Scalar::from(value), ScalarUtils::one()).into()
+    }
+    
+    #[test]
+    fn test_public_inputs_round_trip() {
+        let inputs = ProofPublicInputs {
+            threshold: 1000,
+            embedding_size: 128,
+            commitment_hash: (0..32).collect(),
+            device_binding: None,
+            generator_epoch: None,
+            scale_factor: None,
+            liveness_score: None,
+            distance_range: None,
+            challenge: Vec::new(),
+        };
+        
+        let bytes = inputs.to_bytes();
+        let decoded = ProofPublicInputs::from_bytes(&bytes).unwrap();
+        
+        assert_eq!(decoded.threshold, inputs.threshold);
+        assert_eq!(decoded.embedding_size, inputs.embedding_size);
+        assert_eq!(decoded.commitment_hash, inputs.commitment_hash);
+        assert_eq!(decoded.device_binding, None);
+        assert_eq!(decoded.to_bytes(), bytes);
+        
+        let bound = ProofPublicInputs {
+            device_binding: Some(ProofPublicInputs::device_binding_for(b"attestation")),
+            ..inputs
+        };
+        let decoded = ProofPublicInputs::from_bytes(&bound.to_bytes()).unwrap();
+        assert_eq!(decoded.device_binding, bound.device_binding);
+        
+        let rotated = ProofPublicInputs {
+            generator_epoch: Some(7),
+            scale_factor: Some(-1000),
+            liveness_score: Some(870),
+            distance_range: Some((50, 400)),
+            ..bound
+        };
+        let decoded = ProofPublicInputs::from_bytes(&rotated.to_bytes()).unwrap();
+        assert_eq!(decoded.generator_epoch, Some(7));
+        assert_eq!(decoded.scale_factor, Some(-1000));
+        assert_eq!(decoded.liveness_score, Some(870));
+        assert_eq!(decoded.distance_range, Some((50, 400)));
+        assert_eq!(decoded.device_binding, rotated.device_binding);
+    }
+    
+    #[test]
+    fn test_public_inputs_rejects_truncated_and_trailing() {
+        let inputs = ProofPublicInputs {
+            threshold: 1000,
+            embedding_size: 128,
+            commitment_hash: vec![7; 32],
+            device_binding: Some(vec![9; 32]),
+            generator_epoch: Some(3),
+            scale_factor: Some(1000),
+            liveness_score: Some(900),
+            distance_range: Some((10, 600)),
+            challenge: b"session-42".to_vec(),
+        };
+        let bytes = inputs.to_bytes();
+        
+        assert!(ProofPublicInputs::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(ProofPublicInputs::from_bytes(&bytes[..10]).is_err());
+        
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(ProofPublicInputs::from_bytes(&trailing).is_err());
+    }
+    
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_public_inputs_smaller_than_full_proof() {
+        let proof = BiometricProof::new(
+            vec![0xab; 672],
+            vec![commitment(0xcd); 8],
+            1000,
+            4,
+            vec![0; 32],
+        );
+        
+        let public_bytes = proof.public_inputs.to_bytes();
+        let full_bytes = crate::utils::SerializationUtils::serialize_proof(&proof).unwrap();
+        
+        assert_eq!(public_bytes.len(), 57);
+        assert!(public_bytes.len() * 10 < full_bytes.len());
+    }
+    
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_device_binding_check() {
+        let device_a = ProofPublicInputs::device_binding_for(b"device-a-attestation");
+        let device_b = ProofPublicInputs::device_binding_for(b"device-b-attestation");
+        assert_ne!(device_a, device_b);
+        
+        let proof = BiometricProof::new(vec![0xab; 672], vec![], 1000, 4, vec![0; 32])
+            .with_device_binding(device_a.clone());
+        let inputs = &proof.public_inputs;
+        
+        assert!(inputs.check_device_binding(None).is_ok());
+        assert!(inputs.check_device_binding(Some(&device_a)).is_ok());
+        assert!(inputs.check_device_binding(Some(&device_b)).is_err());
+        
+        // An unbound proof cannot satisfy a verifier that expects a binding
+        let unbound = BiometricProof::new(vec![0xab; 672], vec![], 1000, 4, vec![0; 32]);
+        assert!(unbound.public_inputs.check_device_binding(Some(&device_a)).is_err());
+    }
+    
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_challenge_check() {
+        let mut proof = BiometricProof::new(vec![0xab; 672], vec![], 1000, 4, vec![0; 32]);
+        proof.public_inputs.challenge = b"challenge-a".to_vec();
+        let inputs = &proof.public_inputs;
+        
+        assert!(inputs.check_challenge(b"challenge-a").is_ok());
+        assert!(inputs.check_challenge(b"challenge-b").is_err());
+        assert!(inputs.check_challenge(b"").is_err());
+        assert_eq!(ProofPublicInputs::from_bytes(&inputs.to_bytes()).unwrap().challenge, b"challenge-a");
+    }
+    
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_check_policy() {
+        let inputs = BiometricProof::new(vec![0xab; 672], vec![], 1000, 128, vec![0; 32]).public_inputs;
+        assert!(inputs.check_policy(&(500..=1000), &[128, 512]).is_ok());
+        
+        let error = inputs.check_policy(&(100..=999), &[128, 512]).unwrap_err();
+        assert!(error.to_string().contains("Threshold 1000 is outside the allowed range [100, 999]"), "{}", error);
+        
+        let error = inputs.check_policy(&(500..=1000), &[64, 512]).unwrap_err();
+        assert!(error.to_string().contains("Embedding size 128 is not one of the allowed sizes [64, 512]"), "{}", error);
+    }
+    
+    #[test]
+    fn test_same_claim() {
+        let proof = BiometricProof::new_at(vec![0xab; 672], vec![commitment(1)], 1000, 4, vec![3; 32], 1_700_000_000);
+        
+        let mut restamped = BiometricProof::new_at(vec![0xcd; 672], vec![commitment(1)], 1000, 4, vec![3; 32], 1_700_000_600);
+        restamped.metadata.nonce = vec![1, 2, 3];
+        assert!(proof.same_claim(&restamped));
+        
+        let looser = BiometricProof::new_at(vec![0xab; 672], vec![commitment(1)], 2000, 4, vec![3; 32], 1_700_000_000);
+        assert!(!proof.same_claim(&looser));
+        
+        let other_hash = BiometricProof::new_at(vec![0xab; 672], vec![commitment(1)], 1000, 4, vec![4; 32], 1_700_000_000);
+        assert!(!proof.same_claim(&other_hash));
+    }
+    
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_verify_commitment_hash() {
+        let commitments = vec![commitment(1), commitment(2)];
+        let hash = BiometricProof::hash_commitments(&commitments);
+        let proof = BiometricProof::new(vec![0; 8], commitments, 1000, 1, hash);
+        assert!(proof.verify_commitment_hash().unwrap());
+        
+        let mut mutated = proof.clone();
+        mutated.commitments[1] = commitment(3);
+        assert!(!mutated.verify_commitment_hash().unwrap());
+        
+        let mut truncated = proof;
+        truncated.public_inputs.commitment_hash.pop();
+        assert!(truncated.verify_commitment_hash().is_err());
+    }
+    
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_proof_id_is_stable_per_nonce() {
+        let proof = BiometricProof::new(vec![1, 2, 3], vec![commitment(4)], 1000, 1, vec![5; 32])
+            .with_nonce(b"attempt-1".to_vec());
+        
+        let mut retried = proof.clone();
+        retried.metadata.timestamp += 60;
+        assert_eq!(proof.proof_id(), retried.proof_id());
+        assert_eq!(proof.proof_id().len(), 43);
+        assert!(proof.proof_id().chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+        
+        let renonced = proof.clone().with_nonce(b"attempt-2".to_vec());
+        assert_eq!(proof.canonical_digest(), renonced.canonical_digest());
+        assert_ne!(proof.proof_id(), renonced.proof_id());
+        
+        let mut altered = proof.clone();
+        altered.proof[0] ^= 1;
+        assert_ne!(proof.proof_id(), altered.proof_id());
+    }
+    
+    #[test]
+    fn test_explicit_timestamps() {
+        let proof = BiometricProof::new_at(vec![1], vec![], 1000, 4, vec![0; 32], 1_700_000_000);
+        assert_eq!(proof.metadata.timestamp, 1_700_000_000);
+        
+        let metadata = ProofMetadata::new_at("1.2", proof.metadata.circuit_params.clone(), 42).unwrap();
+        assert_eq!(metadata.timestamp, 42);
+        assert_eq!(metadata.version, "1.2.0");
+        
+        #[cfg(feature = "std")]
+        assert!(current_timestamp().unwrap() > 1_700_000_000);
+        #[cfg(not(feature = "std"))]
+        assert_eq!(current_timestamp(), None);
+    }
+    
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_metadata_version_canonicalization() {
+        assert_eq!(ProofMetadata::canonical_version("1.2.3").unwrap(), "1.2.3");
+        assert_eq!(ProofMetadata::canonical_version("1.2").unwrap(), "1.2.0");
+        assert_eq!(ProofMetadata::canonical_version("2.0-rc.1").unwrap(), "2.0.0-rc.1");
+        assert!(matches!(
+            ProofMetadata::canonical_version("banana"),
+            Err(crate::types::CircuitError::InvalidParameter(_))
+        ));
+        
+        let params = BiometricProof::new(vec![1], vec![], 1000, 4, vec![0; 32]).metadata.circuit_params;
+        let metadata = ProofMetadata::new("1.2", params).unwrap();
+        assert_eq!(metadata.version, "1.2.0");
+        assert!(metadata.timestamp > 0);
+        
+        let mut proof = BiometricProof::new(vec![1], vec![], 1000, 4, vec![0; 32]);
+        assert!(proof.validate_params().is_ok());
+        proof.metadata.version = "banana".to_string();
+        assert!(proof.validate_params().is_err());
+    }
+    
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_version_support() {
+        let mut proof = BiometricProof::new(vec![1], vec![], 1000, 4, vec![0; 32]);
+        assert!(proof.is_version_supported());
+        
+        for (version, supported) in [("0.1", true), ("0.1.7", true), ("0.2.0", false), ("1.0.0", false), ("banana", false)] {
+            proof.metadata.version = version.to_string();
+            assert_eq!(proof.is_version_supported(), supported, "{}", version);
+        }
+    }
+    
+    #[test]
+    fn test_witness_zeroized_on_drop() {
+        // Drop runs the same `zeroize` as below; the bound pins it in place
+        fn assert_zeroize_on_drop<T: ZeroizeOnDrop>() {}
+        assert_zeroize_on_drop::<ProofWitness>();
+        
+        const MARKER: i64 = 0x5a5a_5a5a;
+        let size = 64;
+        let mut witness = ProofWitness::new(vec![MARKER; size], vec![MARKER + 1; size], vec![0x5a; 32]).unwrap();
+        assert_ne!(witness.distance_squared, 0);
+        
+        witness.zeroize();
+        
+        assert!(witness.current_embedding.is_empty());
+        assert!(witness.reference_embedding.is_empty());
+        assert!(witness.blinding_factors.is_empty());
+        assert_eq!(witness.distance_squared, 0);
+        
+        // The buffers are still allocated, and were overwritten before being cleared
+        let spare = witness.current_embedding.spare_capacity_mut();
+        assert!(spare.len() >= size);
+        assert!(spare.iter().all(|value| unsafe { value.assume_init_read() } == 0));
+    }
+}
@@ -1,18 +1,31 @@
-use curve25519_dalek_ng::scalar::Scalar;
+use alloc::vec::Vec;
+use alloc::format;
+use crate::backend::curve25519::scalar::Scalar;
 use rand::{CryptoRng, RngCore};
+use crate::config::MAX_EMBEDDING_VALUE;
+use crate::types::{CircuitError, CircuitResult};
 
 /// Utilities for working with Scalar values in curve25519-dalek v4.x
+///
+/// These paper over API differences between the curve backends, so crate code
+/// should prefer them to backend-specific constructors.
 pub struct ScalarUtils;
 
 impl ScalarUtils {
     /// Create a zero scalar
     pub fn zero() -> Scalar {
-        Scalar::zero()
+        #[cfg(not(feature = "dalek4"))]
+        return Scalar::zero();
+        #[cfg(feature = "dalek4")]
+        return Scalar::ZERO;
     }
     
     /// Create a one scalar
     pub fn one() -> Scalar {
-        Scalar::one()
+        #[cfg(not(feature = "dalek4"))]
+        return Scalar::one();
+        #[cfg(feature = "dalek4")]
+        return Scalar::ONE;
     }
     
     /// Generate a random scalar
@@ -23,10 +36,84 @@ impl ScalarUtils {
     }
     
     /// Generate a random scalar using OsRng (which implements CryptoRng)
+    #[cfg(feature = "std")]
     pub fn thread_random() -> Scalar {
         use rand::rngs::OsRng;
         let mut bytes = [0u8; 64];
         OsRng.fill_bytes(&mut bytes);
         Scalar::from_bytes_mod_order_wide(&bytes)
     }
+    
+    /// Convert floats to fixed-point integers at `scale`
+    ///
+    /// Rounds to the nearest integer (halves away from zero, so `x` and `-x`
+    /// quantize symmetrically) and clamps to `±MAX_EMBEDDING_VALUE`. Every
+    /// float entry point should quantize through here so probe and reference
+    /// agree across bindings.
+    pub fn quantize(floats: &[f64], scale: i64) -> CircuitResult<Vec<i64>> {
+        if scale <= 0 {
+            return Err(CircuitError::InvalidParameter(
+                format!("Scale factor {} must be positive", scale)
+            ));
+        }
+        
+        floats
+            .iter()
+            .enumerate()
+            .map(|(i, &value)| {
+                if !value.is_finite() {
+                    return Err(CircuitError::InvalidEmbedding(
+                        format!("Float value at index {} is not finite: {}", i, value)
+                    ));
+                }
+                let bound = MAX_EMBEDDING_VALUE as f64;
+                Ok(libm::round(value * scale as f64).clamp(-bound, bound) as i64)
+            })
+            .collect()
+    }
+    
+    /// Map fixed-point integers back to floats at `scale`
+    ///
+    /// Inverts `quantize` up to rounding: for inputs inside the clamp range
+    /// each result is within `0.5 / scale` of the original float. `scale`
+    /// must be the positive factor the values were quantized with.
+    pub fn dequantize(values: &[i64], scale: i64) -> Vec<f64> {
+        values.iter().map(|&value| value as f64 / scale as f64).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn test_quantize_rounds_symmetrically() {
+        assert_eq!(ScalarUtils::quantize(&[0.5, -0.5, 0.4, -0.4], 1).unwrap(), vec![1, -1, 0, 0]);
+        assert_eq!(ScalarUtils::quantize(&[0.1234, -0.1236], 1000).unwrap(), vec![123, -124]);
+        
+        // Truncation would have mapped 0.0029 to 2
+        assert_eq!(ScalarUtils::quantize(&[0.0029], 1000).unwrap(), vec![3]);
+    }
+
+    #[test]
+    fn test_dequantize_round_trip() {
+        let floats = [0.1234567, -0.9876543, 0.0004, -0.0006, 0.5, 0.0];
+        for scale in [10, 1000, 100_000] {
+            let restored = ScalarUtils::dequantize(&ScalarUtils::quantize(&floats, scale).unwrap(), scale);
+            for (original, restored) in floats.iter().zip(&restored) {
+                assert!((original - restored).abs() <= 1.0 / scale as f64);
+            }
+        }
+    }
+
+    #[test]
+    fn test_quantize_clamps_and_rejects() {
+        assert_eq!(
+            ScalarUtils::quantize(&[1e12, -1e12], 1000).unwrap(),
+            vec![MAX_EMBEDDING_VALUE, -MAX_EMBEDDING_VALUE]
+        );
+        assert!(matches!(ScalarUtils::quantize(&[f64::NAN], 1000), Err(CircuitError::InvalidEmbedding(_))));
+        assert!(matches!(ScalarUtils::quantize(&[1.0], 0), Err(CircuitError::InvalidParameter(_))));
+    }
 }
@@ -1,5 +1,8 @@
-use serde::{Deserialize, Serialize};
-use crate::types::{CircuitError, CircuitResult, BiometricProof, BiometricEmbedding};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::format;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use crate::types::{CircuitError, CircuitResult, BiometricProof, BiometricEmbedding, CircuitParams, ProofMetadata, ProofPublicInputs, SerializableCommitment};
 
 /// Serialization utilities for ZKP circuit types
 pub struct SerializationUtils;
@@ -12,9 +15,68 @@ impl SerializationUtils {
     }
     
     /// Deserialize a BiometricProof from JSON bytes
+    ///
+    /// A commitment that is not a valid point fails with `InvalidCommitment`.
     pub fn deserialize_proof(data: &[u8]) -> CircuitResult<BiometricProof> {
-        serde_json::from_slice(data)
-            .map_err(|e| CircuitError::SerializationError(format!("Failed to deserialize proof: {}", e)))
+        serde_json::from_slice(data).map_err(|e| {
+            let message = e.to_string();
+            match message.strip_prefix("Invalid commitment: ") {
+                Some(reason) => CircuitError::InvalidCommitment(reason.to_string()),
+                None => CircuitError::SerializationError(format!("Failed to deserialize proof: {}", message)),
+            }
+        })
+    }
+    
+    /// Serialize a BiometricProof to canonical JSON bytes, stable for hashing
+    ///
+    /// Object keys are sorted bytewise at every level and no whitespace is
+    /// written, so logically equal proofs give identical bytes regardless of
+    /// field declaration order or serde_json's `preserve_order` feature.
+    /// Integers are written in plain decimal; floats, which no proof field
+    /// currently holds, use serde_json's shortest round-trip form.
+    pub fn serialize_proof_canonical(proof: &BiometricProof) -> CircuitResult<Vec<u8>> {
+        let value = serde_json::to_value(proof)
+            .map_err(|e| CircuitError::SerializationError(format!("Failed to serialize proof: {}", e)))?;
+        let mut buffer = Vec::new();
+        Self::write_canonical(&mut buffer, &value)?;
+        Ok(buffer)
+    }
+    
+    /// Append `value` as canonical JSON
+    fn write_canonical(buffer: &mut Vec<u8>, value: &serde_json::Value) -> CircuitResult<()> {
+        use serde_json::Value;
+        
+        match value {
+            Value::Array(items) => {
+                buffer.push(b'[');
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        buffer.push(b',');
+                    }
+                    Self::write_canonical(buffer, item)?;
+                }
+                buffer.push(b']');
+            }
+            Value::Object(fields) => {
+                let mut keys: Vec<&String> = fields.keys().collect();
+                keys.sort_unstable();
+                buffer.push(b'{');
+                for (i, key) in keys.into_iter().enumerate() {
+                    if i > 0 {
+                        buffer.push(b',');
+                    }
+                    Self::write_canonical(buffer, &Value::String(key.clone()))?;
+                    buffer.push(b':');
+                    Self::write_canonical(buffer, &fields[key])?;
+                }
+                buffer.push(b'}');
+            }
+            scalar => buffer.extend(
+                serde_json::to_vec(scalar)
+                    .map_err(|e| CircuitError::SerializationError(format!("Failed to serialize proof: {}", e)))?
+            ),
+        }
+        Ok(())
     }
     
     /// Serialize a BiometricEmbedding to JSON bytes
@@ -45,12 +107,12 @@ impl SerializationUtils {
     /// Convert proof to base64 for web compatibility
     pub fn proof_to_base64(proof: &BiometricProof) -> CircuitResult<String> {
         let bytes = Self::serialize_proof(proof)?;
-        Ok(base64::encode(bytes))
+        Ok(BASE64.encode(bytes))
     }
     
     /// Convert proof from base64
     pub fn proof_from_base64(base64_string: &str) -> CircuitResult<BiometricProof> {
-        let bytes = base64::decode(base64_string)
+        let bytes = BASE64.decode(base64_string)
             .map_err(|e| CircuitError::SerializationError(format!("Invalid base64 string: {}", e)))?;
         Self::deserialize_proof(&bytes)
     }
@@ -72,25 +134,80 @@ impl BinarySerializer {
         // Write commitments count and data
         buffer.extend_from_slice(&(proof.commitments.len() as u32).to_le_bytes());
         for commitment in &proof.commitments {
-            buffer.extend_from_slice(&(commitment.len() as u32).to_le_bytes());
-            buffer.extend_from_slice(commitment);
+            Self::write_bytes(&mut buffer, commitment.as_bytes());
         }
         
         // Write public inputs
-        buffer.extend_from_slice(&proof.public_inputs.threshold.to_le_bytes());
-        buffer.extend_from_slice(&(proof.public_inputs.embedding_size as u32).to_le_bytes());
-        buffer.extend_from_slice(&(proof.public_inputs.commitment_hash.len() as u32).to_le_bytes());
-        buffer.extend_from_slice(&proof.public_inputs.commitment_hash);
+        buffer.extend_from_slice(&proof.public_inputs.to_bytes());
         
         // Write metadata
-        buffer.extend_from_slice(&proof.metadata.timestamp.to_le_bytes());
-        let version_bytes = proof.metadata.version.as_bytes();
-        buffer.extend_from_slice(&(version_bytes.len() as u32).to_le_bytes());
-        buffer.extend_from_slice(version_bytes);
+        let metadata = &proof.metadata;
+        buffer.extend_from_slice(&metadata.timestamp.to_le_bytes());
+        Self::write_bytes(&mut buffer, metadata.version.as_bytes());
+        buffer.extend_from_slice(&(metadata.circuit_params.range_bits as u32).to_le_bytes());
+        buffer.extend_from_slice(&(metadata.circuit_params.aggregation_size as u32).to_le_bytes());
+        Self::write_bytes(&mut buffer, metadata.circuit_params.transcript_label.as_bytes());
+        Self::write_bytes(&mut buffer, &metadata.nonce);
+        for field in [metadata.chunk_size, metadata.chunk_count] {
+            match field {
+                Some(value) => {
+                    buffer.push(1);
+                    buffer.extend_from_slice(&(value as u32).to_le_bytes());
+                }
+                None => buffer.push(0),
+            }
+        }
         
         Ok(buffer)
     }
     
+    /// Append a u32 length prefix and the bytes
+    fn write_bytes(buffer: &mut Vec<u8>, bytes: &[u8]) {
+        buffer.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+        buffer.extend_from_slice(bytes);
+    }
+    
+    /// Take `len` bytes at `offset`, advancing it
+    fn read_slice<'a>(data: &'a [u8], offset: &mut usize, len: usize, what: &str) -> CircuitResult<&'a [u8]> {
+        if data.len() - *offset < len {
+            return Err(CircuitError::SerializationError(format!("Insufficient data for {}", what)));
+        }
+        let slice = &data[*offset..*offset + len];
+        *offset += len;
+        Ok(slice)
+    }
+    
+    fn read_u32(data: &[u8], offset: &mut usize, what: &str) -> CircuitResult<u32> {
+        let bytes = Self::read_slice(data, offset, 4, what)?;
+        Ok(u32::from_le_bytes(bytes.try_into().unwrap()))
+    }
+    
+    fn read_u64(data: &[u8], offset: &mut usize, what: &str) -> CircuitResult<u64> {
+        let bytes = Self::read_slice(data, offset, 8, what)?;
+        Ok(u64::from_le_bytes(bytes.try_into().unwrap()))
+    }
+    
+    /// Read a u32 length prefix and that many bytes
+    fn read_bytes<'a>(data: &'a [u8], offset: &mut usize, what: &str) -> CircuitResult<&'a [u8]> {
+        let len = Self::read_u32(data, offset, what)? as usize;
+        Self::read_slice(data, offset, len, what)
+    }
+    
+    fn read_string(data: &[u8], offset: &mut usize, what: &str) -> CircuitResult<String> {
+        let bytes = Self::read_bytes(data, offset, what)?;
+        String::from_utf8(bytes.to_vec())
+            .map_err(|_| CircuitError::SerializationError(format!("Invalid UTF-8 in {}", what)))
+    }
+    
+    /// Read a presence flag followed by a u32 value
+    fn read_optional_u32(data: &[u8], offset: &mut usize, what: &str) -> CircuitResult<Option<usize>> {
+        match Self::read_slice(data, offset, 1, what)?[0] {
+            0 => Ok(None),
+            1 => Ok(Some(Self::read_u32(data, offset, what)? as usize)),
+            flag => Err(CircuitError::SerializationError(format!("Invalid {} flag: {}", what, flag))),
+        }
+    }
+    
     /// Deserialize proof from binary format
     pub fn deserialize_proof_binary(data: &[u8]) -> CircuitResult<BiometricProof> {
         let mut offset = 0;
@@ -115,7 +232,12 @@ impl BinarySerializer {
         let commitments_count = u32::from_le_bytes([data[offset], data[offset+1], data[offset+2], data[offset+3]]) as usize;
         offset += 4;
         
-        let mut commitments = Vec::new();
+        // A zero count is valid and leaves the offset at the public inputs;
+        // otherwise each entry needs at least its 4-byte length prefix
+        if (data.len() - offset) / 4 < commitments_count {
+            return Err(CircuitError::SerializationError("Insufficient data for commitments".to_string()));
+        }
+        let mut commitments = Vec::with_capacity(commitments_count);
         for _ in 0..commitments_count {
             if data.len() < offset + 4 {
                 return Err(CircuitError::SerializationError("Insufficient data for commitment length".to_string()));
@@ -126,32 +248,105 @@ impl BinarySerializer {
             if data.len() < offset + commitment_len {
                 return Err(CircuitError::SerializationError("Insufficient data for commitment".to_string()));
             }
-            commitments.push(data[offset..offset + commitment_len].to_vec());
+            commitments.push(SerializableCommitment::from_bytes(&data[offset..offset + commitment_len])?);
             offset += commitment_len;
         }
         
-        // Read remaining fields...
-        // (For brevity, using simplified deserialization)
-        Ok(BiometricProof::new(
+        // Read public inputs
+        let (public_inputs, consumed) = ProofPublicInputs::read_from(&data[offset..])?;
+        offset += consumed;
+        
+        // Read metadata
+        let timestamp = Self::read_u64(data, &mut offset, "timestamp")?;
+        let version = Self::read_string(data, &mut offset, "version")?;
+        let circuit_params = CircuitParams {
+            range_bits: Self::read_u32(data, &mut offset, "range bits")? as usize,
+            aggregation_size: Self::read_u32(data, &mut offset, "aggregation size")? as usize,
+            transcript_label: Self::read_string(data, &mut offset, "transcript label")?,
+        };
+        let nonce = Self::read_bytes(data, &mut offset, "nonce")?.to_vec();
+        let chunk_size = Self::read_optional_u32(data, &mut offset, "chunk size")?;
+        let chunk_count = Self::read_optional_u32(data, &mut offset, "chunk count")?;
+        
+        if offset != data.len() {
+            return Err(CircuitError::SerializationError(
+                format!("Unexpected {} trailing bytes after proof", data.len() - offset)
+            ));
+        }
+        
+        Ok(BiometricProof {
             proof,
             commitments,
-            1000, // placeholder threshold
-            128,  // placeholder embedding size
-            vec![0; 32], // placeholder hash
-        ))
+            public_inputs,
+            metadata: ProofMetadata {
+                timestamp,
+                version,
+                circuit_params,
+                nonce,
+                chunk_size,
+                chunk_count,
+            },
+        })
     }
 }
 
-#[cfg(test)]
+/// Borsh serialization, a compact schema-stable format for the FFI and JNI layers
+pub struct BorshSerializer;
+
+impl BorshSerializer {
+    /// Serialize a proof to borsh bytes
+    pub fn to_borsh(proof: &BiometricProof) -> CircuitResult<Vec<u8>> {
+        borsh::to_vec(proof)
+            .map_err(|e| CircuitError::SerializationError(format!("Failed to serialize proof: {}", e)))
+    }
+    
+    /// Deserialize a proof from borsh bytes, rejecting trailing data
+    ///
+    /// A commitment that is not a valid point fails with `InvalidCommitment`.
+    pub fn from_borsh(data: &[u8]) -> CircuitResult<BiometricProof> {
+        borsh::from_slice(data).map_err(|e| match Self::invalid_commitment(&e) {
+            Some(reason) => CircuitError::InvalidCommitment(reason),
+            None => CircuitError::SerializationError(format!("Failed to deserialize proof: {}", e)),
+        })
+    }
+    
+    /// Reason of the `InvalidCommitment` a borsh error wraps, if any
+    #[cfg(feature = "std")]
+    fn invalid_commitment(error: &borsh::io::Error) -> Option<String> {
+        match error.get_ref().and_then(|inner| inner.downcast_ref::<CircuitError>()) {
+            Some(CircuitError::InvalidCommitment(reason)) => Some(reason.clone()),
+            _ => None,
+        }
+    }
+    
+    /// Reason of the `InvalidCommitment` a borsh error wraps, if any
+    #[cfg(not(feature = "std"))]
+    fn invalid_commitment(error: &borsh::io::Error) -> Option<String> {
+        error.get_ref()?.strip_prefix("Invalid commitment: ").map(ToString::to_string)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use super::*;
+    use alloc::string::{String, ToString};
+    use alloc::vec::Vec;
+    use alloc::{format, vec};
+    use crate::backend::bulletproofs::PedersenGens;
+    use crate::backend::curve25519::scalar::Scalar;
     use crate::types::BiometricProof;
+    use crate::utils::scalar_utils::ScalarUtils;
+    
+    fn commitments(count: u64) -> Vec<SerializableCommitment> {
+        let gens = PedersenGens::default();
+        (0..count).map(|i| gens.commit(Scalar::from(i), ScalarUtils::one()).into()).collect()
+    }
     
     #[test]
     fn test_json_serialization() {
         let proof = BiometricProof::new(
             vec![1, 2, 3, 4, 5],
-            vec![vec![1, 2], vec![3, 4]],
+            commitments(2),
             1000,
             128,
             vec![0; 32],
@@ -169,7 +364,7 @@ mod tests {
     fn test_hex_serialization() {
         let proof = BiometricProof::new(
             vec![1, 2, 3, 4, 5],
-            vec![vec![1, 2], vec![3, 4]],
+            commitments(2),
             1000,
             128,
             vec![0; 32],
@@ -186,7 +381,7 @@ mod tests {
     fn test_binary_serialization() {
         let proof = BiometricProof::new(
             vec![1, 2, 3, 4, 5],
-            vec![vec![1, 2], vec![3, 4]],
+            commitments(2),
             1000,
             128,
             vec![0; 32],
@@ -199,4 +394,203 @@ mod tests {
         assert_eq!(proof.proof, deserialized.proof);
         assert_eq!(proof.commitments, deserialized.commitments);
     }
+    
+    #[test]
+    fn test_binary_round_trip_preserves_every_field() {
+        let mut proof = BiometricProof::new(
+            vec![1, 2, 3, 4, 5],
+            commitments(2),
+            4242,
+            64,
+            vec![7; 32],
+        )
+        .with_device_binding(vec![9; 32])
+        .with_generator_epoch(3)
+        .with_scale_factor(10000)
+        .with_liveness_score(950)
+        .with_nonce(vec![0xaa, 0xbb]);
+        proof.metadata.timestamp = 1_700_000_000;
+        proof.metadata.version = "2.1.0-rc.1".to_string();
+        proof.metadata.circuit_params.range_bits = 32;
+        proof.metadata.circuit_params.transcript_label = "custom".to_string();
+        proof.metadata.chunk_size = Some(16);
+        proof.metadata.chunk_count = Some(4);
+        
+        let binary = BinarySerializer::serialize_proof_binary(&proof).unwrap();
+        let decoded = BinarySerializer::deserialize_proof_binary(&binary).unwrap();
+        
+        assert_eq!(decoded.proof, proof.proof);
+        assert_eq!(decoded.commitments, proof.commitments);
+        
+        let (inputs, expected) = (&decoded.public_inputs, &proof.public_inputs);
+        assert_eq!(inputs.threshold, 4242);
+        assert_eq!(inputs.embedding_size, 64);
+        assert_eq!(inputs.commitment_hash, expected.commitment_hash);
+        assert_eq!(inputs.device_binding, expected.device_binding);
+        assert_eq!(inputs.generator_epoch, Some(3));
+        assert_eq!(inputs.scale_factor, Some(10000));
+        assert_eq!(inputs.liveness_score, Some(950));
+        
+        let (metadata, expected) = (&decoded.metadata, &proof.metadata);
+        assert_eq!(metadata.timestamp, 1_700_000_000);
+        assert_eq!(metadata.version, "2.1.0-rc.1");
+        assert_eq!(metadata.circuit_params.range_bits, 32);
+        assert_eq!(metadata.circuit_params.aggregation_size, expected.circuit_params.aggregation_size);
+        assert_eq!(metadata.circuit_params.transcript_label, "custom");
+        assert_eq!(metadata.nonce, vec![0xaa, 0xbb]);
+        assert_eq!(metadata.chunk_size, Some(16));
+        assert_eq!(metadata.chunk_count, Some(4));
+        
+        // Same digest means nothing bound into the proof was lost
+        assert_eq!(decoded.canonical_digest(), proof.canonical_digest());
+    }
+    
+    #[test]
+    fn test_binary_rejects_truncated_metadata() {
+        let proof = BiometricProof::new(vec![1, 2, 3], commitments(1), 1000, 128, vec![0; 32]);
+        let binary = BinarySerializer::serialize_proof_binary(&proof).unwrap();
+        
+        assert!(BinarySerializer::deserialize_proof_binary(&binary[..binary.len() - 1]).is_err());
+        
+        let mut padded = binary.clone();
+        padded.push(0);
+        assert!(BinarySerializer::deserialize_proof_binary(&padded).is_err());
+    }
+    
+    #[test]
+    fn test_zero_commitment_round_trip() {
+        use crate::utils::{CommitmentMode, ValidationUtils};
+        
+        let proof = BiometricProof::new(vec![1, 2, 3, 4, 5], vec![], 1000, 128, vec![0; 32]);
+        
+        let binary = BinarySerializer::serialize_proof_binary(&proof).unwrap();
+        let from_binary = BinarySerializer::deserialize_proof_binary(&binary).unwrap();
+        assert!(from_binary.commitments.is_empty());
+        assert_eq!(from_binary.proof, proof.proof);
+        
+        let json = SerializationUtils::serialize_proof(&proof).unwrap();
+        let from_json = SerializationUtils::deserialize_proof(&json).unwrap();
+        assert!(from_json.commitments.is_empty());
+        
+        for decoded in [&from_binary, &from_json] {
+            assert!(ValidationUtils::validate_proof_with_mode(decoded, CommitmentMode::PublicReference).is_ok());
+            assert!(ValidationUtils::validate_proof(decoded).is_err());
+        }
+    }
+    
+    #[test]
+    fn test_binary_rejects_inflated_commitment_count() {
+        let proof = BiometricProof::new(vec![1, 2, 3], vec![], 1000, 128, vec![0; 32]);
+        let mut binary = BinarySerializer::serialize_proof_binary(&proof).unwrap();
+        
+        // Overwrite the commitment count that follows the proof bytes
+        binary[7..11].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(BinarySerializer::deserialize_proof_binary(&binary).is_err());
+    }
+    
+    #[test]
+    fn test_borsh_round_trip_is_smaller_than_json() {
+        let mut proof = BiometricProof::new(
+            vec![0x5a; 672],
+            commitments(128),
+            1000,
+            128,
+            vec![7; 32],
+        )
+        .with_device_binding(vec![9; 32])
+        .with_nonce(vec![0xaa, 0xbb]);
+        proof.metadata.chunk_size = Some(16);
+        
+        let borsh = BorshSerializer::to_borsh(&proof).unwrap();
+        let decoded = BorshSerializer::from_borsh(&borsh).unwrap();
+        
+        assert_eq!(decoded.proof, proof.proof);
+        assert_eq!(decoded.commitments, proof.commitments);
+        assert_eq!(decoded.public_inputs.device_binding, proof.public_inputs.device_binding);
+        assert_eq!(decoded.metadata.nonce, proof.metadata.nonce);
+        assert_eq!(decoded.metadata.chunk_size, Some(16));
+        assert_eq!(decoded.canonical_digest(), proof.canonical_digest());
+        
+        // JSON writes every byte as a comma-separated decimal number
+        let json = SerializationUtils::serialize_proof(&proof).unwrap();
+        assert!(borsh.len() * 2 < json.len(), "borsh {} bytes vs JSON {} bytes", borsh.len(), json.len());
+        
+        let mut padded = borsh.clone();
+        padded.push(0);
+        assert!(BorshSerializer::from_borsh(&padded).is_err());
+        assert!(BorshSerializer::from_borsh(&borsh[..borsh.len() - 1]).is_err());
+    }
+    
+    #[test]
+    fn test_canonical_serialization_is_stable() {
+        let proof = BiometricProof::new(vec![1, 2, 3, 4, 5], commitments(2), 1000, 128, vec![7; 32])
+            .with_nonce(vec![0xaa, 0xbb]);
+        let canonical = SerializationUtils::serialize_proof_canonical(&proof).unwrap();
+        
+        // The same proof, parsed from JSON with every object's keys reversed
+        fn reversed(value: &serde_json::Value) -> String {
+            match value {
+                serde_json::Value::Object(fields) => {
+                    let mut entries: Vec<_> = fields.iter().collect();
+                    entries.sort_by(|a, b| b.0.cmp(a.0));
+                    let body: Vec<String> = entries
+                        .into_iter()
+                        .map(|(key, value)| format!("{:?}: {}", key, reversed(value)))
+                        .collect();
+                    format!("{{ {} }}", body.join(", "))
+                }
+                serde_json::Value::Array(items) => {
+                    format!("[{}]", items.iter().map(reversed).collect::<Vec<_>>().join(", "))
+                }
+                other => other.to_string(),
+            }
+        }
+        let shuffled = reversed(&serde_json::to_value(&proof).unwrap());
+        let reparsed = SerializationUtils::deserialize_proof(shuffled.as_bytes()).unwrap();
+        assert_eq!(SerializationUtils::serialize_proof_canonical(&reparsed).unwrap(), canonical);
+        
+        // As is the proof after a binary round trip
+        let binary = BinarySerializer::serialize_proof_binary(&proof).unwrap();
+        let decoded = BinarySerializer::deserialize_proof_binary(&binary).unwrap();
+        assert_eq!(SerializationUtils::serialize_proof_canonical(&decoded).unwrap(), canonical);
+        
+        // Keys come out sorted, with no whitespace, and still parse as the proof
+        let text = String::from_utf8(canonical.clone()).unwrap();
+        assert!(text.starts_with("{\"commitments\":[["));
+        assert!(!text.contains(' '));
+        let positions: Vec<usize> = ["\"commitments\"", "\"metadata\"", "\"proof\"", "\"public_inputs\""]
+            .iter()
+            .map(|key| text.find(key).unwrap())
+            .collect();
+        assert!(positions.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(SerializationUtils::deserialize_proof(&canonical).unwrap().canonical_digest(), proof.canonical_digest());
+    }
+    
+    #[test]
+    fn test_rejects_invalid_commitment_on_decode() {
+        let proof = BiometricProof::new(vec![1, 2, 3], commitments(2), 1000, 128, vec![0; 32]);
+        // 2^255 - 1 is not a canonical encoding, so it never decompresses
+        let non_canonical = [0xffu8; 32];
+        
+        let mut json: serde_json::Value = serde_json::from_slice(&SerializationUtils::serialize_proof(&proof).unwrap()).unwrap();
+        json["commitments"][1] = serde_json::json!(non_canonical.to_vec());
+        assert!(matches!(
+            SerializationUtils::deserialize_proof(&serde_json::to_vec(&json).unwrap()),
+            Err(CircuitError::InvalidCommitment(_))
+        ));
+        
+        // Both binary layouts put the first commitment after the proof
+        // bytes, the commitment count and its own length prefix
+        let first = 4 + proof.proof.len() + 4 + 4;
+        let mut binary = BinarySerializer::serialize_proof_binary(&proof).unwrap();
+        binary[first..first + 32].copy_from_slice(&non_canonical);
+        assert!(matches!(
+            BinarySerializer::deserialize_proof_binary(&binary),
+            Err(CircuitError::InvalidCommitment(_))
+        ));
+        
+        let mut borsh = BorshSerializer::to_borsh(&proof).unwrap();
+        borsh[first..first + 32].copy_from_slice(&non_canonical);
+        assert!(matches!(BorshSerializer::from_borsh(&borsh), Err(CircuitError::InvalidCommitment(_))));
+    }
 }
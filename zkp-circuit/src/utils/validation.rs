@@ -1,5 +1,34 @@
-use crate::types::{CircuitError, CircuitResult, BiometricEmbedding, BiometricProof};
-use crate::config::{MAX_EMBEDDING_SIZE, MIN_THRESHOLD, MAX_THRESHOLD, MAX_PROOF_SIZE};
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use alloc::format;
+use crate::types::{CircuitError, CircuitResult, BiometricEmbedding, BiometricProof, ProofMetadata};
+use crate::config::{MAX_EMBEDDING_SIZE, MAX_EMBEDDING_VALUE, MIN_THRESHOLD, MAX_THRESHOLD, MAX_PROOF_SIZE, OUTLIER_Z_SCORE, TRANSCRIPT_LABEL};
+
+/// Whether a proof is expected to carry embedding commitments
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CommitmentMode {
+    /// Probe and reference commitments must be present
+    #[default]
+    Committed,
+    /// The reference is public, so a proof may legitimately carry no commitments
+    PublicReference,
+}
+
+/// How `ValidationUtils::sanitize_embedding_with` finds outliers
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SanitizeStrategy {
+    /// Clamp values more than this many standard deviations from the mean
+    ZScore(f64),
+    /// Clamp values outside `[Q1 - 1.5·IQR, Q3 + 1.5·IQR]`; robust to
+    /// skewed distributions whose mean and deviation the outliers dominate
+    Iqr,
+}
+
+impl Default for SanitizeStrategy {
+    fn default() -> Self {
+        SanitizeStrategy::ZScore(OUTLIER_Z_SCORE)
+    }
+}
 
 /// Input validation utilities for ZKP circuit
 pub struct ValidationUtils;
@@ -29,7 +58,7 @@ impl ValidationUtils {
         
         // Check for reasonable value ranges (prevent overflow in calculations)
         for (i, &value) in embedding.data.iter().enumerate() {
-            if value.abs() > 1_000_000 {
+            if value.abs() > MAX_EMBEDDING_VALUE {
                 return Err(CircuitError::InvalidEmbedding(
                     format!("Embedding value at index {} is too large: {}", i, value)
                 ));
@@ -80,8 +109,16 @@ impl ValidationUtils {
         Ok(())
     }
     
-    /// Validate proof structure and size
+    /// Validate proof structure and size, requiring commitments
     pub fn validate_proof(proof: &BiometricProof) -> CircuitResult<()> {
+        Self::validate_proof_with_mode(proof, CommitmentMode::Committed)
+    }
+    
+    /// Validate proof structure and size under the given commitment mode
+    ///
+    /// Any commitments that are present must be well formed in every mode;
+    /// only `CommitmentMode::Committed` rejects a proof without any.
+    pub fn validate_proof_with_mode(proof: &BiometricProof, mode: CommitmentMode) -> CircuitResult<()> {
         // Check proof size
         if proof.size() > MAX_PROOF_SIZE {
             return Err(CircuitError::ProofVerificationFailed(
@@ -114,26 +151,12 @@ impl ValidationUtils {
         }
         
         // Validate commitments
-        if proof.commitments.is_empty() {
+        if proof.commitments.is_empty() && mode == CommitmentMode::Committed {
             return Err(CircuitError::InvalidCommitment(
                 "Proof must contain commitments".to_string()
             ));
         }
         
-        for (i, commitment) in proof.commitments.iter().enumerate() {
-            if commitment.is_empty() {
-                return Err(CircuitError::InvalidCommitment(
-                    format!("Commitment {} is empty", i)
-                ));
-            }
-            
-            if commitment.len() != 32 {
-                return Err(CircuitError::InvalidCommitment(
-                    format!("Invalid commitment {} length: {}", i, commitment.len())
-                ));
-            }
-        }
-        
         // Validate metadata
         if proof.metadata.timestamp == 0 {
             return Err(CircuitError::InvalidParameter(
@@ -147,6 +170,50 @@ impl ValidationUtils {
             ));
         }
         
+        ProofMetadata::canonical_version(&proof.metadata.version)?;
+        
+        Self::validate_circuit_params(proof)?;
+        
+        Ok(())
+    }
+    
+    /// Cross-check the recorded circuit parameters against the public inputs
+    ///
+    /// The circuit bit-decomposes `threshold - distance`, so `range_bits` must
+    /// cover the declared threshold and fit the verifier's 64-bit bound; the
+    /// transcript label must be the verifier's `TRANSCRIPT_LABEL`.
+    pub fn validate_circuit_params(proof: &BiometricProof) -> CircuitResult<()> {
+        let params = &proof.metadata.circuit_params;
+        let threshold = proof.public_inputs.threshold;
+        let required_bits = (u64::BITS - threshold.leading_zeros()) as usize;
+        
+        if params.range_bits > 64 {
+            return Err(CircuitError::ParameterMismatch(
+                format!("Range bits {} exceed the 64-bit verifier bound", params.range_bits)
+            ));
+        }
+        
+        if params.range_bits < required_bits {
+            return Err(CircuitError::ParameterMismatch(
+                format!(
+                    "Range bits {} cannot cover threshold {} (needs {})",
+                    params.range_bits, threshold, required_bits
+                )
+            ));
+        }
+        
+        if params.aggregation_size == 0 {
+            return Err(CircuitError::ParameterMismatch(
+                "Aggregation size cannot be zero".to_string()
+            ));
+        }
+        
+        if params.transcript_label.as_bytes() != TRANSCRIPT_LABEL {
+            return Err(CircuitError::ParameterMismatch(
+                format!("Transcript label {:?} does not match the verifier's", params.transcript_label)
+            ));
+        }
+        
         Ok(())
     }
     
@@ -164,7 +231,19 @@ impl ValidationUtils {
             ));
         }
         
-        Ok(())
+        Self::validate_same_scale(embedding1, embedding2)
+    }
+    
+    /// Reject embeddings recorded as quantized with different scale factors
+    ///
+    /// An embedding without a recorded scale is accepted against any other.
+    pub fn validate_same_scale(embedding1: &BiometricEmbedding, embedding2: &BiometricEmbedding) -> CircuitResult<()> {
+        match (embedding1.scale_factor, embedding2.scale_factor) {
+            (Some(scale1), Some(scale2)) if scale1 != scale2 => Err(CircuitError::InvalidEmbedding(
+                format!("Embeddings quantized with different scales: {} vs {}", scale1, scale2)
+            )),
+            _ => Ok(()),
+        }
     }
     
     /// Validate float array before conversion to embedding
@@ -197,7 +276,7 @@ impl ValidationUtils {
             
             // Check if scaled value will be reasonable
             let scaled = (value * scale_factor as f64).abs();
-            if scaled > 1_000_000.0 {
+            if scaled > MAX_EMBEDDING_VALUE as f64 {
                 return Err(CircuitError::InvalidEmbedding(
                     format!("Scaled float value at index {} is too large: {}", i, scaled)
                 ));
@@ -207,14 +286,21 @@ impl ValidationUtils {
         Ok(())
     }
     
+    /// Common embedding sizes in machine learning
+    const COMMON_SIZES: &'static [usize] = &[
+        16, 32, 64, 128, 256, 384, 512, 768, 1024, 1536, 2048
+    ];
+    
+    /// Common embedding sizes that `validate_embedding_size` accepts
+    ///
+    /// Other powers of two up to `MAX_EMBEDDING_SIZE` are accepted as well.
+    pub fn recommended_embedding_sizes() -> Vec<usize> {
+        Self::COMMON_SIZES.iter().copied().filter(|&size| size <= MAX_EMBEDDING_SIZE).collect()
+    }
+    
     /// Check if the embedding size is reasonable (common sizes used in ML)
     fn is_reasonable_embedding_size(size: usize) -> bool {
-        // Common embedding sizes in machine learning
-        const COMMON_SIZES: &[usize] = &[
-            16, 32, 64, 128, 256, 384, 512, 768, 1024, 1536, 2048
-        ];
-        
-        COMMON_SIZES.contains(&size) || Self::is_power_of_two(size)
+        Self::COMMON_SIZES.contains(&size) || Self::is_power_of_two(size)
     }
     
     /// Check if a number is a power of two
@@ -222,47 +308,121 @@ impl ValidationUtils {
         n > 0 && (n & (n - 1)) == 0
     }
     
-    /// Sanitize embedding data (remove outliers, clamp values)
+    /// Sanitize embedding data in place (remove outliers, clamp values)
+    ///
+    /// Outliers are judged against `OUTLIER_Z_SCORE`; see
+    /// `sanitize_embedding_with` for other strategies.
     pub fn sanitize_embedding(embedding: &mut BiometricEmbedding) -> CircuitResult<()> {
-        // Clamp values to reasonable range
+        *embedding = Self::sanitize_embedding_cloned(embedding)?;
+        Ok(())
+    }
+    
+    /// Sanitized copy of `embedding`, leaving the original untouched
+    pub fn sanitize_embedding_cloned(embedding: &BiometricEmbedding) -> CircuitResult<BiometricEmbedding> {
+        Self::sanitize_embedding_with(embedding, SanitizeStrategy::default())
+    }
+    
+    /// Sanitized copy of `embedding`, finding outliers with `strategy`
+    pub fn sanitize_embedding_with(
+        embedding: &BiometricEmbedding,
+        strategy: SanitizeStrategy,
+    ) -> CircuitResult<BiometricEmbedding> {
+        match strategy {
+            SanitizeStrategy::ZScore(max_z_score) => Self::sanitize_embedding_with_z_score(embedding, max_z_score),
+            SanitizeStrategy::Iqr => Self::sanitize_embedding_iqr(embedding),
+        }
+    }
+    
+    /// Sanitized copy of `embedding`, clamping values outside the Tukey
+    /// fences `[Q1 - 1.5·IQR, Q3 + 1.5·IQR]`
+    ///
+    /// Values are first clamped to ±100 000 as for the z-score strategy.
+    /// Quartiles interpolate linearly between the sorted values.
+    pub fn sanitize_embedding_iqr(embedding: &BiometricEmbedding) -> CircuitResult<BiometricEmbedding> {
+        let mut sanitized = embedding.clone();
+        Self::clamp_magnitudes(&mut sanitized.data);
+        
+        let mut sorted = sanitized.data.clone();
+        sorted.sort_unstable();
+        let quartile = |p: f64| {
+            let position = (sorted.len() - 1) as f64 * p;
+            let (low, high) = (libm::floor(position) as usize, libm::ceil(position) as usize);
+            sorted[low] as f64 + (position - low as f64) * (sorted[high] - sorted[low]) as f64
+        };
+        let (q1, q3) = (quartile(0.25), quartile(0.75));
+        let iqr = q3 - q1;
+        let (lower, upper) = (libm::ceil(q1 - 1.5 * iqr) as i64, libm::floor(q3 + 1.5 * iqr) as i64);
+        
+        for value in &mut sanitized.data {
+            *value = (*value).clamp(lower, upper);
+        }
+        
+        Ok(sanitized)
+    }
+    
+    /// Clamp values to ±100 000 ahead of outlier removal
+    fn clamp_magnitudes(data: &mut [i64]) {
         const MAX_ABS_VALUE: i64 = 100_000;
         
-        for value in &mut embedding.data {
+        for value in data {
             *value = (*value).clamp(-MAX_ABS_VALUE, MAX_ABS_VALUE);
         }
+    }
+    
+    /// Sanitized copy of `embedding`, clamping values beyond `max_z_score`
+    /// standard deviations from the mean
+    ///
+    /// Values are first clamped to ±100 000; outliers are then pulled in to
+    /// `mean ± max_z_score · std_dev`. A lower `max_z_score` is stricter.
+    pub fn sanitize_embedding_with_z_score(
+        embedding: &BiometricEmbedding,
+        max_z_score: f64,
+    ) -> CircuitResult<BiometricEmbedding> {
+        if !(max_z_score.is_finite() && max_z_score > 0.0) {
+            return Err(CircuitError::InvalidParameter(
+                format!("Z-score threshold {} must be positive", max_z_score)
+            ));
+        }
+        
+        let mut sanitized = embedding.clone();
+        Self::clamp_magnitudes(&mut sanitized.data);
         
         // Remove extreme outliers using z-score
-        let mean = embedding.data.iter().sum::<i64>() as f64 / embedding.data.len() as f64;
-        let variance = embedding.data.iter()
+        let mean = sanitized.data.iter().sum::<i64>() as f64 / sanitized.data.len() as f64;
+        let variance = sanitized.data.iter()
             .map(|&x| {
                 let diff = x as f64 - mean;
                 diff * diff
             })
-            .sum::<f64>() / embedding.data.len() as f64;
+            .sum::<f64>() / sanitized.data.len() as f64;
         
-        let std_dev = variance.sqrt();
+        let std_dev = libm::sqrt(variance);
         
         if std_dev > 0.0 {
-            for value in &mut embedding.data {
+            for value in &mut sanitized.data {
                 let z_score = (*value as f64 - mean) / std_dev;
-                if z_score.abs() > 3.0 {
+                if z_score.abs() > max_z_score {
                     // Replace outliers with clamped values
                     *value = if z_score > 0.0 {
-                        (mean + 3.0 * std_dev) as i64
+                        (mean + max_z_score * std_dev) as i64
                     } else {
-                        (mean - 3.0 * std_dev) as i64
+                        (mean - max_z_score * std_dev) as i64
                     };
                 }
             }
         }
         
-        Ok(())
+        Ok(sanitized)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloc::vec::Vec;
+    use alloc::vec;
+    #[cfg(feature = "std")]
+    use crate::types::SerializableCommitment;
     
     #[test]
     fn test_embedding_validation() {
@@ -288,6 +448,12 @@ mod tests {
         assert!(ValidationUtils::validate_embedding_size(64).is_ok());  // Power of 2
         assert!(ValidationUtils::validate_embedding_size(0).is_err());  // Zero
         assert!(ValidationUtils::validate_embedding_size(1000).is_err()); // Too large
+        
+        let recommended = ValidationUtils::recommended_embedding_sizes();
+        assert!(recommended.contains(&128) && recommended.contains(&384));
+        for size in recommended {
+            assert!(ValidationUtils::validate_embedding_size(size).is_ok());
+        }
     }
     
     #[test]
@@ -315,6 +481,26 @@ mod tests {
         assert!(ValidationUtils::validate_embedding_compatibility(&embedding1, &embedding3).is_err());
     }
     
+    #[test]
+    fn test_embedding_compatibility_requires_same_scale() {
+        let floats = vec![0.12, -0.4, 0.33];
+        let fine = BiometricEmbedding::from_floats(floats.clone(), 1000).unwrap();
+        let coarse = BiometricEmbedding::from_floats(floats.clone(), 100).unwrap();
+        assert_eq!(fine.scale_factor, Some(1000));
+        
+        assert!(matches!(
+            ValidationUtils::validate_embedding_compatibility(&fine, &coarse),
+            Err(CircuitError::InvalidEmbedding(_))
+        ));
+        
+        let matched = BiometricEmbedding::from_floats(vec![0.13, -0.4, 0.32], 1000).unwrap();
+        assert!(ValidationUtils::validate_embedding_compatibility(&fine, &matched).is_ok());
+        
+        // An unrecorded scale is not a mismatch
+        let raw = BiometricEmbedding::new(vec![120, -400, 330]).unwrap();
+        assert!(ValidationUtils::validate_embedding_compatibility(&raw, &coarse).is_ok());
+    }
+    
     #[test]
     fn test_sanitize_embedding() {
         let mut embedding = BiometricEmbedding::new(vec![1, 2, 200_000, 4, 5]).unwrap();
@@ -323,4 +509,93 @@ mod tests {
         // The outlier should be clamped
         assert!(embedding.data.iter().all(|&x| x.abs() <= 100_000));
     }
+    
+    #[test]
+    fn test_sanitize_embedding_cloned_and_z_score() {
+        // Mean 19, standard deviation 27, so the last value has z-score 3
+        let raw = BiometricEmbedding::new(vec![10, 10, 10, 10, 10, 10, 10, 10, 10, 100]).unwrap();
+        
+        let default = ValidationUtils::sanitize_embedding_cloned(&raw).unwrap();
+        assert_eq!(default.data, raw.data);
+        
+        let strict = ValidationUtils::sanitize_embedding_with_z_score(&raw, 2.0).unwrap();
+        assert_eq!(strict.data[9], 19 + 2 * 27);
+        assert_eq!(strict.data[..9], raw.data[..9]);
+        
+        let lenient = ValidationUtils::sanitize_embedding_with_z_score(&raw, 3.5).unwrap();
+        assert_eq!(lenient.data, raw.data);
+        
+        // The input is left as it was
+        assert_eq!(raw.data[9], 100);
+        
+        let mut in_place = BiometricEmbedding::new(vec![1, 2, 200_000, 4, 5]).unwrap();
+        let copy = ValidationUtils::sanitize_embedding_cloned(&in_place).unwrap();
+        ValidationUtils::sanitize_embedding(&mut in_place).unwrap();
+        assert_eq!(in_place.data, copy.data);
+        
+        for bad in [0.0, -1.0, f64::NAN] {
+            assert!(ValidationUtils::sanitize_embedding_with_z_score(&raw, bad).is_err());
+        }
+    }
+    
+    #[test]
+    fn test_iqr_clamps_skewed_tail() {
+        // Two clusters at ±50 and a two-value tail at 5000: the tail inflates
+        // the deviation enough (z ≈ 2.6) to escape the z-score check
+        let raw = BiometricEmbedding::new([vec![-50; 7], vec![50; 7], vec![5000; 2]].concat()).unwrap();
+        
+        let z_score = ValidationUtils::sanitize_embedding_with(&raw, SanitizeStrategy::default()).unwrap();
+        assert_eq!(z_score.data, raw.data);
+        
+        // Q1 = -50 and Q3 = 50, so the fences are ±200
+        let iqr = ValidationUtils::sanitize_embedding_with(&raw, SanitizeStrategy::Iqr).unwrap();
+        assert_eq!(iqr.data, [vec![-50; 7], vec![50; 7], vec![200; 2]].concat());
+        assert_eq!(ValidationUtils::sanitize_embedding_iqr(&raw).unwrap().data, iqr.data);
+    }
+    
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_proof_version_validation() {
+        let mut proof = BiometricProof::new(vec![1, 2, 3], vec![SerializableCommitment::from_bytes(&[0; 32]).unwrap()], 1000, 128, vec![0; 32]);
+        
+        proof.metadata.version = "1.2.3".to_string();
+        assert!(ValidationUtils::validate_proof(&proof).is_ok());
+        
+        proof.metadata.version = "banana".to_string();
+        assert!(matches!(
+            ValidationUtils::validate_proof(&proof),
+            Err(CircuitError::InvalidParameter(_))
+        ));
+    }
+    
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_circuit_params_consistency() {
+        let proof = BiometricProof::new(vec![1, 2, 3], vec![SerializableCommitment::from_bytes(&[0; 32]).unwrap()], 1000, 128, vec![0; 32]);
+        assert!(ValidationUtils::validate_proof(&proof).is_ok());
+        
+        // 1000 needs 10 bits
+        let mut narrow = proof.clone();
+        narrow.metadata.circuit_params.range_bits = 9;
+        assert!(matches!(
+            ValidationUtils::validate_proof(&narrow),
+            Err(CircuitError::ParameterMismatch(_))
+        ));
+        narrow.metadata.circuit_params.range_bits = 10;
+        assert!(ValidationUtils::validate_proof(&narrow).is_ok());
+        
+        let mut wide = proof.clone();
+        wide.metadata.circuit_params.range_bits = 65;
+        assert!(matches!(
+            ValidationUtils::validate_proof(&wide),
+            Err(CircuitError::ParameterMismatch(_))
+        ));
+        
+        let mut relabeled = proof.clone();
+        relabeled.metadata.circuit_params.transcript_label = "OtherProtocol".to_string();
+        assert!(matches!(
+            ValidationUtils::validate_proof(&relabeled),
+            Err(CircuitError::ParameterMismatch(_))
+        ));
+    }
 }
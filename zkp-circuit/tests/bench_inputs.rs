@@ -0,0 +1,17 @@
+//! Smoke test for the verification benchmark inputs
+
+#[path = "../benches/support/mod.rs"]
+mod support;
+
+use support::{proof_inputs, EMBEDDING_SIZES};
+
+#[test]
+fn test_bench_inputs_verify() {
+    for size in EMBEDDING_SIZES {
+        let inputs = proof_inputs(size);
+        assert_eq!(inputs.current.len(), size);
+        assert_eq!(inputs.reference.len(), size);
+        assert_eq!(inputs.commitments.len(), size * 2);
+        assert!(inputs.circuit.verify_proof(&inputs.proof, &inputs.commitments).unwrap());
+    }
+}
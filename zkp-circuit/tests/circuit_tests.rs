@@ -1,6 +1,5 @@
 use zkp_circuit::circuit::BiometricCircuit;
-use zkp_circuit::crypto::CommitmentScheme;
-use curve25519_dalek_ng::scalar::Scalar;
+use zkp_circuit::Scalar;
 
 #[test]
 fn test_circuit_basic_functionality() {
@@ -9,8 +8,11 @@ fn test_circuit_basic_functionality() {
     assert_eq!(circuit.threshold, 1000);
 }
 
+#[cfg(feature = "std")]
 #[test]
 fn test_commitment_scheme() {
+    use zkp_circuit::crypto::CommitmentScheme;
+    
     let scheme = CommitmentScheme::new();
     let value = Scalar::from(42u64);
     
@@ -1,5 +1,5 @@
 use zkp_circuit::circuit::BiometricCircuit;
-use curve25519_dalek_ng::scalar::Scalar;
+use zkp_circuit::Scalar;
 
 #[test]
 fn test_circuit_creation() {
@@ -41,6 +41,54 @@ fn test_proof_verification() {
     let dummy_proof = vec![0u8; 32]; // Dummy proof bytes
     let dummy_commitments = vec![];
     
+    // A blob that is not a serialized proof is rejected, not accepted
     let result = circuit.verify_proof(&dummy_proof, &dummy_commitments);
-    assert!(result.is_ok());
+    assert!(result.is_err());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_end_to_end_prove_verify() {
+    use zkp_circuit::utils::SerializationUtils;
+    use zkp_circuit::BiometricEmbedding;
+    
+    let circuit = BiometricCircuit::new(4, 1000);
+    let current = BiometricEmbedding::new(vec![1, 2, 3, 4]).unwrap();
+    let reference = BiometricEmbedding::new(vec![1, 2, 3, 5]).unwrap();
+    
+    let proof = circuit.prove(&current, &reference).unwrap();
+    let received = SerializationUtils::deserialize_proof(
+        &SerializationUtils::serialize_proof(&proof).unwrap()
+    ).unwrap();
+    
+    assert!(circuit.verify(&received).unwrap());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_aggregated_proofs_flag_tampered_entry() {
+    use zkp_circuit::{AggregatedProof, BiometricEmbedding};
+    
+    let circuit = BiometricCircuit::new(4, 1000);
+    let reference = BiometricEmbedding::new(vec![1, 2, 3, 4]).unwrap();
+    let mut proofs: Vec<_> = [vec![1, 2, 3, 5], vec![2, 2, 3, 4], vec![1, 1, 3, 4]]
+        .into_iter()
+        .map(|probe| circuit.prove(&BiometricEmbedding::new(probe).unwrap(), &reference).unwrap())
+        .collect();
+    
+    // The second user submits a corrupted proof
+    proofs[1].proof[40] ^= 1;
+    
+    let aggregate = AggregatedProof::aggregate(&proofs).unwrap();
+    assert_eq!(aggregate.verify_aggregate(&circuit).unwrap(), vec![true, false, true]);
+    
+    // The aggregate digest pins the set of proofs
+    let mut reordered = aggregate.clone();
+    reordered.proofs.swap(0, 2);
+    assert!(reordered.verify_aggregate(&circuit).is_err());
+    
+    // Proofs for a different circuit cannot be aggregated together
+    let other = BiometricCircuit::new(4, 500).prove(&reference, &reference).unwrap();
+    assert!(AggregatedProof::aggregate(&[proofs[0].clone(), other]).is_err());
+    assert!(AggregatedProof::aggregate(&[]).is_err());
 }